@@ -0,0 +1,471 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+
+use super::rule::{AlertEvent, AlertRule, AlertState};
+use super::webhook::{Webhook, WebhookFormat};
+use crate::dataflow::dataflow_table::hex_color;
+use crate::metrics::telemetry::time_label;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    PANEL_BG = #ffffff
+    TOOLBAR_BG = #f1f5f9
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+
+    AlertText = <Label> {
+        height: Fit
+        draw_text: {
+            color: (TEXT_PRIMARY),
+            text_style: { font_size: 10.0 }
+            wrap: Ellipsis
+        }
+    }
+
+    AlertSectionRow = <View> {
+        width: Fill, height: Fit
+        padding: { left: 12, right: 12, top: 8, bottom: 2 }
+
+        section_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+            }
+        }
+    }
+
+    // A rule, with a button to delete it
+    AlertRuleRow = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        spacing: 8
+        align: { y: 0.5 }
+        padding: { left: 12, right: 12, top: 2, bottom: 2 }
+
+        rule_label = <AlertText> { width: Fill }
+        remove_button = <Button> {
+            width: Fit, height: 24
+            padding: { left: 8, right: 8 }
+            text: "Remove"
+            draw_text: { text_style: { font_size: 10.0 } }
+        }
+    }
+
+    // A webhook, with buttons to send it a test notification or delete it
+    AlertWebhookRow = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        spacing: 8
+        align: { y: 0.5 }
+        padding: { left: 12, right: 12, top: 2, bottom: 2 }
+
+        webhook_label = <AlertText> { width: Fill }
+        test_button = <Button> {
+            width: Fit, height: 24
+            padding: { left: 8, right: 8 }
+            text: "Test"
+            draw_text: { text_style: { font_size: 10.0 } }
+        }
+        remove_webhook_button = <Button> {
+            width: Fit, height: 24
+            padding: { left: 8, right: 8 }
+            text: "Remove"
+            draw_text: { text_style: { font_size: 10.0 } }
+        }
+    }
+
+    // An alert that fired or resolved
+    AlertEventRow = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        spacing: 8
+        padding: { left: 12, right: 12, top: 3, bottom: 3 }
+
+        time_label = <AlertText> { width: 90 }
+        state_label = <AlertText> { width: 70 }
+        message_label = <AlertText> { width: Fill }
+    }
+
+    // Alert rules, the alerts firing now and the ones before
+    pub AlertPanel = {{AlertPanel}} {
+        visible: false
+        width: Fill, height: 260
+        flow: Down
+        margin: { top: 12 }
+        show_bg: true
+        draw_bg: { color: (PANEL_BG) }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+            padding: { left: 12, right: 12, top: 8, bottom: 8 }
+            show_bg: true
+            draw_bg: { color: (TOOLBAR_BG) }
+
+            <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 13.0 }
+                }
+                text: "Alerts"
+            }
+            status_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 }
+                    wrap: Ellipsis
+                }
+                text: ""
+            }
+            rule_input = <TextInput> {
+                width: 280, height: 28
+                empty_text: "camera: memory > 2 GB for 60s"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            add_button = <Button> {
+                width: Fit, height: 28
+                padding: { left: 10, right: 10 }
+                text: "Add rule"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            close_button = <Button> {
+                width: Fit, height: 28
+                padding: { left: 10, right: 10 }
+                text: "Close"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        // New webhook: where to, in which shape and with what text
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+            padding: { left: 12, right: 12, top: 0, bottom: 8 }
+            show_bg: true
+            draw_bg: { color: (TOOLBAR_BG) }
+
+            webhook_url_input = <TextInput> {
+                width: Fill, height: 28
+                empty_text: "Webhook URL, e.g. https://hooks.slack.com/services/..."
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            webhook_format_dropdown = <DropDown> {
+                width: 90, height: 28
+                labels: ["JSON", "Slack"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            webhook_template_input = <TextInput> {
+                width: 220, height: 28
+                empty_text: "[{{state}}] {{message}}"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            add_webhook_button = <Button> {
+                width: Fit, height: 28
+                padding: { left: 10, right: 10 }
+                text: "Add webhook"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        alert_list = <PortalList> {
+            width: Fill, height: Fill
+            flow: Down
+
+            SectionRow = <AlertSectionRow> {}
+            RuleRow = <AlertRuleRow> {}
+            WebhookRow = <AlertWebhookRow> {}
+            EventRow = <AlertEventRow> {}
+        }
+    }
+}
+
+/// Alerts shown from the history, newest first
+pub const HISTORY_SHOWN: usize = 200;
+
+/// Requests from the alert panel the app has to act on
+#[derive(Clone, Debug, PartialEq, DefaultNone)]
+pub enum AlertPanelAction {
+    None,
+    /// Save a new rule
+    AddRule(AlertRule),
+    /// Delete the rule at this index
+    RemoveRule(usize),
+    AddWebhook(Webhook),
+    /// Delete the webhook at this index
+    RemoveWebhook(usize),
+    /// Send a webhook a test notification
+    TestWebhook(Webhook),
+    Close,
+}
+
+/// One line of the list
+#[derive(Debug, Clone)]
+enum PanelRow {
+    Section(String),
+    /// Index into the rules
+    Rule(usize),
+    Webhook(usize),
+    Firing(usize),
+    History(usize),
+}
+
+/// Rules and alerts. The app saves rule changes and feeds in the alerts the
+/// poller raises.
+#[derive(Live, LiveHook, Widget)]
+pub struct AlertPanel {
+    #[deref]
+    view: View,
+    #[rust]
+    rules: Vec<AlertRule>,
+    #[rust]
+    webhooks: Vec<Webhook>,
+    /// Format picked for the next webhook
+    #[rust]
+    format: WebhookFormat,
+    #[rust]
+    firing: Vec<AlertEvent>,
+    /// Newest first
+    #[rust]
+    history: Vec<AlertEvent>,
+    #[rust]
+    rows: Vec<PanelRow>,
+}
+
+impl Widget for AlertPanel {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_rows(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for AlertPanel {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        let uid = self.widget_uid();
+        let input = self.view.text_input(id!(rule_input));
+        if self.view.button(id!(add_button)).clicked(actions) || input.returned(actions).is_some() {
+            match AlertRule::parse(&input.text()) {
+                Ok(rule) => {
+                    input.set_text(cx, "");
+                    cx.widget_action(uid, &scope.path, AlertPanelAction::AddRule(rule));
+                }
+                Err(e) => self.set_status(cx, &e),
+            }
+        }
+
+        if let Some(index) = self.view.drop_down(id!(webhook_format_dropdown)).selected(actions) {
+            self.format = WebhookFormat::ALL.get(index).copied().unwrap_or_default();
+        }
+
+        let url_input = self.view.text_input(id!(webhook_url_input));
+        if self.view.button(id!(add_webhook_button)).clicked(actions) || url_input.returned(actions).is_some() {
+            let url = url_input.text().trim().to_string();
+            if url.starts_with("http://") || url.starts_with("https://") {
+                let template_input = self.view.text_input(id!(webhook_template_input));
+                let webhook = Webhook {
+                    url,
+                    format: self.format,
+                    template: template_input.text().trim().to_string(),
+                };
+                url_input.set_text(cx, "");
+                template_input.set_text(cx, "");
+                cx.widget_action(uid, &scope.path, AlertPanelAction::AddWebhook(webhook));
+            } else {
+                self.set_status(cx, "Webhook URLs start with http:// or https://");
+            }
+        }
+
+        for (item_id, item) in self.view.portal_list(id!(alert_list)).items_with_actions(actions) {
+            match self.rows.get(item_id).cloned() {
+                Some(PanelRow::Rule(index)) => {
+                    if item.button(id!(remove_button)).clicked(actions) {
+                        cx.widget_action(uid, &scope.path, AlertPanelAction::RemoveRule(index));
+                    }
+                }
+                Some(PanelRow::Webhook(index)) => {
+                    if item.button(id!(test_button)).clicked(actions) {
+                        let webhook = self.webhooks[index].clone();
+                        self.set_status(cx, &format!("Sending a test notification to {}...", webhook.url));
+                        cx.widget_action(uid, &scope.path, AlertPanelAction::TestWebhook(webhook));
+                    }
+                    if item.button(id!(remove_webhook_button)).clicked(actions) {
+                        cx.widget_action(uid, &scope.path, AlertPanelAction::RemoveWebhook(index));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if self.view.button(id!(close_button)).clicked(actions) {
+            self.view.set_visible(cx, false);
+            cx.widget_action(uid, &scope.path, AlertPanelAction::Close);
+        }
+    }
+}
+
+impl AlertPanel {
+    fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        list.set_item_range(cx, 0, self.rows.len());
+        while let Some(item_id) = list.next_visible_item(cx) {
+            let Some(row) = self.rows.get(item_id) else {
+                continue;
+            };
+            let item = match row {
+                PanelRow::Section(title) => {
+                    let item = list.item(cx, item_id, live_id!(SectionRow));
+                    item.label(id!(section_label)).set_text(cx, title);
+                    item
+                }
+                PanelRow::Rule(index) => {
+                    let item = list.item(cx, item_id, live_id!(RuleRow));
+                    item.label(id!(rule_label)).set_text(cx, &self.rules[*index].to_string());
+                    item
+                }
+                PanelRow::Webhook(index) => {
+                    let webhook = &self.webhooks[*index];
+                    let mut text = format!("{} {}", webhook.format.label(), webhook.url);
+                    if !webhook.template.is_empty() {
+                        text.push_str(&format!("  \"{}\"", webhook.template));
+                    }
+                    let item = list.item(cx, item_id, live_id!(WebhookRow));
+                    item.label(id!(webhook_label)).set_text(cx, &text);
+                    item
+                }
+                PanelRow::Firing(index) | PanelRow::History(index) => {
+                    let event = match row {
+                        PanelRow::Firing(_) => &self.firing[*index],
+                        _ => &self.history[*index],
+                    };
+                    let color = match event.state {
+                        AlertState::Firing => hex_color(0xdc2626),
+                        AlertState::Resolved => hex_color(0x16a34a),
+                    };
+                    let item = list.item(cx, item_id, live_id!(EventRow));
+                    item.label(id!(time_label)).set_text(cx, &time_label(event.timestamp, 86400));
+                    let state = item.label(id!(state_label));
+                    state.set_text(cx, event.state.label());
+                    state.apply_over(cx, live! { draw_text: { color: (color) } });
+                    item.label(id!(message_label)).set_text(cx, &event.message);
+                    item
+                }
+            };
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+
+    /// Lay out the list again and update the summary
+    fn update_rows(&mut self, cx: &mut Cx) {
+        let mut rows = vec![PanelRow::Section(if self.rules.is_empty() {
+            "RULES: none yet, type one above, e.g. status becomes Failed".to_string()
+        } else {
+            "RULES".to_string()
+        })];
+        rows.extend((0..self.rules.len()).map(PanelRow::Rule));
+        rows.push(PanelRow::Section(if self.webhooks.is_empty() {
+            "WEBHOOKS: none, add one below the rule box to be told about failures and alerts".to_string()
+        } else {
+            "WEBHOOKS".to_string()
+        }));
+        rows.extend((0..self.webhooks.len()).map(PanelRow::Webhook));
+        if !self.firing.is_empty() {
+            rows.push(PanelRow::Section("FIRING".to_string()));
+            rows.extend((0..self.firing.len()).map(PanelRow::Firing));
+        }
+        if !self.history.is_empty() {
+            rows.push(PanelRow::Section("HISTORY".to_string()));
+            rows.extend((0..self.history.len()).map(PanelRow::History));
+        }
+        self.rows = rows;
+
+        let status = match self.firing.len() {
+            0 => format!("{} rules, nothing firing", self.rules.len()),
+            firing => format!("{} rules, {} firing", self.rules.len(), firing),
+        };
+        self.set_status(cx, &status);
+        self.redraw(cx);
+    }
+
+    fn set_status(&mut self, cx: &mut Cx, text: &str) {
+        self.view.label(id!(status_label)).set_text(cx, text);
+    }
+}
+
+impl AlertPanelRef {
+    pub fn open(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.view.set_visible(cx, true);
+            inner.update_rows(cx);
+        }
+    }
+
+    pub fn set_rules(&self, cx: &mut Cx, rules: Vec<AlertRule>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.rules = rules;
+            inner.update_rows(cx);
+        }
+    }
+
+    pub fn set_webhooks(&self, cx: &mut Cx, webhooks: Vec<Webhook>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.webhooks = webhooks;
+            inner.update_rows(cx);
+        }
+    }
+
+    /// Show a message in the toolbar, e.g. how a test notification went
+    pub fn set_status(&self, cx: &mut Cx, text: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_status(cx, text);
+        }
+    }
+
+    /// Show the recorded history, newest first
+    pub fn set_history(&self, cx: &mut Cx, history: Vec<AlertEvent>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.history = history;
+            inner.history.truncate(HISTORY_SHOWN);
+            inner.update_rows(cx);
+        }
+    }
+
+    /// Add alerts that just fired or resolved, with all alerts firing now
+    pub fn add_events(&self, cx: &mut Cx, events: &[AlertEvent], firing: Vec<AlertEvent>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            for event in events {
+                inner.history.insert(0, event.clone());
+            }
+            inner.history.truncate(HISTORY_SHOWN);
+            inner.firing = firing;
+            inner.update_rows(cx);
+        }
+    }
+
+    /// Check if a rule or webhook was added, removed or tested, or the panel closed
+    pub fn action(&self, actions: &Actions) -> Option<AlertPanelAction> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .map(|action| action.cast())
+            .find(|action| *action != AlertPanelAction::None)
+    }
+}
@@ -0,0 +1,281 @@
+//! Evaluating alert rules against each refresh of the dataflow list.
+//!
+//! A condition has to hold on every refresh for the rule's duration before
+//! its alert fires, and the alert resolves on the first refresh it no longer
+//! holds. Conditions are tracked per rule and dataflow, so `memory > 2 GB`
+//! fires separately for each dataflow above 2 GB.
+
+use super::rule::{format_value, AlertCondition, AlertEvent, AlertRule, AlertState, Comparison};
+use crate::dataflow::DataflowInfo;
+use crate::metrics::series::MEMORY;
+use crate::metrics::NodeMetrics;
+use std::collections::{HashMap, HashSet};
+
+/// Rule text and dataflow UUID
+type AlertKey = (String, String);
+
+/// Which alerts are firing, and since when the others' conditions hold
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    /// First refresh each condition held on, until its alert fires
+    pending: HashMap<AlertKey, u64>,
+    firing: HashMap<AlertKey, AlertEvent>,
+    /// Dataflow names by UUID of the previous refresh, `None` before the first
+    known: Option<HashMap<String, String>>,
+}
+
+impl AlertEngine {
+    /// Check `rules` against a refresh, returning the alerts that fired or
+    /// resolved. `nodes` are the sampled node processes, for node rules.
+    pub fn evaluate(
+        &mut self,
+        rules: &[AlertRule],
+        dataflows: &[DataflowInfo],
+        nodes: &[NodeMetrics],
+        now: u64,
+    ) -> Vec<AlertEvent> {
+        let names: HashMap<String, String> = dataflows
+            .iter()
+            .map(|df| {
+                let name = if df.name.is_empty() { df.uuid_short() } else { df.name.clone() };
+                (df.uuid.clone(), name)
+            })
+            .collect();
+        let mut events = Vec::new();
+        let mut holding = HashSet::new();
+
+        for rule in rules {
+            let text = rule.to_string();
+            if rule.condition == AlertCondition::Disappears {
+                // Nothing to compare with on the first refresh
+                let Some(known) = &self.known else {
+                    continue;
+                };
+                for (uuid, name) in known {
+                    if !names.contains_key(uuid) && rule.applies_to(uuid, name) {
+                        events.push(event(now, AlertState::Firing, &text, uuid, name, "dataflow disappeared"));
+                    }
+                }
+                continue;
+            }
+
+            for df in dataflows.iter().filter(|df| rule.applies_to(&df.uuid, &df.name)) {
+                let name = &names[&df.uuid];
+                let key = (text.clone(), df.uuid.clone());
+                let (holds, observed) = check(rule, df, nodes);
+                if !holds {
+                    self.pending.remove(&key);
+                    if self.firing.remove(&key).is_some() {
+                        let message = format!("back to {}", observed);
+                        events.push(event(now, AlertState::Resolved, &text, &df.uuid, name, &message));
+                    }
+                    continue;
+                }
+                holding.insert(key.clone());
+                let since = *self.pending.entry(key.clone()).or_insert(now);
+                if now.saturating_sub(since) >= rule.for_secs && !self.firing.contains_key(&key) {
+                    let message = match rule.for_secs {
+                        0 => observed,
+                        secs => format!("{} for {}s", observed, secs),
+                    };
+                    let fired = event(now, AlertState::Firing, &text, &df.uuid, name, &message);
+                    self.firing.insert(key, fired.clone());
+                    events.push(fired);
+                }
+            }
+        }
+
+        // Dataflows that are gone and rules that were removed
+        let stale: Vec<AlertKey> = self.firing.keys().filter(|key| !holding.contains(*key)).cloned().collect();
+        for key in stale {
+            if let Some(fired) = self.firing.remove(&key) {
+                let message = if names.contains_key(&key.1) { "rule removed" } else { "dataflow is gone" };
+                events.push(event(now, AlertState::Resolved, &key.0, &key.1, &fired.dataflow_name, message));
+            }
+        }
+        self.pending.retain(|key, _| holding.contains(key));
+        self.known = Some(names);
+        events
+    }
+
+    /// Alerts firing now, oldest first
+    pub fn firing(&self) -> Vec<AlertEvent> {
+        let mut firing: Vec<AlertEvent> = self.firing.values().cloned().collect();
+        firing.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.message.cmp(&b.message)));
+        firing
+    }
+}
+
+/// Whether the rule's condition holds for a dataflow, and what was observed,
+/// e.g. `memory 2.31 GB above 2 GB`
+fn check(rule: &AlertRule, df: &DataflowInfo, nodes: &[NodeMetrics]) -> (bool, String) {
+    match &rule.condition {
+        AlertCondition::Metric {
+            metric,
+            node,
+            comparison,
+            threshold,
+        } => {
+            let subject = match node {
+                Some(node) => format!("{} of {}", metric, node),
+                None => metric.clone(),
+            };
+            let value = match node {
+                // Only running nodes have processes to sample
+                Some(node) => {
+                    let processes: Vec<&NodeMetrics> = nodes
+                        .iter()
+                        .filter(|m| m.dataflow == df.uuid && m.node == *node)
+                        .collect();
+                    (!processes.is_empty()).then(|| {
+                        processes
+                            .iter()
+                            .map(|m| if metric == MEMORY { m.memory_gb() } else { m.cpu })
+                            .sum()
+                    })
+                }
+                // Stopped dataflows report zero usage, which would trip `<` rules
+                None if df.is_running() => Some(if metric == MEMORY { df.memory } else { df.cpu }),
+                None => None,
+            };
+            let Some(value) = value else {
+                return (false, format!("{} not running", subject));
+            };
+            let (holds, side) = match comparison {
+                Comparison::Above => (value > *threshold, "above"),
+                Comparison::Below => (value < *threshold, "below"),
+            };
+            let observed = format!("{} {}", subject, format_value(metric, value));
+            if holds {
+                (true, format!("{} {} {}", observed, side, format_value(metric, *threshold)))
+            } else {
+                (false, observed)
+            }
+        }
+        AlertCondition::Status { status } => (df.status == *status, format!("status {}", df.status)),
+        AlertCondition::Disappears => (false, String::new()),
+    }
+}
+
+fn event(timestamp: u64, state: AlertState, rule: &str, uuid: &str, name: &str, message: &str) -> AlertEvent {
+    AlertEvent {
+        timestamp,
+        state,
+        rule: rule.to_string(),
+        dataflow: uuid.to_string(),
+        dataflow_name: name.to_string(),
+        message: format!("{}: {}", name, message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataflow::DataflowStatus;
+
+    fn dataflow(uuid: &str, name: &str, status: DataflowStatus, memory: f64) -> DataflowInfo {
+        DataflowInfo {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            status,
+            memory,
+            ..Default::default()
+        }
+    }
+
+    fn states(events: &[AlertEvent]) -> Vec<(AlertState, &str)> {
+        events.iter().map(|e| (e.state, e.message.as_str())).collect()
+    }
+
+    #[test]
+    fn test_metric_alert_waits_for_duration() {
+        let rules = vec![AlertRule::parse("memory > 2 GB for 60s").unwrap()];
+        let mut engine = AlertEngine::default();
+        let high = [dataflow("a", "camera", DataflowStatus::Running, 2.5)];
+
+        assert!(engine.evaluate(&rules, &high, &[], 100).is_empty());
+        assert!(engine.evaluate(&rules, &high, &[], 130).is_empty());
+        let fired = engine.evaluate(&rules, &high, &[], 160);
+        assert_eq!(
+            states(&fired),
+            [(AlertState::Firing, "camera: memory 2.50 GB above 2.00 GB for 60s")]
+        );
+        assert!(engine.evaluate(&rules, &high, &[], 165).is_empty());
+        assert_eq!(engine.firing().len(), 1);
+
+        let low = [dataflow("a", "camera", DataflowStatus::Running, 1.5)];
+        let resolved = engine.evaluate(&rules, &low, &[], 170);
+        assert_eq!(states(&resolved), [(AlertState::Resolved, "camera: back to memory 1.50 GB")]);
+        assert!(engine.firing().is_empty());
+
+        // A dip starts the duration over
+        engine.evaluate(&rules, &high, &[], 200);
+        engine.evaluate(&rules, &low, &[], 230);
+        assert!(engine.evaluate(&rules, &high, &[], 265).is_empty());
+    }
+
+    #[test]
+    fn test_status_and_node_alerts() {
+        let rules = vec![
+            AlertRule::parse("status becomes failed").unwrap(),
+            AlertRule::parse("camera: cpu of detector > 100%").unwrap(),
+        ];
+        let mut engine = AlertEngine::default();
+        let flows = [
+            dataflow("a", "camera", DataflowStatus::Running, 1.0),
+            dataflow("b", "lidar", DataflowStatus::Failed, 0.0),
+        ];
+        let nodes = [NodeMetrics {
+            dataflow: "a".to_string(),
+            node: "detector".to_string(),
+            pids: vec![42],
+            cpu: 150.0,
+            rss_bytes: 0,
+            threads: 4,
+            fds: 12,
+        }];
+        let fired = engine.evaluate(&rules, &flows, &nodes, 10);
+        assert_eq!(
+            states(&fired),
+            [
+                (AlertState::Firing, "lidar: status Failed"),
+                (AlertState::Firing, "camera: cpu of detector 150.0% above 100.0%"),
+            ]
+        );
+
+        // Node gone, alert resolved
+        let resolved = engine.evaluate(&rules, &flows, &[], 15);
+        assert_eq!(states(&resolved), [(AlertState::Resolved, "camera: back to cpu of detector not running")]);
+    }
+
+    #[test]
+    fn test_disappearing_dataflow() {
+        let rules = vec![
+            AlertRule::parse("disappears").unwrap(),
+            AlertRule::parse("status becomes running").unwrap(),
+        ];
+        let mut engine = AlertEngine::default();
+        let flows = [dataflow("a", "camera", DataflowStatus::Running, 1.0)];
+        assert_eq!(engine.evaluate(&rules, &flows, &[], 10).len(), 1);
+
+        let events = engine.evaluate(&rules, &[], &[], 15);
+        assert_eq!(
+            states(&events),
+            [
+                (AlertState::Firing, "camera: dataflow disappeared"),
+                (AlertState::Resolved, "camera: dataflow is gone"),
+            ]
+        );
+        assert!(engine.evaluate(&rules, &[], &[], 20).is_empty());
+    }
+
+    #[test]
+    fn test_removed_rule_resolves() {
+        let rules = vec![AlertRule::parse("status is running").unwrap()];
+        let mut engine = AlertEngine::default();
+        let flows = [dataflow("a", "", DataflowStatus::Running, 1.0)];
+        engine.evaluate(&rules, &flows, &[], 10);
+        let events = engine.evaluate(&[], &flows, &[], 15);
+        assert_eq!(states(&events), [(AlertState::Resolved, "a: rule removed")]);
+    }
+}
@@ -0,0 +1,114 @@
+//! Every alert that fired or resolved, one JSON object per line in
+//! `alerts.jsonl` next to the settings.
+
+use super::rule::AlertEvent;
+use crate::settings::studio_dir;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Size the history may grow to before the oldest half is dropped
+pub const MAX_HISTORY_BYTES: u64 = 1024 * 1024;
+
+/// Path of the alert history file
+pub fn alerts_path() -> PathBuf {
+    studio_dir().join("alerts.jsonl")
+}
+
+/// Append events to the history file at `path`, creating parent directories
+pub fn append_to(path: &Path, events: &[AlertEvent]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let mut lines = String::new();
+    for event in events {
+        let json = serde_json::to_string(event).map_err(|e| format!("Failed to serialize alert: {}", e))?;
+        lines.push_str(&json);
+        lines.push('\n');
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    file.write_all(lines.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    if file.metadata().map(|m| m.len()).unwrap_or(0) > MAX_HISTORY_BYTES {
+        trim(path)?;
+    }
+    Ok(())
+}
+
+/// Keep the newest half of the lines
+fn trim(path: &Path) -> Result<(), String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut kept = lines[lines.len() / 2..].join("\n");
+    kept.push('\n');
+    std::fs::write(path, kept).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// The last `limit` events of the history file at `path`, newest first.
+/// Unreadable lines are skipped.
+pub fn load_from(path: &Path, limit: usize) -> Vec<AlertEvent> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect()
+}
+
+/// The last `limit` events of the history, newest first
+pub fn recent(limit: usize) -> Vec<AlertEvent> {
+    load_from(&alerts_path(), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::AlertState;
+
+    fn alert(timestamp: u64, state: AlertState) -> AlertEvent {
+        AlertEvent {
+            timestamp,
+            state,
+            rule: "status becomes Failed".to_string(),
+            dataflow: "019be55a-8468-7d4b-9e95-db7938985bc7".to_string(),
+            dataflow_name: "camera".to_string(),
+            message: "camera: status Failed".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_history_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("studio").join("alerts.jsonl");
+        assert!(load_from(&path, 10).is_empty());
+
+        append_to(&path, &[alert(1, AlertState::Firing)]).unwrap();
+        append_to(&path, &[alert(2, AlertState::Resolved), alert(3, AlertState::Firing)]).unwrap();
+        let mut content = std::fs::read_to_string(&path).unwrap();
+        content.push_str("not json\n");
+        std::fs::write(&path, content).unwrap();
+
+        let recent = load_from(&path, 2);
+        assert_eq!(recent, vec![alert(3, AlertState::Firing), alert(2, AlertState::Resolved)]);
+    }
+
+    #[test]
+    fn test_history_trimmed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("alerts.jsonl");
+        let events: Vec<AlertEvent> = (0..8000).map(|i| alert(i, AlertState::Firing)).collect();
+        append_to(&path, &events).unwrap();
+
+        assert!(std::fs::metadata(&path).unwrap().len() <= MAX_HISTORY_BYTES);
+        let kept = load_from(&path, usize::MAX);
+        assert_eq!(kept.first().unwrap().timestamp, 7999);
+        assert_eq!(kept.len(), 4000);
+    }
+}
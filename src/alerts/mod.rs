@@ -0,0 +1,22 @@
+//! Alerts on dataflow usage and status.
+//!
+//! Rules are kept in the settings and checked by the poller on every
+//! refresh. Alerts that fire or resolve go to the panel and `alerts.jsonl`,
+//! and with failed dataflows to the configured webhooks.
+
+pub mod alert_panel;
+pub mod engine;
+pub mod history;
+pub mod rule;
+pub mod webhook;
+
+pub use alert_panel::{AlertPanel, AlertPanelAction, AlertPanelRef, AlertPanelWidgetRefExt};
+pub use engine::AlertEngine;
+pub use rule::{AlertCondition, AlertEvent, AlertRule, AlertState, Comparison};
+pub use webhook::{Notification, Webhook, WebhookFormat};
+
+use makepad_widgets::*;
+
+pub fn live_design(cx: &mut Cx) {
+    alert_panel::live_design(cx);
+}
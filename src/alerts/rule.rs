@@ -0,0 +1,336 @@
+//! Alert rules and the alerts they raise.
+//!
+//! Rules are typed the way they read: `memory > 2 GB for 60s`,
+//! `cpu of detector > 150% for 30s`, `status becomes Failed` or
+//! `disappears`, optionally for one dataflow only: `camera: memory > 2 GB`.
+//! They are kept in the settings in structured form and shown as text again.
+
+use crate::dataflow::model::{format_cpu, format_memory};
+use crate::dataflow::DataflowStatus;
+use crate::metrics::series::{CPU, MEMORY};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Which side of the threshold raises the alert
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+/// What a rule watches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// CPU in percent or memory in GB, of the dataflow or one of its nodes
+    Metric {
+        metric: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        node: Option<String>,
+        comparison: Comparison,
+        threshold: f64,
+    },
+    /// The dataflow is in this status
+    Status { status: DataflowStatus },
+    /// The dataflow was listed before and is not anymore
+    Disappears,
+}
+
+/// A condition, how long it has to hold and which dataflows it applies to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    /// Name or UUID prefix of the only dataflow checked, all when `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dataflow: Option<String>,
+    pub condition: AlertCondition,
+    /// Seconds the condition has to hold before the alert fires
+    #[serde(default)]
+    pub for_secs: u64,
+}
+
+impl AlertRule {
+    /// Parse a rule as typed, e.g. `camera: memory > 2 GB for 60s`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (dataflow, rest) = match text.split_once(':') {
+            Some((dataflow, rest)) if !dataflow.trim().is_empty() && !dataflow.trim().contains(' ') => {
+                (Some(dataflow.trim().to_string()), rest)
+            }
+            _ => (None, text),
+        };
+        let spaced = rest.replace('>', " > ").replace('<', " < ");
+        // Keywords and units in any case, node names as typed
+        let mut words: Vec<&str> = spaced.split_whitespace().collect();
+        let mut lower: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+        if words.is_empty() {
+            return Err("Type a rule, e.g. memory > 2 GB for 60s".to_string());
+        }
+
+        let mut for_secs = 0;
+        if let Some(at) = lower.iter().position(|word| word == "for") {
+            let duration = lower[at + 1..].concat();
+            for_secs = parse_duration(&duration).ok_or_else(|| format!("Invalid duration: {}", duration))?;
+            words.truncate(at);
+            lower.truncate(at);
+        }
+        if words.is_empty() {
+            return Err("Missing metric, e.g. memory > 2 GB for 60s".to_string());
+        }
+
+        let condition = match lower.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+            ["disappears"] => {
+                if for_secs > 0 {
+                    return Err("Disappearing happens at once, it takes no duration".to_string());
+                }
+                AlertCondition::Disappears
+            }
+            ["status", "becomes" | "is" | "=", status] => {
+                let status = DataflowStatus::parse(status);
+                if status == DataflowStatus::Unknown {
+                    return Err(format!("Unknown status: {}", words[2]));
+                }
+                AlertCondition::Status { status }
+            }
+            [metric, ref rest @ ..] => {
+                let metric = match metric {
+                    "cpu" => CPU,
+                    "memory" | "mem" => MEMORY,
+                    _ => {
+                        return Err(format!(
+                            "Unknown rule: {}. Rules watch cpu, memory, status or disappears",
+                            lower.join(" ")
+                        ))
+                    }
+                };
+                let (node, rest) = match rest {
+                    ["of", _, rest @ ..] => (Some(words[2].to_string()), rest),
+                    _ => (None, rest),
+                };
+                let (comparison, value) = match rest {
+                    [">", value @ ..] => (Comparison::Above, value.concat()),
+                    ["<", value @ ..] => (Comparison::Below, value.concat()),
+                    _ => return Err(format!("Expected > or < after {}", metric)),
+                };
+                AlertCondition::Metric {
+                    metric: metric.to_string(),
+                    node,
+                    comparison,
+                    threshold: parse_threshold(metric, &value)
+                        .ok_or_else(|| format!("Invalid threshold: {}", value))?,
+                }
+            }
+            [] => unreachable!(),
+        };
+        Ok(Self {
+            dataflow,
+            condition,
+            for_secs,
+        })
+    }
+
+    /// Whether the rule checks the dataflow with this UUID and name
+    pub fn applies_to(&self, uuid: &str, name: &str) -> bool {
+        self.dataflow
+            .as_ref()
+            .is_none_or(|dataflow| uuid.starts_with(dataflow.as_str()) || name == dataflow)
+    }
+}
+
+/// `60`, `60s`, `5m` or `1h` in seconds
+fn parse_duration(text: &str) -> Option<u64> {
+    let digits = text.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let value: u64 = digits.parse().ok()?;
+    let scale = match &text[digits.len()..] {
+        "" | "s" | "sec" | "secs" | "seconds" => 1,
+        "m" | "min" | "mins" | "minutes" => 60,
+        "h" | "hour" | "hours" => 3600,
+        _ => return None,
+    };
+    value.checked_mul(scale)
+}
+
+/// A CPU threshold in percent, or a memory threshold in GB from `512mb`,
+/// `2gb` or `2` (GB)
+fn parse_threshold(metric: &str, text: &str) -> Option<f64> {
+    let number = text.trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%');
+    let value: f64 = number.parse().ok()?;
+    let scale = match (metric, &text[number.len()..]) {
+        (CPU, "" | "%") => 1.0,
+        (MEMORY, "" | "gb" | "g") => 1.0,
+        (MEMORY, "mb" | "m") => 1.0 / 1024.0,
+        (MEMORY, "kb" | "k") => 1.0 / (1024.0 * 1024.0),
+        _ => return None,
+    };
+    (value >= 0.0).then_some(value * scale)
+}
+
+/// Format a value of a metric for messages
+pub fn format_value(metric: &str, value: f64) -> String {
+    match metric {
+        MEMORY => format_memory(value),
+        _ => format_cpu(value),
+    }
+}
+
+impl fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(dataflow) = &self.dataflow {
+            write!(f, "{}: ", dataflow)?;
+        }
+        match &self.condition {
+            AlertCondition::Metric {
+                metric,
+                node,
+                comparison,
+                threshold,
+            } => {
+                write!(f, "{}", metric)?;
+                if let Some(node) = node {
+                    write!(f, " of {}", node)?;
+                }
+                let sign = match comparison {
+                    Comparison::Above => ">",
+                    Comparison::Below => "<",
+                };
+                // Thresholds as typed, not rounded like measured values
+                let threshold = match metric.as_str() {
+                    MEMORY if *threshold < 1.0 => format!("{} MB", threshold * 1024.0),
+                    MEMORY => format!("{} GB", threshold),
+                    _ => format!("{}%", threshold),
+                };
+                write!(f, " {} {}", sign, threshold)?;
+            }
+            AlertCondition::Status { status } => write!(f, "status becomes {}", status)?,
+            AlertCondition::Disappears => write!(f, "disappears")?,
+        }
+        if self.for_secs > 0 {
+            write!(f, " for {}s", self.for_secs)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether an alert started or ended
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    Firing,
+    Resolved,
+}
+
+impl AlertState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlertState::Firing => "FIRING",
+            AlertState::Resolved => "RESOLVED",
+        }
+    }
+}
+
+/// An alert firing or resolving, as shown and kept in the history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertEvent {
+    /// Unix seconds
+    pub timestamp: u64,
+    pub state: AlertState,
+    /// The rule, as text
+    pub rule: String,
+    /// UUID of the dataflow
+    pub dataflow: String,
+    /// Name of the dataflow, or its short UUID
+    pub dataflow_name: String,
+    /// What happened, e.g. `camera: memory 2.31 GB above 2 GB for 60s`
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let rule = AlertRule::parse("memory > 2 GB for 60s").unwrap();
+        assert_eq!(
+            rule,
+            AlertRule {
+                dataflow: None,
+                condition: AlertCondition::Metric {
+                    metric: MEMORY.to_string(),
+                    node: None,
+                    comparison: Comparison::Above,
+                    threshold: 2.0,
+                },
+                for_secs: 60,
+            }
+        );
+        assert_eq!(rule.to_string(), "memory > 2 GB for 60s");
+
+        let rule = AlertRule::parse("camera: CPU of detector>150% for 5m").unwrap();
+        assert_eq!(rule.dataflow.as_deref(), Some("camera"));
+        assert_eq!(rule.for_secs, 300);
+        assert_eq!(rule.to_string(), "camera: cpu of detector > 150% for 300s");
+
+        let rule = AlertRule::parse("mem < 512mb").unwrap();
+        assert_eq!(rule.to_string(), "memory < 512 MB");
+        assert_eq!(
+            AlertRule::parse("Status becomes failed").unwrap().condition,
+            AlertCondition::Status {
+                status: DataflowStatus::Failed
+            }
+        );
+        assert_eq!(AlertRule::parse("disappears").unwrap().to_string(), "disappears");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(AlertRule::parse("memory > lots").unwrap_err(), "Invalid threshold: lots");
+        assert_eq!(AlertRule::parse("cpu > 50 for ever").unwrap_err(), "Invalid duration: ever");
+        assert_eq!(AlertRule::parse("status becomes sleepy").unwrap_err(), "Unknown status: sleepy");
+        assert!(AlertRule::parse("disk > 90%").unwrap_err().starts_with("Unknown rule"));
+        assert!(AlertRule::parse("disappears for 10s").is_err());
+        assert!(AlertRule::parse("cpu = 50").is_err());
+        assert!(AlertRule::parse("  ").is_err());
+        assert!(AlertRule::parse("for 5s").unwrap_err().starts_with("Missing metric"));
+        assert!(AlertRule::parse("camera: for 5s").unwrap_err().starts_with("Missing metric"));
+        assert_eq!(
+            AlertRule::parse("cpu > 50 for 18446744073709551615h").unwrap_err(),
+            "Invalid duration: 18446744073709551615h"
+        );
+    }
+
+    #[test]
+    fn test_node_names_keep_their_case() {
+        let rule = AlertRule::parse("CPU OF Detector > 50% FOR 30S").unwrap();
+        assert_eq!(
+            rule.condition,
+            AlertCondition::Metric {
+                metric: CPU.to_string(),
+                node: Some("Detector".to_string()),
+                comparison: Comparison::Above,
+                threshold: 50.0,
+            }
+        );
+        assert_eq!(rule.for_secs, 30);
+        assert_eq!(rule.to_string(), "cpu of Detector > 50% for 30s");
+    }
+
+    #[test]
+    fn test_applies_to() {
+        let all = AlertRule::parse("disappears").unwrap();
+        assert!(all.applies_to("019be55a-8468", "camera"));
+        let camera = AlertRule::parse("camera: disappears").unwrap();
+        assert!(camera.applies_to("019be55a-8468", "camera"));
+        assert!(!camera.applies_to("019be55a-8468", "lidar"));
+        let by_id = AlertRule::parse("019be55a: disappears").unwrap();
+        assert!(by_id.applies_to("019be55a-8468", ""));
+    }
+
+    #[test]
+    fn test_rule_roundtrip() {
+        let rule = AlertRule::parse("camera: cpu of detector > 150% for 30s").unwrap();
+        let json = serde_json::to_string(&rule).unwrap();
+        assert_eq!(serde_json::from_str::<AlertRule>(&json).unwrap(), rule);
+        assert!(json.contains(r#""kind":"metric""#));
+    }
+}
@@ -0,0 +1,391 @@
+//! Outbound webhooks for failed dataflows and alerts.
+//!
+//! Each webhook gets a JSON POST per notification, either a generic object
+//! with every field or a Slack-compatible `{"text": ...}` payload. The text
+//! comes from a template with `{{placeholders}}`. Failed deliveries are
+//! retried with backoff on network errors, 5xx and 429 answers. Deliveries run
+//! in order on their own thread, so a slow endpoint never holds up the poller.
+
+use super::rule::{AlertEvent, AlertState};
+use crate::dataflow::model::{format_utc, unix_now};
+use crate::dataflow::{DataflowInfo, DataflowStatus};
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Notifications and the webhooks to send them to
+type Delivery = (Vec<Webhook>, Vec<Notification>);
+
+// Delivery queue of the webhook thread, set on first use
+static SENDER: Mutex<Option<Sender<Delivery>>> = Mutex::new(None);
+
+/// Text sent when a webhook has no template of its own
+pub const DEFAULT_TEMPLATE: &str = "[{{state}}] {{message}}";
+
+/// Time one delivery attempt may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Payload shape a webhook expects
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// An object with every field and the rendered `text`
+    #[default]
+    Json,
+    /// `{"text": ...}`, as Slack incoming webhooks and compatible chats take
+    Slack,
+}
+
+impl WebhookFormat {
+    /// All formats, in dropdown order
+    pub const ALL: [WebhookFormat; 2] = [WebhookFormat::Json, WebhookFormat::Slack];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WebhookFormat::Json => "JSON",
+            WebhookFormat::Slack => "Slack",
+        }
+    }
+}
+
+/// An endpoint notified of failed dataflows and alerts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Text of each notification, [`DEFAULT_TEMPLATE`] when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub template: String,
+}
+
+impl Webhook {
+    /// The body posted for a notification
+    pub fn payload(&self, notification: &Notification) -> serde_json::Value {
+        let template = if self.template.trim().is_empty() {
+            DEFAULT_TEMPLATE
+        } else {
+            self.template.as_str()
+        };
+        let text = render_template(template, notification);
+        match self.format {
+            WebhookFormat::Slack => serde_json::json!({ "text": text }),
+            WebhookFormat::Json => {
+                let mut payload: serde_json::Map<String, serde_json::Value> = notification
+                    .fields()
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value.into()))
+                    .collect();
+                payload.insert("timestamp".to_string(), notification.timestamp().into());
+                payload.insert("text".to_string(), text.into());
+                serde_json::Value::Object(payload)
+            }
+        }
+    }
+}
+
+/// Something webhooks are told about
+#[derive(Debug, Clone, PartialEq)]
+pub enum Notification {
+    /// A dataflow went to `Failed` since the previous refresh
+    Failed {
+        timestamp: u64,
+        dataflow: String,
+        dataflow_name: String,
+        reason: Option<String>,
+    },
+    /// An alert fired or resolved
+    Alert(AlertEvent),
+}
+
+impl Notification {
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            Notification::Failed { timestamp, .. } => *timestamp,
+            Notification::Alert(event) => event.timestamp,
+        }
+    }
+
+    /// Template placeholders and their values
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let (event, state, dataflow, name, rule, message) = match self {
+            Notification::Failed {
+                dataflow,
+                dataflow_name,
+                reason,
+                ..
+            } => {
+                let message = match reason {
+                    Some(reason) => format!("{}: failed: {}", dataflow_name, reason),
+                    None => format!("{}: failed", dataflow_name),
+                };
+                ("dataflow_failed", "FAILED", dataflow, dataflow_name, "", message)
+            }
+            Notification::Alert(alert) => {
+                let event = match alert.state {
+                    AlertState::Firing => "alert_firing",
+                    AlertState::Resolved => "alert_resolved",
+                };
+                let (dataflow, name) = (&alert.dataflow, &alert.dataflow_name);
+                (event, alert.state.label(), dataflow, name, alert.rule.as_str(), alert.message.clone())
+            }
+        };
+        vec![
+            ("event", event.to_string()),
+            ("state", state.to_string()),
+            ("dataflow", name.clone()),
+            ("dataflow_id", dataflow.clone()),
+            ("rule", rule.to_string()),
+            ("message", message),
+            ("time", format_utc(self.timestamp())),
+        ]
+    }
+}
+
+/// Fill `{{event}}`, `{{state}}`, `{{dataflow}}`, `{{dataflow_id}}`,
+/// `{{rule}}`, `{{message}}` and `{{time}}`. Unknown placeholders are kept.
+pub fn render_template(template: &str, notification: &Notification) -> String {
+    let mut text = template.to_string();
+    for (key, value) in notification.fields() {
+        text = text.replace(&format!("{{{{{}}}}}", key), &value);
+    }
+    text
+}
+
+/// Dataflows that failed since the previous refresh, including ones that
+/// showed up failed
+pub fn failures(previous: &[DataflowInfo], current: &[DataflowInfo], now: u64) -> Vec<Notification> {
+    current
+        .iter()
+        .filter(|df| df.status == DataflowStatus::Failed)
+        .filter(|df| {
+            previous
+                .iter()
+                .find(|p| p.uuid == df.uuid)
+                .is_none_or(|p| p.status != DataflowStatus::Failed)
+        })
+        .map(|df| Notification::Failed {
+            timestamp: now,
+            dataflow: df.uuid.clone(),
+            dataflow_name: if df.name.is_empty() { df.uuid_short() } else { df.name.clone() },
+            reason: df.exit_reason.clone(),
+        })
+        .collect()
+}
+
+/// How often and how patiently a delivery is retried
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Retry {
+    /// Attempts in total, including the first
+    pub attempts: u32,
+    /// Wait before the second attempt, doubled before each further one
+    pub backoff: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Post a notification, retrying failures that may pass. Returns the number
+/// of attempts it took.
+pub async fn deliver(
+    client: &reqwest::Client,
+    webhook: &Webhook,
+    notification: &Notification,
+    retry: Retry,
+) -> Result<u32, String> {
+    let payload = webhook.payload(notification);
+    let mut backoff = retry.backoff;
+    let mut attempt = 1;
+    loop {
+        let result = client
+            .post(&webhook.url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&payload)
+            .send()
+            .await;
+        let error = match result {
+            Ok(response) if response.status().is_success() => return Ok(attempt),
+            Ok(response) => {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                let error = format!("{} answered {} {}", webhook.url, status, body.trim());
+                // Anything else would fail the same way again
+                if !status.is_server_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    return Err(error);
+                }
+                error
+            }
+            Err(e) => format!("Cannot reach {}: {}", webhook.url, e),
+        };
+        if attempt >= retry.attempts {
+            return Err(format!("{} (gave up after {} attempts)", error.trim(), attempt));
+        }
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+        attempt += 1;
+    }
+}
+
+/// Queue notifications for every webhook, delivered on the webhook thread
+pub fn notify(webhooks: &[Webhook], notifications: Vec<Notification>) {
+    if webhooks.is_empty() || notifications.is_empty() {
+        return;
+    }
+    let mut sender = SENDER.lock().unwrap();
+    let sender = sender.get_or_insert_with(|| {
+        let (tx, rx) = channel::<Delivery>();
+        std::thread::spawn(move || {
+            let client = reqwest::Client::new();
+            for (webhooks, notifications) in rx {
+                block_on(async {
+                    for notification in &notifications {
+                        for webhook in &webhooks {
+                            if let Err(e) = deliver(&client, webhook, notification, Retry::default()).await {
+                                eprintln!("[Webhooks] {}", e);
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        tx
+    });
+    let _ = sender.send((webhooks.to_vec(), notifications));
+}
+
+/// A made-up failing alert, sent by the test button
+pub fn test_notification() -> Notification {
+    Notification::Alert(AlertEvent {
+        timestamp: unix_now(),
+        state: AlertState::Firing,
+        rule: "test".to_string(),
+        dataflow: "00000000-0000-0000-0000-000000000000".to_string(),
+        dataflow_name: "test".to_string(),
+        message: "test: notification from Dora Studio".to_string(),
+    })
+}
+
+/// Outcome of a test notification
+#[derive(Debug)]
+pub struct WebhookTested(pub Result<String, String>);
+
+/// Send [`test_notification`] to one webhook on a background thread, once,
+/// and hand the outcome to `done`
+pub fn test_in_background(webhook: Webhook, done: impl FnOnce(WebhookTested) + Send + 'static) {
+    std::thread::spawn(move || {
+        let retry = Retry {
+            attempts: 1,
+            ..Retry::default()
+        };
+        let result = block_on(async {
+            deliver(&reqwest::Client::new(), &webhook, &test_notification(), retry).await
+        });
+        done(WebhookTested(result.map(|_| format!("Test notification delivered to {}", webhook.url))));
+    });
+}
+
+fn block_on<T>(future: impl std::future::Future<Output = T>) -> T {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to create Tokio runtime")
+        .block_on(future)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert() -> Notification {
+        Notification::Alert(AlertEvent {
+            timestamp: 1_700_000_000,
+            state: AlertState::Firing,
+            rule: "memory > 2 GB for 60s".to_string(),
+            dataflow: "019be55a-8468-7d4b-9e95-db7938985bc7".to_string(),
+            dataflow_name: "camera".to_string(),
+            message: "camera: memory 2.50 GB above 2.00 GB for 60s".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_render_template() {
+        assert_eq!(
+            render_template(DEFAULT_TEMPLATE, &alert()),
+            "[FIRING] camera: memory 2.50 GB above 2.00 GB for 60s"
+        );
+        assert_eq!(
+            render_template("{{dataflow}} ({{dataflow_id}}) at {{time}}: {{rule}} {{unknown}}", &alert()),
+            "camera (019be55a-8468-7d4b-9e95-db7938985bc7) at 2023-11-14 22:13:20 UTC: memory > 2 GB for 60s {{unknown}}"
+        );
+    }
+
+    #[test]
+    fn test_payloads() {
+        let slack = Webhook {
+            url: "http://localhost/hook".to_string(),
+            format: WebhookFormat::Slack,
+            template: "{{event}}: {{message}}".to_string(),
+        };
+        assert_eq!(
+            slack.payload(&alert()),
+            serde_json::json!({ "text": "alert_firing: camera: memory 2.50 GB above 2.00 GB for 60s" })
+        );
+
+        let json = Webhook {
+            format: WebhookFormat::Json,
+            template: String::new(),
+            ..slack
+        };
+        let payload = json.payload(&alert());
+        assert_eq!(payload["event"], "alert_firing");
+        assert_eq!(payload["dataflow"], "camera");
+        assert_eq!(payload["timestamp"], 1_700_000_000);
+        assert_eq!(payload["text"], "[FIRING] camera: memory 2.50 GB above 2.00 GB for 60s");
+    }
+
+    #[test]
+    fn test_failures_since_previous_refresh() {
+        let flow = |uuid: &str, status| DataflowInfo {
+            uuid: uuid.to_string(),
+            name: "camera".to_string(),
+            status,
+            exit_reason: Some("node detector exited with 1".to_string()),
+            ..Default::default()
+        };
+        let previous = [flow("a", DataflowStatus::Running), flow("b", DataflowStatus::Failed)];
+        let current = [
+            flow("a", DataflowStatus::Failed),
+            flow("b", DataflowStatus::Failed),
+            flow("c", DataflowStatus::Failed),
+        ];
+        let failed = failures(&previous, &current, 10);
+        let ids: Vec<String> = failed
+            .iter()
+            .map(|n| n.fields().into_iter().find(|(key, _)| *key == "dataflow_id").unwrap().1)
+            .collect();
+        assert_eq!(ids, ["a", "c"]);
+        assert_eq!(
+            render_template("{{message}}", &failed[0]),
+            "camera: failed: node detector exited with 1"
+        );
+    }
+
+    #[test]
+    fn test_webhook_roundtrip() {
+        let webhook: Webhook = serde_json::from_str(r#"{"url": "http://localhost/hook"}"#).unwrap();
+        assert_eq!(webhook.format, WebhookFormat::Json);
+        assert!(webhook.template.is_empty());
+        let slack = Webhook {
+            format: WebhookFormat::Slack,
+            ..webhook
+        };
+        assert_eq!(serde_json::from_str::<Webhook>(&serde_json::to_string(&slack).unwrap()).unwrap(), slack);
+    }
+}
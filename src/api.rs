@@ -37,7 +37,7 @@ Rules:
 - Use bullet points for lists
 - Skip pleasantries
 
-You have tools for: dora dataflows (list/start/stop/destroy), file operations (read/write), shell commands, directory browsing.
+You have tools for: dora dataflows (list/start/stop/destroy/restart), bringing dora up/down, file operations (read/write), shell commands, directory browsing.
 
 Use tools proactively. Show results briefly."#;
 
@@ -1,22 +1,41 @@
 use makepad_widgets::*;
-use crate::dataflow::{DataflowInfo, DataflowTableWidgetRefExt};
-use crate::tools::execute_tool;
-
-// Auto-refresh interval in seconds
-const AUTO_REFRESH_INTERVAL: f64 = 5.0;
+use crate::alerts::webhook::{self, WebhookTested};
+use crate::alerts::{alert_panel, history, AlertPanelAction, AlertPanelWidgetRefExt, AlertRule, AlertState, Webhook};
+use crate::dataflow::descriptor::{self, NodeSpec};
+use crate::dataflow::table_view::TableView;
+use crate::dataflow::{DataflowDetailWidgetRefExt, DataflowTableWidgetRefExt, DetailAction, StartDialogWidgetRefExt};
+use crate::client::ConnectionHealth;
+use crate::launches;
+use crate::logs::{FollowReady, LogDock, LogFollower, LogViewerAction, LogViewerWidgetRefExt};
+use crate::metrics::store::{self, TelemetryLoaded};
+use crate::metrics::{
+    NodeTopAction, NodeTopWidgetRefExt, TelemetryAction, TelemetryDashboardWidgetRefExt, TelemetryRequest,
+};
+use crate::poller::{self, PollerEvent, PollerRequest};
+use crate::settings;
+use crate::supervisor;
+use crate::watcher;
 
 live_design! {
     use link::theme::*;
     use link::shaders::*;
     use link::widgets::*;
 
+    use crate::alerts::alert_panel::AlertPanel;
     use crate::chat::chat_screen::ChatScreen;
     use crate::dataflow::dataflow_table::DataflowTable;
+    use crate::dataflow::dataflow_detail::DataflowDetail;
+    use crate::dataflow::start_dialog::StartDialog;
+    use crate::logs::log_viewer::LogViewer;
+    use crate::metrics::dashboard::TelemetryDashboard;
+    use crate::metrics::node_top::NodeTop;
 
     // Colors
     SIDEBAR_BG = #1e293b
     MAIN_BG = #f8fafc
     DIVIDER_COLOR = #e2e8f0
+    BANNER_BG = #fef3c7
+    BANNER_TEXT = #92400e
 
     App = {{App}} {
         ui: <Root> {
@@ -28,14 +47,61 @@ live_design! {
                     show_bg: true
                     draw_bg: { color: (MAIN_BG) }
 
-                    // Top panel - Dataflow Table
-                    <View> {
+                    // Top panel - Dataflow Table, with the log viewer docked right or below
+                    workspace = <View> {
                         width: Fill, height: Fill
-                        flow: Down
-                        align: { x: 0.0, y: 0.0 }
-                        padding: { top: 0, left: 16, right: 16, bottom: 16 }
-
-                        dataflow_table = <DataflowTable> {}
+                        flow: Right
+
+                        <View> {
+                            width: Fill, height: Fill
+                            flow: Down
+                            align: { x: 0.0, y: 0.0 }
+                            padding: { top: 0, left: 16, right: 16, bottom: 16 }
+
+                            // Shown when the coordinator or daemon is not reachable
+                            connection_banner = <RoundedView> {
+                                visible: false
+                                width: Fill, height: Fit
+                                flow: Right
+                                align: { y: 0.5 }
+                                spacing: 12
+                                margin: { top: 12 }
+                                padding: { left: 16, right: 16, top: 8, bottom: 8 }
+                                show_bg: true
+                                draw_bg: { color: (BANNER_BG), border_radius: 6.0 }
+
+                                banner_label = <Label> {
+                                    width: Fill
+                                    draw_text: {
+                                        color: (BANNER_TEXT)
+                                        text_style: { font_size: 11.0 }
+                                    }
+                                    text: ""
+                                }
+
+                                up_button = <Button> {
+                                    width: Fit, height: 28
+                                    text: "Start local dora"
+                                }
+
+                                down_button = <Button> {
+                                    width: Fit, height: 28
+                                    text: "Stop local dora"
+                                }
+                            }
+
+                            dataflow_table = <DataflowTable> {}
+
+                            detail_panel = <DataflowDetail> {}
+
+                            telemetry = <TelemetryDashboard> {}
+
+                            node_top = <NodeTop> {}
+
+                            alert_panel = <AlertPanel> {}
+                        }
+
+                        log_viewer = <LogViewer> {}
                     }
 
                     // Divider line
@@ -54,6 +120,12 @@ live_design! {
 
                         <ChatScreen> {}
                     }
+
+                    start_modal = <Modal> {
+                        content: {
+                            start_dialog = <StartDialog> {}
+                        }
+                    }
                 }
             }
         }
@@ -62,23 +134,35 @@ live_design! {
 
 app_main!(App);
 
+/// Seconds without table view changes before they are saved
+const TABLE_VIEW_SAVE_DELAY: f64 = 1.0;
+
 #[derive(Live, LiveHook)]
 pub struct App {
     #[live]
     ui: WidgetRef,
     #[rust]
-    next_frame: NextFrame,
+    health: Option<ConnectionHealth>,
+    #[rust]
+    version_warning: Option<String>,
+    /// Live log stream of the dataflow in the log viewer
+    #[rust]
+    log_follower: Option<LogFollower>,
+    /// Table sort and filters not saved yet, written once typing pauses
     #[rust]
-    initialized: bool,
+    unsaved_table_view: Option<TableView>,
     #[rust]
-    last_refresh_time: f64,
+    save_table_view_timer: Timer,
 }
 
 impl LiveRegister for App {
     fn live_register(cx: &mut Cx) {
         crate::makepad_widgets::live_design(cx);
+        crate::alerts::live_design(cx);
         crate::chat::live_design(cx);
+        crate::metrics::live_design(cx);
         crate::dataflow::live_design(cx);
+        crate::logs::live_design(cx);
         // Light theme
         cx.link(live_id!(theme), live_id!(theme_desktop_light));
     }
@@ -89,122 +173,590 @@ impl MatchEvent for App {
         // Initialize API key from environment variable
         crate::api::init_api_key_from_env();
 
-        // Schedule initial data load for next frame (after UI is ready)
-        self.next_frame = cx.new_next_frame();
+        // Populate the coordinator switcher from the settings
+        let settings = settings::get();
+        let labels = settings.coordinators.iter().map(|c| c.label()).collect();
+        self.ui
+            .dataflow_table(id!(dataflow_table))
+            .set_coordinators(cx, labels, settings.selected_coordinator);
+        self.ui
+            .dataflow_table(id!(dataflow_table))
+            .set_table_view(cx, settings.table_view);
+
+        // Alert rules are checked by the poller, the panel shows them and past alerts
+        let alerts = self.ui.alert_panel(id!(alert_panel));
+        alerts.set_rules(cx, settings.alert_rules);
+        alerts.set_webhooks(cx, settings.webhooks);
+        alerts.set_history(cx, history::recent(alert_panel::HISTORY_SHOWN));
+
+        // All dora calls run on the poller thread, results come back as actions
+        self.ui.dataflow_table(id!(dataflow_table)).set_loading(cx);
+        poller::start(|event| Cx::post_action(event));
+        watcher::start();
     }
 
     fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions) {
+        for action in actions {
+            if let Some(event) = action.downcast_ref::<PollerEvent>() {
+                self.handle_poller_event(cx, event);
+            }
+            if let Some(ready) = action.downcast_ref::<FollowReady>() {
+                self.drain_followed_logs(cx, &ready.dataflow_id);
+            }
+            if let Some(TelemetryLoaded(result)) = action.downcast_ref::<TelemetryLoaded>() {
+                if let Err(e) = result {
+                    log!("Error loading telemetry: {}", e);
+                }
+                self.ui.telemetry_dashboard(id!(telemetry)).set_data(cx, result.clone());
+            }
+            if let Some(WebhookTested(result)) = action.downcast_ref::<WebhookTested>() {
+                let status = match result {
+                    Ok(message) => message.clone(),
+                    Err(e) => format!("Test notification failed: {}", e),
+                };
+                log!("[App] {}", status);
+                self.ui.alert_panel(id!(alert_panel)).set_status(cx, &status);
+            }
+        }
+
         // Handle DataflowTable actions using direct button click checks
         let table = self.ui.dataflow_table(id!(dataflow_table));
 
+        if let Some(index) = table.coordinator_changed(actions) {
+            self.select_coordinator(cx, index);
+        }
+
+        // The filter changes on every keystroke, save once it settles
+        if let Some(table_view) = table.table_view_changed(actions) {
+            self.unsaved_table_view = Some(table_view);
+            cx.stop_timer(self.save_table_view_timer);
+            self.save_table_view_timer = cx.start_timeout(TABLE_VIEW_SAVE_DELAY);
+        }
+
+        if let Some(uuid) = table.row_clicked(actions) {
+            self.show_detail(cx, &uuid);
+        }
+
+        let detail = self.ui.dataflow_detail(id!(detail_panel));
+        match detail.action(actions) {
+            Some(DetailAction::Restart(uuid)) => poller::request(PollerRequest::Restart(uuid)),
+            Some(DetailAction::Stop(uuid)) => poller::request(PollerRequest::Stop(uuid)),
+            Some(DetailAction::Destroy(uuid)) => poller::request(PollerRequest::Destroy(uuid)),
+            Some(DetailAction::Logs(uuid)) => self.open_logs(cx, &uuid),
+            Some(DetailAction::Close) => detail.hide(cx),
+            None => {}
+        }
+
+        if table.start_clicked(actions) {
+            self.ui
+                .start_dialog(id!(start_dialog))
+                .open(cx, settings::get().recent_dataflows);
+            self.ui.modal(id!(start_modal)).open(cx);
+        }
+
+        let start_dialog = self.ui.start_dialog(id!(start_dialog));
+        if let Some(request) = start_dialog.start_requested(cx, actions) {
+            log!("[App] Starting {}", request.path.display());
+            poller::request(PollerRequest::Start(request));
+        }
+
+        if start_dialog.cancelled(actions) {
+            self.ui.modal(id!(start_modal)).close(cx);
+        }
+
         if table.refresh_clicked(actions) {
             log!("[App] Refresh button clicked - refreshing dataflows");
-            self.refresh_dataflows(cx);
+            poller::refresh();
+        }
+
+        if let Some((command, uuids)) = table.batch_clicked(actions) {
+            log!("[App] {:?} on {} selected dataflows", command, uuids.len());
+            table.set_batch_summary(cx, &format!("Running {:?} on {} dataflows...", command, uuids.len()));
+            poller::request(PollerRequest::Batch(command, uuids));
+        }
+
+        if let Some(uuid) = table.restart_clicked(actions) {
+            log!("[App] Restart button clicked for {}", uuid);
+            poller::request(PollerRequest::Restart(uuid));
         }
 
         if let Some(uuid) = table.stop_clicked(actions) {
             log!("[App] Stop button clicked for {}", uuid);
-            self.stop_dataflow(cx, &uuid);
+            poller::request(PollerRequest::Stop(uuid));
         }
 
         if let Some(uuid) = table.destroy_clicked(actions) {
             log!("[App] Destroy button clicked for {}", uuid);
-            self.destroy_dataflow(cx, &uuid);
+            poller::request(PollerRequest::Destroy(uuid));
         }
 
         if let Some(uuid) = table.logs_clicked(actions) {
             log!("[App] Logs button clicked for {}", uuid);
-            self.view_dataflow_logs(&uuid);
+            self.open_logs(cx, &uuid);
+        }
+
+        if table.system_logs_clicked(actions) {
+            log!("[App] Opening system logs");
+            self.log_follower = None;
+            self.ui.log_viewer(id!(log_viewer)).open_system(cx);
+            poller::request(PollerRequest::SystemLogs);
+        }
+
+        if table.telemetry_clicked(actions) {
+            let selected = self.ui.dataflow_detail(id!(detail_panel)).shown_uuid();
+            let dashboard = self.ui.telemetry_dashboard(id!(telemetry));
+            match dashboard.open(cx, table.dataflow_labels(), selected.as_deref()) {
+                Some(request) => load_telemetry(request),
+                None => log!("[App] No dataflows to show telemetry of"),
+            }
+        }
+
+        if let Some(TelemetryAction::Load(request)) = self.ui.telemetry_dashboard(id!(telemetry)).action(actions) {
+            load_telemetry(request);
+        }
+
+        if table.nodes_clicked(actions) {
+            log!("[App] Showing node processes");
+            self.ui.node_top(id!(node_top)).open(cx);
+            poller::request(PollerRequest::WatchNodes(true));
+        }
+
+        if let Some(NodeTopAction::Close) = self.ui.node_top(id!(node_top)).action(actions) {
+            poller::request(PollerRequest::WatchNodes(false));
+        }
+
+        if table.alerts_clicked(actions) {
+            self.ui.alert_panel(id!(alert_panel)).open(cx);
+        }
+
+        match self.ui.alert_panel(id!(alert_panel)).action(actions) {
+            Some(AlertPanelAction::AddRule(rule)) => {
+                log!("[App] Adding alert rule {}", rule);
+                self.update_alert_rules(cx, |rules| rules.push(rule));
+            }
+            Some(AlertPanelAction::RemoveRule(index)) => {
+                self.update_alert_rules(cx, |rules| {
+                    if index < rules.len() {
+                        rules.remove(index);
+                    }
+                });
+            }
+            Some(AlertPanelAction::AddWebhook(webhook)) => {
+                log!("[App] Adding {} webhook {}", webhook.format.label(), webhook.url);
+                self.update_webhooks(cx, |webhooks| webhooks.push(webhook));
+            }
+            Some(AlertPanelAction::RemoveWebhook(index)) => {
+                self.update_webhooks(cx, |webhooks| {
+                    if index < webhooks.len() {
+                        webhooks.remove(index);
+                    }
+                });
+            }
+            Some(AlertPanelAction::TestWebhook(webhook)) => {
+                webhook::test_in_background(webhook, |tested| Cx::post_action(tested));
+            }
+            _ => {}
+        }
+
+        if let Some(uuids) = table.logs_selected(actions) {
+            log!("[App] Viewing merged logs of {} dataflows", uuids.len());
+            self.open_merged_logs(cx, uuids);
+        }
+
+        match self.ui.log_viewer(id!(log_viewer)).action(actions) {
+            Some(LogViewerAction::Fetch { dataflow_ids, node }) => {
+                for dataflow_id in dataflow_ids {
+                    match &node {
+                        Some(node) => poller::request(PollerRequest::NodeLogs {
+                            dataflow_id,
+                            node: node.clone(),
+                        }),
+                        None => poller::request(PollerRequest::Logs(dataflow_id)),
+                    }
+                }
+            }
+            Some(LogViewerAction::FetchSystem) => poller::request(PollerRequest::SystemLogs),
+            Some(LogViewerAction::Follow(dataflow_id)) => self.follow_logs(&dataflow_id),
+            // Dropping the follower closes its stream
+            Some(LogViewerAction::Unfollow) | Some(LogViewerAction::Close) => self.log_follower = None,
+            Some(LogViewerAction::Dock(dock)) => self.dock_log_viewer(cx, dock),
+            _ => {}
+        }
+
+        if self.ui.button(id!(connection_banner.up_button)).clicked(actions) {
+            self.bring_up_dora(cx);
+        }
+
+        if self.ui.button(id!(connection_banner.down_button)).clicked(actions) {
+            self.bring_down_dora(cx);
         }
     }
 }
 
 impl AppMain for App {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
-        self.match_event(cx, event);
-
-        // Handle next frame for initialization and auto-refresh
-        if let Some(ne) = self.next_frame.is_event(event) {
-            if !self.initialized {
-                self.initialized = true;
-                self.last_refresh_time = ne.time;
-                log!("[App] Initializing dataflow table on first frame");
-                self.refresh_dataflows(cx);
-            } else {
-                // Check if it's time for auto-refresh
-                let elapsed = ne.time - self.last_refresh_time;
-                if elapsed >= AUTO_REFRESH_INTERVAL {
-                    self.last_refresh_time = ne.time;
-                    log!("[App] Auto-refresh triggered after {:.1}s", elapsed);
-                    self.refresh_dataflows(cx);
+        if self.save_table_view_timer.is_event(event).is_some() {
+            if let Some(table_view) = self.unsaved_table_view.take() {
+                if let Err(e) = settings::update(|s| s.table_view = table_view) {
+                    log!("Error saving settings: {}", e);
                 }
             }
-            // Schedule the next frame to keep auto-refresh running
-            self.next_frame = cx.new_next_frame();
         }
-
+        self.match_event(cx, event);
         self.ui.handle_event(cx, event, &mut Scope::empty());
     }
 }
 
 impl App {
-    fn refresh_dataflows(&mut self, cx: &mut Cx) {
-        log!("[App] refresh_dataflows called");
+    fn select_coordinator(&mut self, cx: &mut Cx, index: usize) {
+        let Some(endpoint) = settings::get().coordinators.get(index).cloned() else {
+            return;
+        };
+        log!("[App] Switching to coordinator {}", endpoint.label());
+
+        if let Err(e) = settings::update(|s| s.selected_coordinator = index) {
+            log!("Error saving settings: {}", e);
+        }
+        self.ui.dataflow_table(id!(dataflow_table)).set_loading(cx);
+        poller::request(PollerRequest::SetCoordinator(endpoint));
+    }
+
+    fn handle_poller_event(&mut self, cx: &mut Cx, event: &PollerEvent) {
         let table = self.ui.dataflow_table(id!(dataflow_table));
-        table.set_loading(cx);
-
-        // Execute dora list command
-        let result = execute_tool("dora_list", "refresh", &serde_json::json!({}));
-        log!("[App] dora_list result: is_error={}, content={}", result.is_error, &result.content);
-
-        if result.is_error {
-            table.set_error(cx, &result.content);
-        } else {
-            // Try parsing as JSON array first, then NDJSON
-            let dataflows = if result.content.trim().starts_with('[') {
-                DataflowInfo::parse_json_array(&result.content)
-            } else {
-                DataflowInfo::parse_ndjson(&result.content)
-            };
-            log!("[App] Parsed {} dataflows", dataflows.len());
-            table.set_dataflows(cx, dataflows);
+        match event {
+            PollerEvent::Dataflows { dataflows, recent } => {
+                log!("[App] Dataflow list changed: {} dataflows", dataflows.len());
+                table.set_dataflows(cx, dataflows.clone(), recent);
+                if let Some(uuid) = self.ui.dataflow_detail(id!(detail_panel)).shown_uuid() {
+                    self.show_detail(cx, &uuid);
+                }
+                // New samples were recorded, move the last minutes or hours along
+                if let Some(request) = self.ui.telemetry_dashboard(id!(telemetry)).follow_now(cx) {
+                    load_telemetry(request);
+                }
+            }
+            PollerEvent::Sparklines(recent) => table.set_sparklines(cx, recent),
+            PollerEvent::ListFailed(e) => {
+                log!("[App] Listing dataflows failed: {}", e);
+                table.set_error(cx, e);
+            }
+            PollerEvent::Health(health) => {
+                self.health = Some(health.clone());
+                self.update_connection_banner(cx);
+            }
+            PollerEvent::VersionWarning(warning) => {
+                self.version_warning = warning.clone();
+                self.update_connection_banner(cx);
+            }
+            PollerEvent::Started { request, result } => {
+                self.ui.start_dialog(id!(start_dialog)).show_result(cx, result);
+                match result {
+                    Ok(uuid) => {
+                        log!("[App] Started dataflow {}", uuid);
+                        table.highlight(cx, Some(uuid));
+                        if let Err(e) = settings::update(|s| s.remember_recent(&request.path)) {
+                            log!("Error saving settings: {}", e);
+                        }
+                        // Attached: stream its logs into the viewer from the start
+                        if request.attach {
+                            self.show_logs(cx, uuid);
+                            self.ui.log_viewer(id!(log_viewer)).start_following(cx);
+                            self.follow_logs(uuid);
+                        }
+                    }
+                    Err(e) => log!("Error starting {}: {}", request.path.display(), e),
+                }
+            }
+            PollerEvent::Restarted {
+                dataflow_id,
+                result,
+            } => match result {
+                Ok(uuid) => {
+                    log!("[App] Restarted {} as {}", dataflow_id, uuid);
+                    table.highlight(cx, Some(uuid));
+                }
+                Err(e) => log!("Error restarting {}: {}", dataflow_id, e),
+            },
+            PollerEvent::BatchFinished { command, results } => {
+                let summary = poller::batch_summary(*command, results);
+                log!("[App] {}", summary);
+                table.set_batch_summary(cx, &summary);
+            }
+            PollerEvent::CommandFinished {
+                command,
+                dataflow_id,
+                result: Err(e),
+            } => {
+                log!("Error running {} on {}: {}", command, dataflow_id, e);
+            }
+            PollerEvent::CommandFinished { .. } => {}
+            PollerEvent::Logs {
+                dataflow_id,
+                node,
+                result,
+            } => {
+                if let Err(e) = result {
+                    log!("Error getting logs of {}: {}", dataflow_id, e);
+                }
+                self.ui
+                    .log_viewer(id!(log_viewer))
+                    .set_logs(cx, dataflow_id, node.as_deref(), result);
+            }
+            PollerEvent::SystemLogs(result) => {
+                if let Err(e) = result {
+                    log!("Error reading system logs: {}", e);
+                }
+                self.ui.log_viewer(id!(log_viewer)).set_system_logs(cx, result);
+            }
+            PollerEvent::NodeMetrics(result) => {
+                let names = table.dataflow_labels().into_iter().collect();
+                self.ui.node_top(id!(node_top)).set_metrics(cx, result, names);
+            }
+            PollerEvent::Alerts { events, firing } => {
+                for event in events {
+                    log!("[Alerts] {} {}", event.state.label(), event.message);
+                }
+                let alerts = self.ui.alert_panel(id!(alert_panel));
+                alerts.add_events(cx, events, firing.clone());
+                // The panel is the notification area, show it when something fires
+                if events.iter().any(|event| event.state == AlertState::Firing) {
+                    alerts.open(cx);
+                }
+                table.set_alert_count(cx, firing.len());
+            }
         }
     }
 
-    fn stop_dataflow(&mut self, cx: &mut Cx, uuid: &str) {
-        let args = serde_json::json!({ "dataflow_id": uuid });
-        let result = execute_tool("dora_stop", "stop", &args);
+    /// Change the alert rules, save them and show them in the panel
+    fn update_alert_rules(&mut self, cx: &mut Cx, change: impl FnOnce(&mut Vec<AlertRule>)) {
+        if let Err(e) = settings::update(|s| change(&mut s.alert_rules)) {
+            log!("Error saving settings: {}", e);
+        }
+        self.ui.alert_panel(id!(alert_panel)).set_rules(cx, settings::get().alert_rules);
+    }
 
-        if result.is_error {
-            log!("Error stopping dataflow: {}", result.content);
+    /// Change the webhooks, save them and show them in the panel
+    fn update_webhooks(&mut self, cx: &mut Cx, change: impl FnOnce(&mut Vec<Webhook>)) {
+        if let Err(e) = settings::update(|s| change(&mut s.webhooks)) {
+            log!("Error saving settings: {}", e);
         }
+        self.ui.alert_panel(id!(alert_panel)).set_webhooks(cx, settings::get().webhooks);
+    }
 
-        // Refresh the table after stopping
-        self.refresh_dataflows(cx);
+    /// Open the detail pane for a dataflow, or update it with fresh info
+    fn show_detail(&mut self, cx: &mut Cx, uuid: &str) {
+        let table = self.ui.dataflow_table(id!(dataflow_table));
+        let Some(info) = table.dataflow(uuid) else {
+            return;
+        };
+        let history = table.status_history(uuid);
+        let (nodes, source) = node_specs(uuid);
+        self.ui
+            .dataflow_detail(id!(detail_panel))
+            .show(cx, info, &history, nodes, source);
     }
 
-    fn destroy_dataflow(&mut self, cx: &mut Cx, uuid: &str) {
-        let args = serde_json::json!({ "dataflow_id": uuid });
-        let result = execute_tool("dora_destroy", "destroy", &args);
+    /// Open the log viewer on a dataflow and fetch its logs
+    fn open_logs(&mut self, cx: &mut Cx, uuid: &str) {
+        self.show_logs(cx, uuid);
+        poller::request(PollerRequest::Logs(uuid.to_string()));
+    }
 
-        if result.is_error {
-            log!("Error destroying dataflow: {}", result.content);
+    /// Open the log viewer on a dataflow, empty until lines arrive
+    fn show_logs(&mut self, cx: &mut Cx, uuid: &str) {
+        let table = self.ui.dataflow_table(id!(dataflow_table));
+        let info = table.dataflow(uuid);
+        let title = match &info {
+            Some(info) if !info.name.is_empty() => info.name.clone(),
+            Some(info) => info.uuid_short(),
+            None => uuid.to_string(),
+        };
+
+        // The stream of the dataflow shown before is no longer wanted
+        if self.ui.log_viewer(id!(log_viewer)).dataflow_ids() != [uuid] {
+            self.log_follower = None;
         }
+        let nodes = self.log_nodes(uuid);
+        self.ui
+            .log_viewer(id!(log_viewer))
+            .open(cx, vec![uuid.to_string()], &title, nodes);
+    }
 
-        // Refresh the table after destroying
-        self.refresh_dataflows(cx);
+    /// Open the log viewer on several dataflows, merged by time
+    fn open_merged_logs(&mut self, cx: &mut Cx, uuids: Vec<String>) {
+        if let [uuid] = &uuids[..] {
+            return self.open_logs(cx, uuid);
+        }
+        self.log_follower = None;
+        let mut nodes: Vec<String> = Vec::new();
+        for uuid in &uuids {
+            for node in self.log_nodes(uuid) {
+                if !nodes.contains(&node) {
+                    nodes.push(node);
+                }
+            }
+        }
+        let title = format!("{} dataflows", uuids.len());
+        self.ui
+            .log_viewer(id!(log_viewer))
+            .open(cx, uuids.clone(), &title, nodes);
+        for uuid in uuids {
+            poller::request(PollerRequest::Logs(uuid));
+        }
+    }
+
+    /// Node ids for the log viewer's node filter: the coordinator's, else the descriptor's
+    fn log_nodes(&self, uuid: &str) -> Vec<String> {
+        let nodes: Vec<String> = self
+            .ui
+            .dataflow_table(id!(dataflow_table))
+            .dataflow(uuid)
+            .map(|info| info.node_entries.iter().map(|n| n.id.clone()).collect())
+            .unwrap_or_default();
+        if !nodes.is_empty() {
+            return nodes;
+        }
+        node_specs(uuid).0.into_iter().map(|n| n.id).collect()
+    }
+
+    /// Stream the logs of `dataflow_id` into the log viewer
+    fn follow_logs(&mut self, dataflow_id: &str) {
+        log!("[App] Following logs of {}", dataflow_id);
+        let nodes = self.log_nodes(dataflow_id);
+        self.log_follower = Some(LogFollower::follow_dataflow(
+            settings::get().selected_endpoint(),
+            dataflow_id,
+            nodes,
+            |ready| Cx::post_action(ready),
+        ));
     }
 
-    fn view_dataflow_logs(&self, uuid: &str) {
-        let args = serde_json::json!({ "dataflow_id": uuid });
-        let result = execute_tool("dora_logs", "logs", &args);
+    /// Move streamed lines from the follower into the log viewer
+    fn drain_followed_logs(&mut self, cx: &mut Cx, dataflow_id: &str) {
+        let Some(follower) = &self.log_follower else {
+            return;
+        };
+        let batch = follower.drain();
+        if batch.dropped > 0 {
+            log!("[App] Log viewer fell behind, dropped {} lines of {}", batch.dropped, dataflow_id);
+        }
+        let viewer = self.ui.log_viewer(id!(log_viewer));
+        viewer.append_lines(cx, dataflow_id, batch.lines, batch.dropped);
+        if batch.finished {
+            viewer.follow_ended(cx, dataflow_id, batch.error.as_deref());
+            self.log_follower = None;
+        }
+    }
+
+    /// Put the log viewer right of the table or below it
+    fn dock_log_viewer(&mut self, cx: &mut Cx, dock: LogDock) {
+        let workspace = self.ui.view(id!(workspace));
+        let viewer = self.ui.log_viewer(id!(log_viewer));
+        match dock {
+            LogDock::Right => {
+                workspace.apply_over(cx, live! { flow: Right });
+                viewer.apply_over(cx, live! { width: 520, height: Fill });
+            }
+            LogDock::Bottom => {
+                workspace.apply_over(cx, live! { flow: Down });
+                viewer.apply_over(cx, live! { width: Fill, height: 320 });
+            }
+        }
+        workspace.redraw(cx);
+    }
 
-        if result.is_error {
-            log!("Error getting logs: {}", result.content);
-        } else {
-            log!("Dataflow logs for {}:\n{}", uuid, result.content);
+    /// Show or hide the connection banner depending on coordinator/daemon health.
+    fn update_connection_banner(&mut self, cx: &mut Cx) {
+        let Some(health) = self.health.clone() else {
+            return;
+        };
+        let local = supervisor::is_local(&settings::get().selected_endpoint());
+        let supervising = supervisor::is_supervising();
+
+        let mut message = match &health {
+            ConnectionHealth::Connected if supervising => {
+                "dora is running under Studio supervision".to_string()
+            }
+            ConnectionHealth::Connected => String::new(),
+            ConnectionHealth::NoDaemon => "Coordinator is running but no daemon is connected".to_string(),
+            ConnectionHealth::CoordinatorUnreachable(e) => format!("Coordinator not reachable: {}", e),
+        };
+        if let Some(warning) = &self.version_warning {
+            if !message.is_empty() {
+                message.push_str(" - ");
+            }
+            message.push_str(warning);
+        }
+        let healthy = health == ConnectionHealth::Connected;
+
+        let banner = self.ui.view(id!(connection_banner));
+        banner.set_visible(cx, !message.is_empty());
+        self.ui.label(id!(connection_banner.banner_label)).set_text(cx, &message);
+        self.ui
+            .button(id!(connection_banner.up_button))
+            .set_visible(cx, local && !healthy);
+        self.ui
+            .button(id!(connection_banner.down_button))
+            .set_visible(cx, supervising);
+        banner.redraw(cx);
+    }
+
+    fn bring_up_dora(&mut self, cx: &mut Cx) {
+        let endpoint = settings::get().selected_endpoint();
+        self.ui
+            .label(id!(connection_banner.banner_label))
+            .set_text(cx, &format!("Starting dora on {}...", endpoint.addr()));
+        self.ui.button(id!(connection_banner.up_button)).set_visible(cx, false);
+        self.ui.view(id!(connection_banner)).redraw(cx);
+
+        // Starting the processes waits for the coordinator port, keep it off the UI thread
+        std::thread::spawn(move || {
+            match supervisor::up(&endpoint) {
+                Ok(msg) => log!("[App] {}", msg),
+                Err(e) => log!("Error starting dora: {}", e),
+            }
+            poller::refresh();
+        });
+    }
+
+    fn bring_down_dora(&mut self, cx: &mut Cx) {
+        self.ui
+            .label(id!(connection_banner.banner_label))
+            .set_text(cx, "Stopping dora...");
+        self.ui.button(id!(connection_banner.down_button)).set_visible(cx, false);
+        self.ui.view(id!(connection_banner)).redraw(cx);
+
+        std::thread::spawn(|| {
+            match supervisor::down() {
+                Ok(msg) => log!("[App] {}", msg),
+                Err(e) => log!("Error stopping dora: {}", e),
+            }
+            poller::refresh();
+        });
+    }
+}
+
+/// Load telemetry off the UI thread, the dashboard gets it as [`TelemetryLoaded`]
+fn load_telemetry(request: TelemetryRequest) {
+    store::load_telemetry_in_background(request, |loaded| Cx::post_action(loaded));
+}
+
+/// Nodes of a dataflow from its recorded YAML, or else from the descriptor
+/// the coordinator logged when spawning it
+fn node_specs(uuid: &str) -> (Vec<NodeSpec>, &'static str) {
+    if let Some(record) = launches::get(uuid) {
+        match record.request.load_descriptor() {
+            Ok(yaml) => return (descriptor::nodes_from_yaml(&yaml), "the YAML recorded at start"),
+            Err(e) => log!("Error reading recorded YAML of {}: {}", uuid, e),
+        }
+    }
+    if let Some(debug) = watcher::spawn_descriptor(uuid) {
+        match descriptor::nodes_from_debug(&debug) {
+            Ok(nodes) => return (nodes, "the coordinator's spawn log"),
+            Err(e) => log!("Error parsing spawn descriptor of {}: {}", uuid, e),
         }
     }
+    (Vec::new(), "")
 }
 
 #[cfg(test)]
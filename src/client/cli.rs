@@ -0,0 +1,168 @@
+//! Client that shells out to the `dora` CLI.
+
+use super::version::{self, ListFormat};
+use super::{ClientError, DoraClient, LogSubscription, StartRequest};
+use crate::dataflow::model::looks_like_uuid;
+use crate::dataflow::DataflowInfo;
+use crate::settings::CoordinatorEndpoint;
+use std::process::{Child, Command, Stdio};
+
+/// Client that runs `dora` subcommands. Requires the binary on `PATH`.
+#[derive(Debug, Clone, Default)]
+pub struct CliClient {
+    coordinator: Option<CoordinatorEndpoint>,
+}
+
+impl CliClient {
+    /// Client for the CLI's default coordinator
+    pub fn new() -> Self {
+        Self { coordinator: None }
+    }
+
+    /// Client that passes `--coordinator-addr`/`--coordinator-port` to every command
+    pub fn with_coordinator(endpoint: CoordinatorEndpoint) -> Self {
+        Self {
+            coordinator: Some(endpoint),
+        }
+    }
+
+    /// Run a `dora` subcommand against the configured coordinator
+    fn dora(&self, args: &[&str]) -> Result<String, ClientError> {
+        let port;
+        let mut args = args.to_vec();
+        if let Some(endpoint) = &self.coordinator {
+            port = endpoint.port.to_string();
+            args.extend(["--coordinator-addr", endpoint.host.as_str()]);
+            args.extend(["--coordinator-port", port.as_str()]);
+        }
+        run_command("dora", &args)
+    }
+
+    /// Spawn `dora logs --follow`, which prints new lines until it is killed
+    pub fn follow_logs(&self, id: &str) -> Result<Child, ClientError> {
+        let mut command = Command::new("dora");
+        command.args(["logs", "--follow", id]);
+        if let Some(endpoint) = &self.coordinator {
+            command.args(["--coordinator-addr", endpoint.host.as_str()]);
+            command.args(["--coordinator-port", endpoint.port.to_string().as_str()]);
+        }
+        command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| ClientError::CommandFailed(format!("Failed to execute dora: {}", e)))
+    }
+}
+
+impl DoraClient for CliClient {
+    fn list_dataflows(&mut self) -> Result<Vec<DataflowInfo>, ClientError> {
+        let format = ListFormat::for_version(version::cli_version().ok());
+        match self.dora(format.list_args()) {
+            Ok(output) => format.parse(&output),
+            // Unknown version, or one that predates `--format`: fall back to the table
+            Err(ClientError::CommandFailed(e)) if format == ListFormat::Json && e.contains("--format") => {
+                let output = self.dora(ListFormat::Table.list_args())?;
+                ListFormat::Table.parse(&output)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn start(&mut self, request: &StartRequest) -> Result<String, ClientError> {
+        // The CLI only takes a file: write snapshots and overrides to a sibling
+        // copy so relative node paths still resolve against the same directory
+        let override_file = if !request.overrides_file() {
+            None
+        } else {
+            let descriptor = request.load_descriptor()?;
+            let yaml = serde_yaml::to_string(&descriptor)
+                .map_err(|e| ClientError::ParseError(format!("failed to write dataflow YAML: {}", e)))?;
+            let stem = request.path.file_stem().unwrap_or_default().to_string_lossy();
+            let copy = request
+                .path
+                .with_file_name(format!(".{}.studio-{}.yml", stem, std::process::id()));
+            std::fs::write(&copy, yaml)?;
+            Some(copy)
+        };
+
+        let path = override_file.as_deref().unwrap_or(&request.path).to_string_lossy();
+        let mut args = vec!["start", "--detach", path.as_ref()];
+        if let Some(name) = &request.name {
+            args.push("--name");
+            args.push(name);
+        }
+
+        let output = self.dora(&args);
+        if let Some(copy) = &override_file {
+            let _ = std::fs::remove_file(copy);
+        }
+        let output = output?;
+        output
+            .split(|c: char| c.is_whitespace() || c == '`')
+            .find(|token| looks_like_uuid(token))
+            .map(String::from)
+            .ok_or_else(|| ClientError::ParseError(format!("no dataflow UUID in output: {}", output)))
+    }
+
+    fn stop_dataflow(&mut self, id: &str) -> Result<(), ClientError> {
+        self.dora(&["stop", id]).map(|_| ())
+    }
+
+    fn destroy_dataflow(&mut self, id: &str) -> Result<(), ClientError> {
+        self.dora(&["stop", id, "--grace-duration", "0s"]).map(|_| ())
+    }
+
+    fn destroy(&mut self) -> Result<(), ClientError> {
+        self.dora(&["destroy"]).map(|_| ())
+    }
+
+    fn dataflow_logs(&mut self, id: &str, node: Option<&str>) -> Result<String, ClientError> {
+        let mut args = vec!["logs", id];
+        if let Some(node) = node {
+            args.push(node);
+        }
+        self.dora(&args)
+    }
+
+    fn subscribe_logs(&mut self, _dataflow_id: &str) -> Result<LogSubscription, ClientError> {
+        Err(ClientError::Unsupported(
+            "live log streaming requires the native coordinator client".to_string(),
+        ))
+    }
+
+    fn daemon_connected(&mut self) -> Result<bool, ClientError> {
+        // `dora check` exits unsuccessfully when something is down, but still
+        // prints one status line per component
+        let output = match self.dora(&["check"]) {
+            Ok(output) | Err(ClientError::CommandFailed(output)) => output,
+            Err(e) => return Err(e),
+        };
+        if !output.contains("Coordinator: ok") {
+            return Err(ClientError::CommandFailed(output));
+        }
+        Ok(output.contains("Daemon: ok"))
+    }
+}
+
+/// Run a command and return its combined output, or an error with both streams
+pub(crate) fn run_command(program: &str, args: &[&str]) -> Result<String, ClientError> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| ClientError::CommandFailed(format!("Failed to execute {}: {}", program, e)))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if output.status.success() {
+        Ok(format!("{}{}", stdout, stderr))
+    } else {
+        Err(ClientError::CommandFailed(format!(
+            "{} failed with exit code {:?}\nstdout: {}\nstderr: {}",
+            program,
+            output.status.code(),
+            stdout,
+            stderr
+        )))
+    }
+}
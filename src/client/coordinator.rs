@@ -0,0 +1,286 @@
+//! Native client for the dora-coordinator control channel.
+
+use super::protocol::{
+    parse_dataflow_list, read_frame, write_frame, ControlRequest, ControlRequestReply, LogMessage,
+};
+use super::{ClientError, DoraClient, StartRequest};
+use crate::dataflow::model::looks_like_uuid;
+use crate::dataflow::DataflowInfo;
+use std::io::ErrorKind;
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+// Timeouts for control requests
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Client that talks to dora-coordinator over its TCP control channel.
+///
+/// The connection is opened lazily and re-established transparently when the
+/// coordinator drops it, e.g. after a coordinator restart.
+pub struct CoordinatorClient {
+    addr: String,
+    stream: Option<TcpStream>,
+}
+
+impl CoordinatorClient {
+    /// Create a client for `addr` (`host:port`) without connecting yet
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            stream: None,
+        }
+    }
+
+    /// Create a client and connect to `addr` immediately
+    pub fn connect(addr: &str) -> Result<Self, ClientError> {
+        let mut client = Self::new(addr);
+        client.stream = Some(client.open_stream()?);
+        Ok(client)
+    }
+
+    /// Address of the coordinator control channel
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// Whether a connection is currently open
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    fn open_stream(&self) -> Result<TcpStream, ClientError> {
+        let mut last_error = None;
+        for addr in self.addr.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+                Ok(stream) => {
+                    stream.set_nodelay(true)?;
+                    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+                    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+                    return Ok(stream);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(ClientError::Io(last_error.unwrap_or_else(|| {
+            std::io::Error::new(
+                ErrorKind::AddrNotAvailable,
+                format!("{} did not resolve to any address", self.addr),
+            )
+        })))
+    }
+
+    /// Send a request and wait for the reply
+    fn request(&mut self, request: &ControlRequest) -> Result<ControlRequestReply, ClientError> {
+        let body = serde_json::to_vec(request)
+            .map_err(|e| ClientError::ParseError(format!("failed to encode request: {}", e)))?;
+
+        // A connection the coordinator closed since the last request is
+        // opened again. Once a request is written it is never sent twice:
+        // Start, Stop and Destroy are not idempotent, so errors reading the
+        // reply go to the caller.
+        if self.stream.as_ref().is_some_and(is_closed) {
+            self.stream = None;
+        }
+        let mut retried = false;
+        loop {
+            if self.stream.is_none() {
+                self.stream = Some(self.open_stream()?);
+            }
+            let stream = self.stream.as_mut().unwrap();
+
+            if let Err(e) = write_frame(stream, &body) {
+                self.stream = None;
+                let stale = matches!(
+                    e.kind(),
+                    ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted | ErrorKind::BrokenPipe
+                );
+                if retried || !stale {
+                    return Err(ClientError::Io(e));
+                }
+                retried = true;
+                continue;
+            }
+            let raw = match read_frame(stream) {
+                Ok(raw) => raw,
+                Err(e) => {
+                    self.stream = None;
+                    return Err(ClientError::Io(e));
+                }
+            };
+            return serde_json::from_slice(&raw).map_err(|e| {
+                ClientError::ParseError(format!("{}: {}", e, String::from_utf8_lossy(&raw)))
+            });
+        }
+    }
+
+    fn stop(&mut self, id: &str, grace_duration: Option<Duration>) -> Result<(), ClientError> {
+        let request = if looks_like_uuid(id) {
+            ControlRequest::Stop {
+                dataflow_uuid: id.to_string(),
+                grace_duration,
+            }
+        } else {
+            ControlRequest::StopByName {
+                name: id.to_string(),
+                grace_duration,
+            }
+        };
+
+        match self.request(&request)? {
+            ControlRequestReply::DataflowStopped { .. } => Ok(()),
+            ControlRequestReply::Error(e) => Err(ClientError::CoordinatorError(e)),
+            other => Err(ClientError::UnexpectedResponse(format!("{:?}", other))),
+        }
+    }
+}
+
+/// Whether the coordinator closed the connection, or left unread data on it.
+/// Either way it can't carry another request.
+fn is_closed(stream: &TcpStream) -> bool {
+    if stream.set_nonblocking(true).is_err() {
+        return true;
+    }
+    let closed = match stream.peek(&mut [0u8; 1]) {
+        Err(e) => e.kind() != ErrorKind::WouldBlock,
+        Ok(_) => true,
+    };
+    stream.set_nonblocking(false).is_err() || closed
+}
+
+impl DoraClient for CoordinatorClient {
+    fn list_dataflows(&mut self) -> Result<Vec<DataflowInfo>, ClientError> {
+        match self.request(&ControlRequest::List)? {
+            ControlRequestReply::DataflowList(payload) => parse_dataflow_list(&payload),
+            ControlRequestReply::Error(e) => Err(ClientError::CoordinatorError(e)),
+            other => Err(ClientError::UnexpectedResponse(format!("{:?}", other))),
+        }
+    }
+
+    fn start(&mut self, request: &StartRequest) -> Result<String, ClientError> {
+        // The coordinator expects the parsed descriptor, not the file path
+        let descriptor = request.load_descriptor()?;
+        let dataflow = serde_json::to_value(&descriptor).map_err(|e| {
+            ClientError::ParseError(format!("invalid dataflow YAML {}: {}", request.path.display(), e))
+        })?;
+        // A snapshot may outlive its file, the directory is enough then
+        let working_dir = std::fs::canonicalize(&request.path)
+            .unwrap_or_else(|_| request.path.clone())
+            .parent()
+            .map(|dir| dir.to_path_buf());
+
+        let request = ControlRequest::Start {
+            dataflow,
+            name: request.name.clone(),
+            local_working_dir: working_dir,
+            uv: false,
+        };
+
+        match self.request(&request)? {
+            ControlRequestReply::DataflowStarted { uuid }
+            | ControlRequestReply::DataflowSpawned { uuid } => Ok(uuid),
+            ControlRequestReply::Error(e) => Err(ClientError::CoordinatorError(e)),
+            other => Err(ClientError::UnexpectedResponse(format!("{:?}", other))),
+        }
+    }
+
+    fn stop_dataflow(&mut self, id: &str) -> Result<(), ClientError> {
+        self.stop(id, None)
+    }
+
+    fn destroy_dataflow(&mut self, id: &str) -> Result<(), ClientError> {
+        self.stop(id, Some(Duration::ZERO))
+    }
+
+    fn destroy(&mut self) -> Result<(), ClientError> {
+        let reply = self.request(&ControlRequest::Destroy);
+        // The coordinator exits right after acknowledging
+        self.stream = None;
+        match reply? {
+            ControlRequestReply::DestroyOk | ControlRequestReply::CoordinatorStopped => Ok(()),
+            ControlRequestReply::Error(e) => Err(ClientError::CoordinatorError(e)),
+            other => Err(ClientError::UnexpectedResponse(format!("{:?}", other))),
+        }
+    }
+
+    fn dataflow_logs(&mut self, id: &str, node: Option<&str>) -> Result<String, ClientError> {
+        let node = node.ok_or_else(|| {
+            ClientError::Unsupported(
+                "the coordinator only serves logs for a single node".to_string(),
+            )
+        })?;
+        let (uuid, name) = if looks_like_uuid(id) {
+            (Some(id.to_string()), None)
+        } else {
+            (None, Some(id.to_string()))
+        };
+
+        let request = ControlRequest::Logs {
+            uuid,
+            name,
+            node: node.to_string(),
+        };
+        match self.request(&request)? {
+            ControlRequestReply::Logs(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            ControlRequestReply::Error(e) => Err(ClientError::CoordinatorError(e)),
+            other => Err(ClientError::UnexpectedResponse(format!("{:?}", other))),
+        }
+    }
+
+    fn subscribe_logs(&mut self, dataflow_id: &str) -> Result<LogSubscription, ClientError> {
+        // The coordinator turns the connection into a one-way stream, so it
+        // gets a dedicated socket instead of the shared request connection
+        let mut stream = self.open_stream()?;
+        stream.set_read_timeout(None)?;
+
+        let request = ControlRequest::LogSubscribe {
+            dataflow_id: dataflow_id.to_string(),
+            level: "INFO".to_string(),
+        };
+        let body = serde_json::to_vec(&request)
+            .map_err(|e| ClientError::ParseError(format!("failed to encode request: {}", e)))?;
+        write_frame(&mut stream, &body)?;
+
+        Ok(LogSubscription { stream })
+    }
+
+    fn daemon_connected(&mut self) -> Result<bool, ClientError> {
+        match self.request(&ControlRequest::DaemonConnected)? {
+            ControlRequestReply::DaemonConnected(connected) => Ok(connected),
+            ControlRequestReply::Error(e) => Err(ClientError::CoordinatorError(e)),
+            other => Err(ClientError::UnexpectedResponse(format!("{:?}", other))),
+        }
+    }
+}
+
+/// Live log stream opened by [`DoraClient::subscribe_logs`].
+///
+/// Iteration blocks until the next message arrives and ends when the
+/// coordinator closes the connection.
+pub struct LogSubscription {
+    stream: TcpStream,
+}
+
+impl LogSubscription {
+    /// Closure that ends the stream from another thread, unblocking `next`
+    pub fn closer(&self) -> Result<impl FnOnce() + Send + 'static, ClientError> {
+        let stream = self.stream.try_clone()?;
+        Ok(move || {
+            let _ = stream.shutdown(Shutdown::Both);
+        })
+    }
+}
+
+impl Iterator for LogSubscription {
+    type Item = LogMessage;
+
+    fn next(&mut self) -> Option<LogMessage> {
+        loop {
+            let raw = read_frame(&mut self.stream).ok()?;
+            // Skip messages from newer coordinators that we can't decode
+            if let Ok(message) = serde_json::from_slice(&raw) {
+                return Some(message);
+            }
+        }
+    }
+}
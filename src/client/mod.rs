@@ -0,0 +1,230 @@
+//! Clients for controlling a dora coordinator.
+//!
+//! [`DoraClient`] abstracts the operations Studio performs on dataflows. Two
+//! implementations exist: [`CoordinatorClient`] speaks the coordinator's
+//! control protocol over TCP, and [`CliClient`] shells out to the `dora`
+//! binary. The native client is used unless `DORA_STUDIO_CLIENT=cli` is set.
+//!
+//! All operations go to the coordinator selected in the settings; switching
+//! coordinators replaces the shared client. A call takes the idle client out
+//! of the shared slot and puts it back when done, so a slow coordinator never
+//! holds the lock: concurrent calls get a client of their own.
+
+pub mod cli;
+pub mod coordinator;
+pub mod protocol;
+pub mod start;
+pub mod version;
+
+pub use cli::CliClient;
+pub use coordinator::{CoordinatorClient, LogSubscription};
+pub use protocol::LogMessage;
+pub use start::StartRequest;
+pub use version::DoraVersion;
+
+use crate::dataflow::DataflowInfo;
+use crate::settings::{self, CoordinatorEndpoint};
+use std::fmt;
+use std::path::Path;
+use std::sync::Mutex;
+
+// Shared client used by the UI and the agent tools
+static CLIENT: Mutex<Option<SharedClient>> = Mutex::new(None);
+
+/// The selected coordinator and a client for it that no call is using
+struct SharedClient {
+    endpoint: CoordinatorEndpoint,
+    idle: Option<Box<dyn DoraClient>>,
+}
+
+/// Errors returned by [`DoraClient`] implementations
+#[derive(Debug)]
+pub enum ClientError {
+    /// Connecting to or talking with the coordinator failed
+    Io(std::io::Error),
+    /// The reply could not be decoded
+    ParseError(String),
+    /// The coordinator answered with an error
+    CoordinatorError(String),
+    /// The coordinator answered with a reply that doesn't fit the request
+    UnexpectedResponse(String),
+    /// The `dora` CLI exited unsuccessfully
+    CommandFailed(String),
+    /// The operation isn't available on this client
+    Unsupported(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Io(e) => write!(f, "Coordinator connection error: {}", e),
+            ClientError::ParseError(e) => write!(f, "Failed to parse coordinator reply: {}", e),
+            ClientError::CoordinatorError(e) => write!(f, "Coordinator error: {}", e),
+            ClientError::UnexpectedResponse(e) => write!(f, "Unexpected coordinator reply: {}", e),
+            ClientError::CommandFailed(e) => write!(f, "{}", e),
+            ClientError::Unsupported(e) => write!(f, "Unsupported operation: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<std::io::Error> for ClientError {
+    fn from(e: std::io::Error) -> Self {
+        ClientError::Io(e)
+    }
+}
+
+/// Operations Studio performs against a dora coordinator
+pub trait DoraClient: Send {
+    /// List all dataflows known to the coordinator
+    fn list_dataflows(&mut self) -> Result<Vec<DataflowInfo>, ClientError>;
+
+    /// Start a dataflow in detached mode, returning its UUID
+    fn start(&mut self, request: &StartRequest) -> Result<String, ClientError>;
+
+    /// Start a dataflow from a YAML file without overrides, returning its UUID
+    fn start_dataflow(&mut self, path: &Path, name: Option<&str>) -> Result<String, ClientError> {
+        self.start(&StartRequest {
+            name: name.map(String::from),
+            ..StartRequest::new(path)
+        })
+    }
+
+    /// Gracefully stop a dataflow by UUID or name
+    fn stop_dataflow(&mut self, id: &str) -> Result<(), ClientError>;
+
+    /// Forcefully stop a dataflow by UUID or name, without a grace period
+    fn destroy_dataflow(&mut self, id: &str) -> Result<(), ClientError>;
+
+    /// Tear down the coordinator and all connected daemons
+    fn destroy(&mut self) -> Result<(), ClientError>;
+
+    /// Fetch the logs of a dataflow, optionally restricted to one node
+    fn dataflow_logs(&mut self, id: &str, node: Option<&str>) -> Result<String, ClientError>;
+
+    /// Open a live log stream for a dataflow
+    fn subscribe_logs(&mut self, dataflow_id: &str) -> Result<LogSubscription, ClientError>;
+
+    /// Whether at least one daemon is connected. Fails if the coordinator is unreachable.
+    fn daemon_connected(&mut self) -> Result<bool, ClientError>;
+}
+
+/// Reachability of the selected coordinator and its daemons
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionHealth {
+    Connected,
+    NoDaemon,
+    CoordinatorUnreachable(String),
+}
+
+/// Check whether the coordinator behind `client` is up and has a daemon
+pub fn health_of(client: &mut dyn DoraClient) -> ConnectionHealth {
+    match client.daemon_connected() {
+        Ok(true) => ConnectionHealth::Connected,
+        Ok(false) => ConnectionHealth::NoDaemon,
+        Err(e) => ConnectionHealth::CoordinatorUnreachable(e.to_string()),
+    }
+}
+
+/// Check whether the selected coordinator is up and has a daemon
+pub fn check_health() -> ConnectionHealth {
+    let (endpoint, mut client) = checkout();
+    let health = health_of(client.as_mut());
+    checkin(endpoint, client);
+    health
+}
+
+/// Build a client for `endpoint`, of the kind selected by `DORA_STUDIO_CLIENT`
+pub fn client_for(endpoint: &CoordinatorEndpoint) -> Box<dyn DoraClient> {
+    match std::env::var("DORA_STUDIO_CLIENT").as_deref() {
+        Ok("cli") => Box::new(CliClient::with_coordinator(endpoint.clone())),
+        _ => Box::new(CoordinatorClient::new(endpoint.addr())),
+    }
+}
+
+/// Build a client for the coordinator selected in the settings
+pub fn default_client() -> Box<dyn DoraClient> {
+    client_for(&settings::get().selected_endpoint())
+}
+
+/// Point the shared client at another coordinator
+pub fn set_coordinator(endpoint: &CoordinatorEndpoint) {
+    *CLIENT.lock().unwrap() = Some(SharedClient {
+        endpoint: endpoint.clone(),
+        idle: None,
+    });
+}
+
+/// Run `f` against the shared client, creating it on first use
+pub fn with_client<T>(
+    f: impl FnOnce(&mut dyn DoraClient) -> Result<T, ClientError>,
+) -> Result<T, ClientError> {
+    let (endpoint, mut client) = checkout();
+    let result = f(client.as_mut());
+    checkin(endpoint, client);
+    result
+}
+
+/// Take the idle shared client, or build one if another call is using it
+fn checkout() -> (CoordinatorEndpoint, Box<dyn DoraClient>) {
+    let (endpoint, idle) = {
+        let mut shared = CLIENT.lock().unwrap();
+        let shared = shared.get_or_insert_with(|| SharedClient {
+            endpoint: settings::get().selected_endpoint(),
+            idle: None,
+        });
+        (shared.endpoint.clone(), shared.idle.take())
+    };
+    // Connecting happens in the call, outside the lock
+    let client = idle.unwrap_or_else(|| client_for(&endpoint));
+    (endpoint, client)
+}
+
+/// Keep `client` for the next call, unless the coordinator was switched
+/// meanwhile or another client was put back first
+fn checkin(endpoint: CoordinatorEndpoint, client: Box<dyn DoraClient>) {
+    if let Some(shared) = CLIENT.lock().unwrap().as_mut() {
+        if shared.endpoint == endpoint && shared.idle.is_none() {
+            shared.idle = Some(client);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_error_display() {
+        let err = ClientError::CoordinatorError("no dataflow with ID `x`".to_string());
+        assert_eq!(err.to_string(), "Coordinator error: no dataflow with ID `x`");
+
+        let err = ClientError::from(std::io::Error::new(
+            std::io::ErrorKind::ConnectionRefused,
+            "refused",
+        ));
+        assert!(matches!(err, ClientError::Io(_)));
+        assert!(err.to_string().contains("refused"));
+    }
+
+    #[test]
+    fn test_slow_call_does_not_block_others() {
+        set_coordinator(&CoordinatorEndpoint::local());
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let slow = std::thread::spawn(move || {
+            with_client(|_| {
+                started_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+                Ok(())
+            })
+        });
+
+        // The slow call holds a client but not the lock
+        started_rx.recv().unwrap();
+        assert!(with_client(|_| Ok(())).is_ok());
+        release_tx.send(()).unwrap();
+        assert!(slow.join().unwrap().is_ok());
+    }
+}
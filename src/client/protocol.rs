@@ -0,0 +1,297 @@
+//! Wire types for the dora-coordinator control channel.
+//!
+//! The coordinator listens for control connections (the ones the `dora` CLI
+//! uses) on a plain TCP socket. Every message is a JSON document prefixed with
+//! its length as a little-endian `u64`. Requests and replies mirror the
+//! `ControlRequest` / `ControlRequestReply` enums from `dora-message`, but only
+//! the variants Studio needs are modelled here, and replies are decoded
+//! leniently so that minor differences between dora versions don't break
+//! parsing.
+
+use crate::dataflow::{DataflowInfo, DataflowStatus, NodeInfo};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::ClientError;
+
+/// Default port of the coordinator control channel (`DORA_COORDINATOR_PORT_CONTROL_DEFAULT`)
+pub const DEFAULT_CONTROL_PORT: u16 = 6012;
+
+/// Default port daemons connect to the coordinator on (`DORA_COORDINATOR_PORT_DEFAULT`)
+pub const DEFAULT_DAEMON_PORT: u16 = 53290;
+
+/// Upper bound for a single frame, to avoid allocating garbage lengths
+const MAX_FRAME_LEN: u64 = 64 * 1024 * 1024;
+
+/// Requests sent from Studio to the coordinator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ControlRequest {
+    Start {
+        dataflow: serde_json::Value,
+        name: Option<String>,
+        local_working_dir: Option<PathBuf>,
+        #[serde(default)]
+        uv: bool,
+    },
+    Stop {
+        dataflow_uuid: String,
+        grace_duration: Option<Duration>,
+    },
+    StopByName {
+        name: String,
+        grace_duration: Option<Duration>,
+    },
+    Logs {
+        uuid: Option<String>,
+        name: Option<String>,
+        node: String,
+    },
+    Destroy,
+    List,
+    DaemonConnected,
+    LogSubscribe {
+        dataflow_id: String,
+        level: String,
+    },
+}
+
+/// Replies received from the coordinator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ControlRequestReply {
+    Error(String),
+    CoordinatorStopped,
+    DataflowStarted {
+        uuid: String,
+    },
+    DataflowSpawned {
+        uuid: String,
+    },
+    DataflowStopped {
+        uuid: String,
+        #[serde(default)]
+        result: serde_json::Value,
+    },
+    /// Either a bare list of entries (current dora) or `{ "dataflows": [...] }` (older dora)
+    DataflowList(serde_json::Value),
+    DestroyOk,
+    DaemonConnected(bool),
+    Logs(Vec<u8>),
+}
+
+/// Log message streamed after a `LogSubscribe` request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogMessage {
+    #[serde(default)]
+    pub dataflow_id: Option<String>,
+    #[serde(default)]
+    pub node_id: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_level")]
+    pub level: String,
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// Accept `"INFO"`, `{"LogLevel":"INFO"}` and `"Stdout"` level encodings
+fn deserialize_level<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(match value {
+        serde_json::Value::String(s) => s,
+        serde_json::Value::Object(map) => map
+            .values()
+            .next()
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        _ => String::new(),
+    })
+}
+
+/// Convert the payload of a `DataflowList` reply into table rows
+pub fn parse_dataflow_list(payload: &serde_json::Value) -> Result<Vec<DataflowInfo>, ClientError> {
+    let entries = match payload {
+        serde_json::Value::Array(entries) => entries,
+        serde_json::Value::Object(map) => match map.get("dataflows") {
+            Some(serde_json::Value::Array(entries)) => entries,
+            _ => {
+                return Err(ClientError::ParseError(format!(
+                    "unrecognized dataflow list: {}",
+                    payload
+                )))
+            }
+        },
+        _ => {
+            return Err(ClientError::ParseError(format!(
+                "unrecognized dataflow list: {}",
+                payload
+            )))
+        }
+    };
+
+    entries.iter().map(parse_dataflow_entry).collect()
+}
+
+fn parse_dataflow_entry(entry: &serde_json::Value) -> Result<DataflowInfo, ClientError> {
+    // Current dora nests the identity under `id`, older versions list bare ids
+    let id = entry.get("id").unwrap_or(entry);
+    let uuid = id
+        .get("uuid")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ClientError::ParseError(format!("dataflow entry without uuid: {}", entry)))?;
+    let name = id.get("name").and_then(|v| v.as_str()).unwrap_or_default();
+
+    // Only running dataflows were listed before statuses were added
+    let (status, reason) = match entry.get("status") {
+        Some(status) => DataflowStatus::from_value(status),
+        None => (DataflowStatus::Running, None),
+    };
+
+    // `nodes` is either a count or the list of node entries
+    let (nodes, node_entries) = match entry.get("nodes") {
+        Some(serde_json::Value::Array(items)) => {
+            let entries: Vec<NodeInfo> = items.iter().filter_map(NodeInfo::from_value).collect();
+            (entries.len() as u32, entries)
+        }
+        Some(count) => (count.as_u64().unwrap_or(0) as u32, Vec::new()),
+        None => (0, Vec::new()),
+    };
+
+    Ok(DataflowInfo {
+        uuid: uuid.to_string(),
+        name: name.to_string(),
+        status,
+        nodes,
+        cpu: entry.get("cpu").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        memory: entry.get("memory").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        started_at: ["started_at", "start_time"]
+            .iter()
+            .find_map(|key| entry.get(*key).and_then(crate::dataflow::model::parse_timestamp)),
+        exit_reason: entry
+            .get("exit_reason")
+            .or_else(|| entry.get("error"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or(reason),
+        node_entries,
+    })
+}
+
+/// Write one length-prefixed frame
+pub fn write_frame(stream: &mut impl Write, data: &[u8]) -> std::io::Result<()> {
+    stream.write_all(&(data.len() as u64).to_le_bytes())?;
+    stream.write_all(data)?;
+    stream.flush()
+}
+
+/// Read one length-prefixed frame
+pub fn read_frame(stream: &mut impl Read) -> std::io::Result<Vec<u8>> {
+    let mut len_buf = [0u8; 8];
+    stream.read_exact(&mut len_buf)?;
+    let len = u64::from_le_bytes(len_buf);
+    if len > MAX_FRAME_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("frame of {} bytes exceeds limit", len),
+        ));
+    }
+    let mut data = vec![0u8; len as usize];
+    stream.read_exact(&mut data)?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_serialization() {
+        let json = serde_json::to_string(&ControlRequest::List).unwrap();
+        assert_eq!(json, "\"List\"");
+
+        let json = serde_json::to_string(&ControlRequest::Stop {
+            dataflow_uuid: "abc".to_string(),
+            grace_duration: None,
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"Stop":{"dataflow_uuid":"abc","grace_duration":null}}"#);
+    }
+
+    #[test]
+    fn test_parse_dataflow_list_current_format() {
+        let reply: ControlRequestReply = serde_json::from_str(
+            r#"{"DataflowList":[{"id":{"uuid":"019be55a-8468-7d4b-9e95-db7938985bc7","name":"camera"},"status":"Running"},{"id":{"uuid":"019be55b-3eda-728a-996c-cb66206e95b3","name":null},"status":"Failed"}]}"#,
+        )
+        .unwrap();
+        let ControlRequestReply::DataflowList(payload) = reply else {
+            panic!("expected DataflowList");
+        };
+
+        let dataflows = parse_dataflow_list(&payload).unwrap();
+        assert_eq!(dataflows.len(), 2);
+        assert_eq!(dataflows[0].name, "camera");
+        assert_eq!(dataflows[0].status, DataflowStatus::Running);
+        assert!(dataflows[1].name.is_empty());
+        assert_eq!(dataflows[1].status, DataflowStatus::Failed);
+    }
+
+    #[test]
+    fn test_parse_dataflow_list_legacy_format() {
+        let payload = serde_json::json!({ "dataflows": [{ "uuid": "abc", "name": "legacy" }] });
+        let dataflows = parse_dataflow_list(&payload).unwrap();
+        assert_eq!(dataflows.len(), 1);
+        assert_eq!(dataflows[0].uuid, "abc");
+        assert_eq!(dataflows[0].status, DataflowStatus::Running);
+    }
+
+    #[test]
+    fn test_parse_dataflow_list_with_nodes_and_failure() {
+        let payload = serde_json::json!([{
+            "id": { "uuid": "abc", "name": "vision" },
+            "status": { "Failed": { "error": "node `camera` exited with code 1" } },
+            "nodes": [{ "id": "camera", "status": "Failed" }, "plot"]
+        }]);
+        let dataflows = parse_dataflow_list(&payload).unwrap();
+        assert_eq!(dataflows[0].status, DataflowStatus::Failed);
+        assert_eq!(
+            dataflows[0].exit_reason.as_deref(),
+            Some("node `camera` exited with code 1")
+        );
+        assert_eq!(dataflows[0].nodes, 2);
+        assert_eq!(dataflows[0].node_entries[1].id, "plot");
+    }
+
+    #[test]
+    fn test_parse_dataflow_list_rejects_garbage() {
+        assert!(parse_dataflow_list(&serde_json::json!("nope")).is_err());
+        assert!(parse_dataflow_list(&serde_json::json!([{ "name": "no-uuid" }])).is_err());
+    }
+
+    #[test]
+    fn test_log_message_level_encodings() {
+        let plain: LogMessage =
+            serde_json::from_str(r#"{"node_id":"camera","level":"INFO","message":"hi"}"#).unwrap();
+        assert_eq!(plain.level, "INFO");
+
+        let tagged: LogMessage =
+            serde_json::from_str(r#"{"level":{"LogLevel":"WARN"},"message":"hi"}"#).unwrap();
+        assert_eq!(tagged.level, "WARN");
+    }
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, b"\"List\"").unwrap();
+        assert_eq!(&buf[..8], &6u64.to_le_bytes());
+
+        let frame = read_frame(&mut buf.as_slice()).unwrap();
+        assert_eq!(frame, b"\"List\"");
+    }
+}
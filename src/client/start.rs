@@ -0,0 +1,190 @@
+//! Everything needed to start a dataflow: the YAML and the user's options.
+
+use super::ClientError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A request to start a dataflow
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StartRequest {
+    /// Dataflow YAML file
+    pub path: PathBuf,
+    /// Optional dataflow name (`--name`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Stream the dataflow's logs after starting it
+    #[serde(default)]
+    pub attach: bool,
+    /// Environment variables set on every node, overriding the YAML
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// YAML to start instead of the file's current content, e.g. the
+    /// snapshot taken when a dataflow is restarted. `path` still sets the
+    /// working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yaml: Option<String>,
+}
+
+impl StartRequest {
+    /// Start `path` detached, without name or overrides
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Check the request can be sent, with a message for the user if not
+    pub fn validate(&self) -> Result<(), String> {
+        if self.path.as_os_str().is_empty() {
+            return Err("Choose a dataflow YAML file".to_string());
+        }
+        if self.yaml.is_none() && !self.path.is_file() {
+            return Err(format!("{} does not exist", self.path.display()));
+        }
+        if self.name.as_deref().is_some_and(|n| n.contains(char::is_whitespace)) {
+            return Err("Dataflow names cannot contain spaces".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether the YAML sent differs from the file on disk
+    pub fn overrides_file(&self) -> bool {
+        self.yaml.is_some() || !self.env.is_empty()
+    }
+
+    /// The YAML to start: the snapshot if set, the file otherwise
+    pub fn read_yaml(&self) -> Result<String, ClientError> {
+        match &self.yaml {
+            Some(yaml) => Ok(yaml.clone()),
+            None => Ok(std::fs::read_to_string(&self.path)?),
+        }
+    }
+
+    /// Read the YAML and apply the environment overrides to every node
+    pub fn load_descriptor(&self) -> Result<serde_yaml::Value, ClientError> {
+        let yaml = self.read_yaml()?;
+        let mut descriptor = parse_descriptor(&self.path, &yaml)?;
+        apply_env_overrides(&mut descriptor, &self.env);
+        Ok(descriptor)
+    }
+}
+
+/// Parse dataflow YAML, naming the file in errors
+pub fn parse_descriptor(path: &Path, yaml: &str) -> Result<serde_yaml::Value, ClientError> {
+    serde_yaml::from_str(yaml)
+        .map_err(|e| ClientError::ParseError(format!("invalid dataflow YAML {}: {}", path.display(), e)))
+}
+
+/// Merge `env` into the `env` map of every node of a dataflow descriptor
+pub fn apply_env_overrides(descriptor: &mut serde_yaml::Value, env: &BTreeMap<String, String>) {
+    if env.is_empty() {
+        return;
+    }
+    let Some(nodes) = descriptor.get_mut("nodes").and_then(|n| n.as_sequence_mut()) else {
+        return;
+    };
+    for node in nodes.iter_mut().filter(|n| n.is_mapping()) {
+        let node_env = &mut node["env"];
+        if !node_env.is_mapping() {
+            *node_env = serde_yaml::Value::Mapping(Default::default());
+        }
+        if let Some(map) = node_env.as_mapping_mut() {
+            for (key, value) in env {
+                map.insert(key.clone().into(), value.clone().into());
+            }
+        }
+    }
+}
+
+/// Parse `KEY=VALUE` pairs separated by new lines or `;`
+pub fn parse_env_overrides(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut env = BTreeMap::new();
+    for entry in text.split(['\n', ';']).map(str::trim).filter(|e| !e.is_empty()) {
+        let (key, value) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("`{}` is not KEY=VALUE", entry))?;
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(format!("invalid variable name in `{}`", entry));
+        }
+        env.insert(key.to_string(), value.trim().to_string());
+    }
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_overrides() {
+        let env = parse_env_overrides("IMAGE_WIDTH=640\n IMAGE_HEIGHT = 480 ;MODEL=yolo=v8\n\n").unwrap();
+        assert_eq!(env.len(), 3);
+        assert_eq!(env["IMAGE_HEIGHT"], "480");
+        assert_eq!(env["MODEL"], "yolo=v8");
+        assert!(parse_env_overrides("").unwrap().is_empty());
+
+        assert!(parse_env_overrides("NO_VALUE").is_err());
+        assert!(parse_env_overrides("=1").is_err());
+        assert!(parse_env_overrides("TWO WORDS=1").is_err());
+    }
+
+    #[test]
+    fn test_apply_env_overrides() {
+        let mut descriptor: serde_yaml::Value = serde_yaml::from_str(
+            "nodes:\n  - id: camera\n    env:\n      IMAGE_WIDTH: 320\n      CAPTURE_PATH: 0\n  - id: plot\n",
+        )
+        .unwrap();
+        let env = parse_env_overrides("IMAGE_WIDTH=640").unwrap();
+        apply_env_overrides(&mut descriptor, &env);
+
+        let camera = &descriptor["nodes"][0]["env"];
+        assert_eq!(camera["IMAGE_WIDTH"].as_str(), Some("640"));
+        assert_eq!(camera["CAPTURE_PATH"].as_u64(), Some(0));
+        assert_eq!(descriptor["nodes"][1]["env"]["IMAGE_WIDTH"].as_str(), Some("640"));
+    }
+
+    #[test]
+    fn test_validate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flow.yml");
+        std::fs::write(&path, "nodes: []").unwrap();
+
+        assert!(StartRequest::new(&path).validate().is_ok());
+        assert!(StartRequest::new("").validate().is_err());
+        assert!(StartRequest::new(dir.path().join("missing.yml")).validate().is_err());
+        let spaced = StartRequest {
+            name: Some("my flow".to_string()),
+            ..StartRequest::new(&path)
+        };
+        assert!(spaced.validate().is_err());
+    }
+
+    #[test]
+    fn test_load_descriptor_prefers_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flow.yml");
+        std::fs::write(&path, "nodes:\n  - id: edited\n").unwrap();
+
+        let request = StartRequest {
+            yaml: Some("nodes:\n  - id: original\n".to_string()),
+            ..StartRequest::new(&path)
+        };
+        assert!(request.overrides_file());
+        assert_eq!(request.load_descriptor().unwrap()["nodes"][0]["id"].as_str(), Some("original"));
+        assert!(!StartRequest::new(&path).overrides_file());
+    }
+
+    #[test]
+    fn test_load_descriptor_reports_invalid_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.yml");
+        std::fs::write(&path, "nodes: [").unwrap();
+
+        let err = StartRequest::new(&path).load_descriptor().unwrap_err();
+        assert!(matches!(err, ClientError::ParseError(_)));
+        assert!(err.to_string().contains("broken.yml"));
+    }
+}
@@ -0,0 +1,464 @@
+//! dora version detection and per-version `dora list` parsing.
+//!
+//! The CLI version is probed once with `dora --version` and decides how
+//! [`CliClient`](super::CliClient) asks for and reads the dataflow list:
+//! JSON output for current versions, the whitespace table for older ones.
+
+use super::cli::run_command;
+use super::ClientError;
+use crate::dataflow::model::looks_like_uuid;
+use crate::dataflow::{DataflowInfo, DataflowStatus};
+use crate::settings::CoordinatorEndpoint;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Mutex;
+
+// Cached result of `dora --version`, probed on first use
+static CLI_VERSION: Mutex<Option<Result<DoraVersion, String>>> = Mutex::new(None);
+
+/// First release whose `dora list` accepts `--format json`
+pub const JSON_LIST_SINCE: DoraVersion = DoraVersion::new(0, 3, 10);
+
+/// A dora release version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DoraVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl DoraVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Parse the output of `dora --version`, e.g. `dora-cli 0.3.11`
+    pub fn parse(output: &str) -> Option<Self> {
+        output.split_whitespace().find_map(|token| {
+            let token = token.trim_start_matches('v');
+            // Drop pre-release and build suffixes like `-rc1` or `+abc`
+            let core = token.split(['-', '+']).next()?;
+            let mut parts = core.split('.').map(|p| p.parse::<u32>());
+            let major = parts.next()?.ok()?;
+            let minor = parts.next()?.ok()?;
+            let patch = match parts.next() {
+                Some(p) => p.ok()?,
+                None => 0,
+            };
+            Some(Self::new(major, minor, patch))
+        })
+    }
+}
+
+impl fmt::Display for DoraVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Version of the `dora` CLI on `PATH`, probed once and cached
+pub fn cli_version() -> Result<DoraVersion, String> {
+    let mut cached = CLI_VERSION.lock().unwrap();
+    if let Some(result) = cached.as_ref() {
+        return result.clone();
+    }
+    let result = probe_cli_version();
+    match &result {
+        Ok(version) => eprintln!("[Client] dora CLI version {}", version),
+        Err(e) => eprintln!("[Client] Could not detect dora CLI version: {}", e),
+    }
+    *cached = Some(result.clone());
+    result
+}
+
+/// Forget the cached CLI version so the next call probes again
+pub fn reset_cli_version() {
+    *CLI_VERSION.lock().unwrap() = None;
+}
+
+fn probe_cli_version() -> Result<DoraVersion, String> {
+    let output = run_command("dora", &["--version"]).map_err(|e| e.to_string())?;
+    DoraVersion::parse(&output)
+        .ok_or_else(|| format!("unrecognized `dora --version` output: {}", output.trim()))
+}
+
+/// Version of the coordinator listening locally, found through its process.
+///
+/// The control protocol does not report versions, so this only works for a
+/// coordinator on this machine: its executable is asked for `--version`.
+pub fn local_coordinator_version() -> Option<DoraVersion> {
+    let exe = find_coordinator_exe()?;
+    let output = run_command(&exe, &["--version"]).ok()?;
+    DoraVersion::parse(&output)
+}
+
+#[cfg(target_os = "linux")]
+fn find_coordinator_exe() -> Option<String> {
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        let path = entry.path();
+        let Ok(cmdline) = std::fs::read(path.join("cmdline")) else {
+            continue;
+        };
+        let args: Vec<String> = cmdline
+            .split(|b| *b == 0)
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect();
+        let program = args.first().map(|a| a.rsplit('/').next().unwrap_or(a)).unwrap_or("");
+        let is_coordinator = program == "dora-coordinator"
+            || (program == "dora" && args.get(1).map(String::as_str) == Some("coordinator"));
+        if is_coordinator {
+            if let Ok(exe) = std::fs::read_link(path.join("exe")) {
+                return Some(exe.to_string_lossy().into_owned());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+fn find_coordinator_exe() -> Option<String> {
+    None
+}
+
+/// Warning text when the CLI and coordinator versions differ
+pub fn version_mismatch(cli: DoraVersion, coordinator: DoraVersion) -> Option<String> {
+    (cli != coordinator).then(|| {
+        format!(
+            "dora CLI {} does not match coordinator {}; commands may fail or be misparsed",
+            cli, coordinator
+        )
+    })
+}
+
+/// Warning for the coordinator at `endpoint` when its version differs from the CLI.
+///
+/// Only local coordinators can be checked; `None` means no mismatch was found.
+pub fn check_versions(endpoint: &CoordinatorEndpoint) -> Option<String> {
+    if !crate::supervisor::is_local(endpoint) {
+        return None;
+    }
+    let cli = cli_version().ok()?;
+    version_mismatch(cli, local_coordinator_version()?)
+}
+
+/// Output formats of `dora list` across versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFormat {
+    /// `dora list --format json`: a JSON array or one object per line
+    Json,
+    /// Plain `dora list`: a whitespace separated table
+    Table,
+}
+
+impl ListFormat {
+    /// Format to request from a CLI of the given version
+    pub fn for_version(version: Option<DoraVersion>) -> Self {
+        match version {
+            Some(v) if v < JSON_LIST_SINCE => ListFormat::Table,
+            _ => ListFormat::Json,
+        }
+    }
+
+    /// Arguments for `dora list` in this format
+    pub fn list_args(&self) -> &'static [&'static str] {
+        match self {
+            ListFormat::Json => &["list", "--format", "json"],
+            ListFormat::Table => &["list"],
+        }
+    }
+
+    /// Parse `dora list` output in this format
+    pub fn parse(&self, output: &str) -> Result<Vec<DataflowInfo>, ClientError> {
+        match self {
+            ListFormat::Json => parse_json_list(output),
+            ListFormat::Table => parse_table_list(output),
+        }
+    }
+}
+
+/// Parse JSON list output, either an array or NDJSON
+pub fn parse_json_list(output: &str) -> Result<Vec<DataflowInfo>, ClientError> {
+    let trimmed = output.trim();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+    if trimmed.starts_with('[') {
+        return serde_json::from_str(trimmed)
+            .map_err(|e| ClientError::ParseError(format!("invalid `dora list` JSON ({}): {}", e, trimmed)));
+    }
+    trimmed
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                ClientError::ParseError(format!("invalid `dora list` JSON on line {} ({}): {}", i + 1, e, line))
+            })
+        })
+        .collect()
+}
+
+/// Parse the whitespace table printed by older `dora list` versions.
+///
+/// Columns changed between releases (some only print UUID and name), so each
+/// row is mostly read by token shape rather than position: the UUID, `12.5%`
+/// for CPU, `36 MB` for memory and whatever remains for the name. Node counts
+/// and status words can be part of names too, so they are only read from
+/// under the `NODES` and `STATUS` headings of the header row.
+pub fn parse_table_list(output: &str) -> Result<Vec<DataflowInfo>, ClientError> {
+    let mut dataflows = Vec::new();
+    let mut unrecognized = Vec::new();
+    let mut columns = TableColumns::default();
+
+    for line in output.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match parse_table_row(line, &columns) {
+            Some(df) => dataflows.push(df),
+            None if is_table_decoration(line) => {
+                if line.to_lowercase().starts_with("uuid") {
+                    columns = TableColumns::from_header(line);
+                }
+            }
+            None => unrecognized.push(line),
+        }
+    }
+
+    if dataflows.is_empty() && !unrecognized.is_empty() {
+        return Err(ClientError::ParseError(format!(
+            "unrecognized `dora list` output: {}",
+            unrecognized.join(" | ")
+        )));
+    }
+    Ok(dataflows)
+}
+
+/// Tokens of a table line with their byte offsets
+fn table_tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices() {
+        let separator = c.is_whitespace() || c == '|';
+        match (start, separator) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                tokens.push((s, &line[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((s, &line[s..]));
+    }
+    tokens
+}
+
+/// Byte ranges of the columns only found by the header row
+#[derive(Debug, Default)]
+struct TableColumns {
+    nodes: Option<Range<usize>>,
+    status: Option<Range<usize>>,
+}
+
+impl TableColumns {
+    fn from_header(header: &str) -> Self {
+        Self {
+            nodes: column_of(header, "nodes"),
+            status: column_of(header, "status"),
+        }
+    }
+}
+
+/// Byte range from a heading of the header row to the next heading
+fn column_of(header: &str, heading: &str) -> Option<Range<usize>> {
+    let tokens = table_tokens(header);
+    let index = tokens.iter().position(|(_, t)| t.eq_ignore_ascii_case(heading))?;
+    let end = tokens.get(index + 1).map_or(usize::MAX, |(start, _)| *start);
+    Some(tokens[index].0..end)
+}
+
+/// Index of the first token starting in `column` that `accept`s
+fn cell_in(tokens: &[(usize, &str)], column: Option<&Range<usize>>, accept: impl Fn(&str) -> bool) -> Option<usize> {
+    let column = column?;
+    tokens
+        .iter()
+        .position(|(start, t)| column.contains(start) && accept(t))
+}
+
+fn parse_table_row(line: &str, columns: &TableColumns) -> Option<DataflowInfo> {
+    let tokens = table_tokens(line);
+    let uuid_index = tokens.iter().position(|(_, t)| looks_like_uuid(t))?;
+    let nodes_index = cell_in(&tokens, columns.nodes.as_ref(), |t| t.parse::<u32>().is_ok());
+    let status_index = cell_in(&tokens, columns.status.as_ref(), |t| {
+        DataflowStatus::parse(t) != DataflowStatus::Unknown
+    });
+
+    let mut df = DataflowInfo {
+        uuid: tokens[uuid_index].1.to_string(),
+        // Old versions only listed running dataflows and printed no status
+        status: DataflowStatus::Running,
+        ..Default::default()
+    };
+    let mut name_parts = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i].1;
+        let next_unit = tokens.get(i + 1).and_then(|(_, u)| memory_unit_in_gb(u));
+        if i == uuid_index {
+            i += 1;
+            continue;
+        }
+        if Some(i) == nodes_index {
+            df.nodes = token.parse().unwrap_or_default();
+        } else if Some(i) == status_index {
+            df.status = DataflowStatus::parse(token);
+        } else if let Some(cpu) = token.strip_suffix('%').and_then(|v| v.parse::<f64>().ok()) {
+            df.cpu = cpu;
+        } else if let (Ok(amount), Some(scale)) = (token.parse::<f64>(), next_unit) {
+            df.memory = amount * scale;
+            i += 1;
+        } else {
+            name_parts.push(token.trim_matches(|c| c == '(' || c == ')'));
+        }
+        i += 1;
+    }
+
+    let name = name_parts.join(" ");
+    if name != "<unnamed>" && name != "-" {
+        df.name = name;
+    }
+    Some(df)
+}
+
+/// Multiplier from a memory unit to GB, the unit used by [`DataflowInfo::memory`]
+fn memory_unit_in_gb(unit: &str) -> Option<f64> {
+    match unit.to_ascii_uppercase().as_str() {
+        "B" => Some(1.0 / (1024.0 * 1024.0 * 1024.0)),
+        "KB" | "KIB" => Some(1.0 / (1024.0 * 1024.0)),
+        "MB" | "MIB" => Some(1.0 / 1024.0),
+        "GB" | "GIB" => Some(1.0),
+        _ => None,
+    }
+}
+
+/// Header, separator and "nothing running" lines of the table output
+fn is_table_decoration(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    lower.starts_with("uuid")
+        || lower.ends_with(':')
+        || lower.starts_with("no ")
+        || line.chars().all(|c| matches!(c, '-' | '=' | '+' | '|' | ' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(DoraVersion::parse("dora-cli 0.3.11"), Some(DoraVersion::new(0, 3, 11)));
+        assert_eq!(DoraVersion::parse("dora v0.3.5\n"), Some(DoraVersion::new(0, 3, 5)));
+        assert_eq!(DoraVersion::parse("dora-cli 0.4.0-rc.1"), Some(DoraVersion::new(0, 4, 0)));
+        assert_eq!(DoraVersion::parse("dora 1.2"), Some(DoraVersion::new(1, 2, 0)));
+        assert_eq!(DoraVersion::parse("command not found"), None);
+    }
+
+    #[test]
+    fn test_list_format_for_version() {
+        assert_eq!(ListFormat::for_version(Some(DoraVersion::new(0, 3, 4))), ListFormat::Table);
+        assert_eq!(ListFormat::for_version(Some(JSON_LIST_SINCE)), ListFormat::Json);
+        assert_eq!(ListFormat::for_version(Some(DoraVersion::new(0, 4, 0))), ListFormat::Json);
+        assert_eq!(ListFormat::for_version(None), ListFormat::Json);
+    }
+
+    #[test]
+    fn test_parse_json_list_surfaces_errors() {
+        let ok = parse_json_list(r#"[{"uuid":"a","name":"x","status":"Running"}]"#).unwrap();
+        assert_eq!(ok.len(), 1);
+        assert!(parse_json_list("").unwrap().is_empty());
+
+        let err = parse_json_list("error: unexpected argument '--format' found").unwrap_err();
+        assert!(matches!(err, ClientError::ParseError(_)));
+
+        let err = parse_json_list("{\"uuid\":\"a\"}\nnot json").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_parse_table_list_full_columns() {
+        let output = "\
+UUID                                  Name     Status   Nodes  CPU    Memory
+019be55a-8468-7d4b-9e95-db7938985bc7  camera   Running  3      12.5%  36 MB
+019be55b-3eda-728a-996c-cb66206e95b3  <unnamed> Failed  2      0.0%   1.5 GB
+";
+        let dataflows = parse_table_list(output).unwrap();
+        assert_eq!(dataflows.len(), 2);
+        assert_eq!(dataflows[0].name, "camera");
+        assert_eq!(dataflows[0].status, DataflowStatus::Running);
+        assert_eq!(dataflows[0].nodes, 3);
+        assert_eq!(dataflows[0].cpu, 12.5);
+        assert!((dataflows[0].memory - 36.0 / 1024.0).abs() < 1e-9);
+        assert!(dataflows[1].name.is_empty());
+        assert_eq!(dataflows[1].status, DataflowStatus::Failed);
+        assert_eq!(dataflows[1].memory, 1.5);
+    }
+
+    #[test]
+    fn test_parse_table_list_numbers_in_names() {
+        let output = "\
+UUID                                  Name        Nodes  Status
+019be55a-8468-7d4b-9e95-db7938985bc7  camera 2    4      Running
+019be55b-3eda-728a-996c-cb66206e95b3  lidar 360          Running
+";
+        let dataflows = parse_table_list(output).unwrap();
+        assert_eq!(dataflows[0].name, "camera 2");
+        assert_eq!(dataflows[0].nodes, 4);
+        assert_eq!(dataflows[1].name, "lidar 360");
+        assert_eq!(dataflows[1].nodes, 0);
+
+        // Without a header there is no node column
+        let dataflows = parse_table_list("019be55a-8468-7d4b-9e95-db7938985bc7 camera 2\n").unwrap();
+        assert_eq!(dataflows[0].name, "camera 2");
+        assert_eq!(dataflows[0].nodes, 0);
+    }
+
+    #[test]
+    fn test_parse_table_list_status_words_in_names() {
+        let output = "\
+UUID                                  Name         Status
+019be55a-8468-7d4b-9e95-db7938985bc7  stopped-cam  Running
+019be55b-3eda-728a-996c-cb66206e95b3  failed over  Finished
+";
+        let dataflows = parse_table_list(output).unwrap();
+        assert_eq!(dataflows[0].name, "stopped-cam");
+        assert_eq!(dataflows[0].status, DataflowStatus::Running);
+        assert_eq!(dataflows[1].name, "failed over");
+        assert_eq!(dataflows[1].status, DataflowStatus::Finished);
+    }
+
+    #[test]
+    fn test_parse_table_list_uuid_and_name_only() {
+        let output = "Running dataflows:\n  019be55a-8468-7d4b-9e95-db7938985bc7 (camera)\n";
+        let dataflows = parse_table_list(output).unwrap();
+        assert_eq!(dataflows.len(), 1);
+        assert_eq!(dataflows[0].name, "camera");
+        assert_eq!(dataflows[0].status, DataflowStatus::Running);
+    }
+
+    #[test]
+    fn test_parse_table_list_empty_and_garbage() {
+        assert!(parse_table_list("No running dataflows\n").unwrap().is_empty());
+        assert!(parse_table_list("").unwrap().is_empty());
+        assert!(matches!(
+            parse_table_list("thread 'main' panicked at src/main.rs"),
+            Err(ClientError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_version_mismatch() {
+        let a = DoraVersion::new(0, 3, 11);
+        assert!(version_mismatch(a, a).is_none());
+        let warning = version_mismatch(a, DoraVersion::new(0, 3, 9)).unwrap();
+        assert!(warning.contains("0.3.11") && warning.contains("0.3.9"));
+    }
+}
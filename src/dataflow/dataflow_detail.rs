@@ -0,0 +1,342 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+
+use super::dataflow_table::{hex_color, status_colors};
+use super::descriptor::NodeSpec;
+use super::model::{format_utc, unix_now, DataflowInfo, DataflowStatus};
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    PANEL_BG = #ffffff
+    SECTION_BG = #f8fafc
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+
+    DetailLabel = <Label> {
+        width: Fill, height: Fit
+        draw_text: {
+            color: (TEXT_SECONDARY),
+            text_style: { font_size: 11.0 }
+            wrap: Word
+        }
+        text: ""
+    }
+
+    DetailButton = <Button> {
+        width: Fit, height: 28
+        padding: { left: 10, right: 10 }
+        draw_text: { text_style: { font_size: 11.0 } }
+    }
+
+    // One node: id and status, then path, inputs, outputs and env
+    NodeRow = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        padding: { left: 12, right: 12, top: 8, bottom: 8 }
+        spacing: 2
+        show_bg: true
+        draw_bg: { color: (SECTION_BG) }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+
+            node_id_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 12.0 }
+                }
+            }
+            node_status_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+        }
+        node_path_label = <DetailLabel> {}
+        node_io_label = <DetailLabel> {}
+        node_env_label = <DetailLabel> {}
+    }
+
+    pub DataflowDetail = {{DataflowDetail}} {
+        visible: false
+        width: Fill, height: Fit
+        flow: Down
+        margin: { top: 12 }
+        padding: 16
+        spacing: 6
+        show_bg: true
+        draw_bg: { color: (PANEL_BG) }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+
+            title_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 15.0 }
+                }
+                text: ""
+            }
+            restart_button = <DetailButton> { text: "Restart" }
+            stop_button = <DetailButton> { text: "Stop" }
+            destroy_button = <DetailButton> { text: "Kill" }
+            logs_button = <DetailButton> { text: "Logs" }
+            close_button = <DetailButton> { text: "Close" }
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+
+            uuid_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: ""
+            }
+            copy_uuid_button = <DetailButton> { height: 22, text: "Copy UUID" }
+        }
+
+        started_label = <DetailLabel> {}
+        history_label = <DetailLabel> {}
+        source_label = <DetailLabel> {}
+
+        node_list = <PortalList> {
+            width: Fill, height: 240
+            flow: Down
+            spacing: 4
+
+            NodeRow = <NodeRow> {}
+        }
+    }
+}
+
+/// Quick actions of the detail pane
+#[derive(Clone, Debug, PartialEq)]
+pub enum DetailAction {
+    Restart(String), // uuid
+    Stop(String),    // uuid
+    Destroy(String), // uuid
+    Logs(String),    // uuid
+    Close,
+}
+
+/// Everything known about one dataflow: status, history and nodes
+#[derive(Live, LiveHook, Widget)]
+pub struct DataflowDetail {
+    #[deref]
+    view: View,
+    #[rust]
+    info: Option<DataflowInfo>,
+    #[rust]
+    nodes: Vec<NodeSpec>,
+}
+
+impl Widget for DataflowDetail {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_nodes(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for DataflowDetail {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.view.button(id!(copy_uuid_button)).clicked(actions) {
+            if let Some(info) = &self.info {
+                cx.copy_to_clipboard(&info.uuid);
+                self.view.button(id!(copy_uuid_button)).set_text(cx, "Copied");
+            }
+        }
+    }
+}
+
+impl DataflowDetail {
+    /// Show a dataflow. `source` names where `nodes` came from.
+    pub fn show(
+        &mut self,
+        cx: &mut Cx,
+        info: DataflowInfo,
+        history: &[(u64, DataflowStatus)],
+        nodes: Vec<NodeSpec>,
+        source: &str,
+    ) {
+        let title = if info.name.is_empty() {
+            format!("Dataflow {}", info.uuid_short())
+        } else {
+            info.name.clone()
+        };
+        self.view.label(id!(title_label)).set_text(cx, &title);
+        self.view.label(id!(uuid_label)).set_text(cx, &info.uuid);
+        self.view.button(id!(copy_uuid_button)).set_text(cx, "Copy UUID");
+
+        let mut started = match info.started_at {
+            Some(at) => format!(
+                "{} - started {} (up {})",
+                info.status,
+                format_utc(at),
+                info.uptime_formatted(unix_now())
+            ),
+            // Older dora versions do not report start times
+            None => info.status.to_string(),
+        };
+        if let Some(reason) = &info.exit_reason {
+            started.push_str(&format!(" - {}", reason));
+        }
+        self.view.label(id!(started_label)).set_text(cx, &started);
+
+        let history = history
+            .iter()
+            .map(|(at, status)| format!("{} {}", format_utc(*at), status))
+            .collect::<Vec<_>>()
+            .join("  →  ");
+        self.view
+            .label(id!(history_label))
+            .set_text(cx, &format!("History: {}", if history.is_empty() { "-" } else { &history }));
+
+        let source = if nodes.is_empty() {
+            "Nodes unknown: the dataflow was not started from Studio and its spawn was not in the watched logs".to_string()
+        } else {
+            format!("{} nodes, from {}", nodes.len(), source)
+        };
+        self.view.label(id!(source_label)).set_text(cx, &source);
+
+        self.info = Some(info);
+        self.nodes = nodes;
+        self.view.set_visible(cx, true);
+        self.view.portal_list(id!(node_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    fn draw_nodes(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        list.set_item_range(cx, 0, self.nodes.len());
+        while let Some(item_id) = list.next_visible_item(cx) {
+            let Some(node) = self.nodes.get(item_id) else {
+                continue;
+            };
+            let item = list.item(cx, item_id, live_id!(NodeRow));
+            item.label(id!(node_id_label)).set_text(cx, &node.id);
+
+            // Per-node status from the coordinator, when it reports one
+            let entry = self
+                .info
+                .as_ref()
+                .and_then(|info| info.node_entries.iter().find(|n| n.id == node.id));
+            let status_label = item.label(id!(node_status_label));
+            match entry {
+                Some(entry) => {
+                    let mut text = entry.status.label().to_string();
+                    if let Some(reason) = &entry.exit_reason {
+                        text.push_str(&format!(" ({})", reason));
+                    }
+                    let color = status_colors(entry.status).1;
+                    status_label.apply_over(cx, live! { draw_text: { color: (color) } });
+                    status_label.set_text(cx, &text);
+                }
+                None => {
+                    let color = hex_color(0x94a3b8);
+                    status_label.apply_over(cx, live! { draw_text: { color: (color) } });
+                    status_label.set_text(cx, "");
+                }
+            }
+
+            item.label(id!(node_path_label))
+                .set_text(cx, &format!("path: {}", node.path.as_deref().unwrap_or("-")));
+            let inputs = node
+                .inputs
+                .iter()
+                .map(|(input, source)| format!("{} ← {}", input, source))
+                .collect::<Vec<_>>()
+                .join(", ");
+            item.label(id!(node_io_label)).set_text(
+                cx,
+                &format!(
+                    "inputs: {}   outputs: {}",
+                    if inputs.is_empty() { "-" } else { &inputs },
+                    if node.outputs.is_empty() { "-".to_string() } else { node.outputs.join(", ") }
+                ),
+            );
+            let env = node
+                .env
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(" ");
+            item.label(id!(node_env_label))
+                .set_text(cx, &format!("env: {}", if env.is_empty() { "-" } else { &env }));
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+}
+
+impl DataflowDetailRef {
+    /// Show a dataflow in the pane
+    pub fn show(
+        &self,
+        cx: &mut Cx,
+        info: DataflowInfo,
+        history: &[(u64, DataflowStatus)],
+        nodes: Vec<NodeSpec>,
+        source: &str,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show(cx, info, history, nodes, source);
+        }
+    }
+
+    /// Hide the pane
+    pub fn hide(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.info = None;
+            inner.view.set_visible(cx, false);
+            inner.redraw(cx);
+        }
+    }
+
+    /// UUID of the dataflow shown, if the pane is open
+    pub fn shown_uuid(&self) -> Option<String> {
+        self.borrow()?.info.as_ref().map(|info| info.uuid.clone())
+    }
+
+    /// Check if a quick action was clicked
+    pub fn action(&self, actions: &Actions) -> Option<DetailAction> {
+        let inner = self.borrow()?;
+        if inner.view.button(id!(close_button)).clicked(actions) {
+            return Some(DetailAction::Close);
+        }
+        let uuid = inner.info.as_ref()?.uuid.clone();
+        if inner.view.button(id!(restart_button)).clicked(actions) {
+            Some(DetailAction::Restart(uuid))
+        } else if inner.view.button(id!(stop_button)).clicked(actions) {
+            Some(DetailAction::Stop(uuid))
+        } else if inner.view.button(id!(destroy_button)).clicked(actions) {
+            Some(DetailAction::Destroy(uuid))
+        } else if inner.view.button(id!(logs_button)).clicked(actions) {
+            Some(DetailAction::Logs(uuid))
+        } else {
+            None
+        }
+    }
+}
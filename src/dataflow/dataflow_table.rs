@@ -1,11 +1,19 @@
 use makepad_widgets::*;
-use serde::Deserialize;
 use std::cell::RefMut;
+use std::collections::{HashMap, HashSet};
+
+use super::model::{format_cpu, format_memory, unix_now, DataflowInfo, DataflowStatus, StatusHistory};
+use super::selection::{ClickModifiers, Selection};
+use super::table_view::{SortColumn, TableView};
+use crate::metrics::series::{CPU, MEMORY};
+use crate::metrics::{RecentSamples, SparklineWidgetRefExt};
+use crate::poller::BatchCommand;
 
 live_design! {
     use link::theme::*;
     use link::shaders::*;
     use link::widgets::*;
+    use crate::metrics::sparkline::Sparkline;
 
     // Colors
     HEADER_BG = #1e3a5f
@@ -54,6 +62,43 @@ live_design! {
 
         <View> { width: Fill, height: Fit }
 
+        // Coordinator switcher
+        coordinator_dropdown = <DropDown> {
+            width: 220, height: 32
+            labels: ["local (127.0.0.1:6012)"]
+            draw_text: { text_style: { font_size: 11.0 } }
+        }
+
+        start_button = <Button> {
+            width: 80, height: 32
+            text: "Start..."
+            draw_text: { text_style: { font_size: 12.0 } }
+        }
+
+        system_logs_button = <Button> {
+            width: 110, height: 32
+            text: "System logs"
+            draw_text: { text_style: { font_size: 12.0 } }
+        }
+
+        telemetry_button = <Button> {
+            width: 100, height: 32
+            text: "Telemetry"
+            draw_text: { text_style: { font_size: 12.0 } }
+        }
+
+        nodes_button = <Button> {
+            width: 80, height: 32
+            text: "Nodes"
+            draw_text: { text_style: { font_size: 12.0 } }
+        }
+
+        alerts_button = <Button> {
+            width: 100, height: 32
+            text: "Alerts"
+            draw_text: { text_style: { font_size: 12.0 } }
+        }
+
         refresh_button = <Button> {
             width: 80, height: 32
             text: "Refresh"
@@ -61,6 +106,56 @@ live_design! {
         }
     }
 
+    // Column header that sorts the table when clicked
+    SortHeader = <ButtonFlat> {
+        height: Fit
+        padding: 0
+        align: { x: 0.0 }
+        draw_text: {
+            color: (TEXT_SECONDARY),
+            text_style: { font_size: 11.0 }
+        }
+    }
+
+    // Status toggle chip of the filter bar
+    StatusChip = <ButtonFlat> {
+        width: Fit, height: 24
+        padding: { left: 8, right: 8 }
+        draw_text: { text_style: { font_size: 10.0 } }
+    }
+
+    // Text filter and status chips
+    FilterBar = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+        padding: { left: 16, right: 16, top: 6, bottom: 6 }
+        align: { y: 0.5 }
+        spacing: 4
+
+        filter_input = <TextInput> {
+            width: 240, height: Fit
+            empty_text: "Filter by name or UUID"
+            draw_text: {
+                color: #000000
+                uniform color_hover: #000000
+                uniform color_focus: #000000
+                uniform color_down: #000000
+                uniform color_empty: #888888
+            }
+        }
+
+        <View> { width: Fill, height: Fit }
+
+        chip_pending = <StatusChip> { text: "Pending" }
+        chip_running = <StatusChip> { text: "Running" }
+        chip_finished = <StatusChip> { text: "Finished" }
+        chip_failed = <StatusChip> { text: "Failed" }
+        chip_stopped = <StatusChip> { text: "Stopped" }
+        chip_unknown = <StatusChip> { text: "Unknown" }
+    }
+
     // Table header row
     TableHeader = <View> {
         width: Fill, height: 40
@@ -79,40 +174,24 @@ live_design! {
             }
             text: "UUID"
         }
-        <Label> {
-            width: Fill, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
-            }
+        sort_name_button = <SortHeader> {
+            width: Fill
             text: "NAME"
         }
-        <Label> {
-            width: 70, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
-            }
+        sort_status_button = <SortHeader> {
+            width: 70
             text: "STATUS"
         }
-        <Label> {
-            width: 50, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
-            }
+        sort_cpu_button = <SortHeader> {
+            width: 120
             text: "CPU"
         }
-        <Label> {
-            width: 60, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
-            }
+        sort_memory_button = <SortHeader> {
+            width: 130
             text: "MEM"
         }
         <Label> {
-            width: 110, height: Fit
+            width: 170, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
                 text_style: { font_size: 11.0 }
@@ -125,15 +204,18 @@ live_design! {
     StatusBadge = <RoundedView> {
         width: Fit, height: 22
         padding: { left: 8, right: 8, top: 2, bottom: 2 }
+        align: { y: 0.5 }
+        show_bg: true
         draw_bg: {
             color: #dcfce7
+            border_radius: 11.0
         }
 
         status_text = <Label> {
             width: Fit, height: Fit
             draw_text: {
                 color: (STATUS_RUNNING),
-                text_style: { font_size: 11.0 }
+                text_style: { font_size: 10.0 }
             }
         }
     }
@@ -145,10 +227,47 @@ live_design! {
         padding: { left: 6, right: 6 }
     }
 
+    // Shown while rows are selected, and after a batch with its summary
+    SelectionToolbar = <View> {
+        visible: false
+        width: Fill, height: Fit
+        flow: Right
+        show_bg: true
+        draw_bg: { color: #eff6ff }
+        padding: { left: 16, right: 16, top: 6, bottom: 6 }
+        align: { y: 0.5 }
+        spacing: 8
+
+        selection_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: ""
+        }
+        batch_restart_button = <ActionButton> { width: 60, text: "Restart" }
+        batch_stop_button = <ActionButton> { text: "Stop" }
+        batch_destroy_button = <ActionButton> { text: "Kill" }
+        batch_logs_button = <ActionButton> { width: 70, text: "View logs" }
+        clear_selection_button = <ActionButton> { width: 60, text: "Clear" }
+
+        batch_summary_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+                wrap: Word
+            }
+            text: ""
+        }
+    }
+
     // Table data row
     TableRow = <View> {
         width: Fill, height: 48
         flow: Right
+        cursor: Hand
         show_bg: true
         draw_bg: { color: (ROW_BG) }
         padding: { left: 16, right: 16 }
@@ -169,35 +288,53 @@ live_design! {
                 text_style: { font_size: 12.0 }
             }
         }
-        status_label = <Label> {
+        <View> {
             width: 70, height: Fit
-            draw_text: {
-                color: (STATUS_RUNNING),
-                text_style: { font_size: 12.0 }
-            }
+            status_badge = <StatusBadge> {}
         }
-        cpu_label = <Label> {
-            width: 50, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
+        // Current value and the recent history
+        <View> {
+            width: 120, height: Fit
+            flow: Right
+            spacing: 6
+            align: { y: 0.5 }
+            cpu_label = <Label> {
+                width: 50, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
             }
+            cpu_sparkline = <Sparkline> {}
         }
-        memory_label = <Label> {
-            width: 60, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
+        <View> {
+            width: 130, height: Fit
+            flow: Right
+            spacing: 6
+            align: { y: 0.5 }
+            memory_label = <Label> {
+                width: 60, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+            memory_sparkline = <Sparkline> {
+                draw_bar: { color: #8b5cf6 }
             }
         }
 
         // Action buttons container
         actions = <View> {
-            width: 110, height: Fit
+            width: 170, height: Fit
             flow: Right
             align: { x: 1.0, y: 0.5 }
             spacing: 4
 
+            restart_button = <ActionButton> {
+                width: 60
+                text: "Restart"
+            }
             stop_button = <ActionButton> {
                 text: "Stop"
             }
@@ -211,6 +348,7 @@ live_design! {
     TableRowAlt = <View> {
         width: Fill, height: 48
         flow: Right
+        cursor: Hand
         show_bg: true
         draw_bg: { color: (ROW_ALT_BG) }
         padding: { left: 16, right: 16 }
@@ -231,35 +369,53 @@ live_design! {
                 text_style: { font_size: 12.0 }
             }
         }
-        status_label = <Label> {
+        <View> {
             width: 70, height: Fit
-            draw_text: {
-                color: (STATUS_RUNNING),
-                text_style: { font_size: 12.0 }
-            }
+            status_badge = <StatusBadge> {}
         }
-        cpu_label = <Label> {
-            width: 50, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
+        // Current value and the recent history
+        <View> {
+            width: 120, height: Fit
+            flow: Right
+            spacing: 6
+            align: { y: 0.5 }
+            cpu_label = <Label> {
+                width: 50, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
             }
+            cpu_sparkline = <Sparkline> {}
         }
-        memory_label = <Label> {
-            width: 60, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
+        <View> {
+            width: 130, height: Fit
+            flow: Right
+            spacing: 6
+            align: { y: 0.5 }
+            memory_label = <Label> {
+                width: 60, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+            memory_sparkline = <Sparkline> {
+                draw_bar: { color: #8b5cf6 }
             }
         }
 
         // Action buttons container
         actions = <View> {
-            width: 110, height: Fit
+            width: 170, height: Fit
             flow: Right
             align: { x: 1.0, y: 0.5 }
             spacing: 4
 
+            restart_button = <ActionButton> {
+                width: 60
+                text: "Restart"
+            }
             stop_button = <ActionButton> {
                 text: "Stop"
             }
@@ -277,7 +433,7 @@ live_design! {
         show_bg: true
         draw_bg: { color: (ROW_BG) }
 
-        <Label> {
+        empty_title = <Label> {
             width: Fit, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
@@ -285,7 +441,7 @@ live_design! {
             }
             text: "No dataflows running"
         }
-        <Label> {
+        empty_hint = <Label> {
             width: Fit, height: Fit
             margin: { top: 8 }
             draw_text: {
@@ -314,6 +470,35 @@ live_design! {
         }
     }
 
+    // Error state view, shown instead of the rows when listing failed
+    ErrorState = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        align: { x: 0.5, y: 0.5 }
+        padding: { top: 24, bottom: 24, left: 16, right: 16 }
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+
+        <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (STATUS_FAILED),
+                text_style: { font_size: 14.0 }
+            }
+            text: "Could not list dataflows"
+        }
+        error_label = <Label> {
+            width: Fill, height: Fit
+            margin: { top: 8 }
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+                wrap: Word
+            }
+            text: ""
+        }
+    }
+
     pub DataflowTable = {{DataflowTable}} {
         width: Fill, height: Fit
         flow: Down
@@ -322,6 +507,10 @@ live_design! {
         <TableTitleBar> {}
 
         // Header
+        <FilterBar> {}
+
+        selection_toolbar = <SelectionToolbar> {}
+
         <TableHeader> {}
 
         // Data rows via PortalList
@@ -333,82 +522,22 @@ live_design! {
             TableRowAlt = <TableRowAlt> {}
             EmptyState = <EmptyState> {}
             LoadingState = <LoadingState> {}
+            ErrorState = <ErrorState> {}
         }
     }
 }
 
-/// Dataflow information from dora list command
-#[derive(Debug, Clone, Deserialize, Default)]
-pub struct DataflowInfo {
-    #[serde(default)]
-    pub uuid: String,
-    #[serde(default)]
-    pub name: String,
-    #[serde(default)]
-    pub status: String,
-    #[serde(default)]
-    pub nodes: u32,
-    #[serde(default)]
-    pub cpu: f64,
-    #[serde(default)]
-    pub memory: f64,
-}
-
-impl DataflowInfo {
-    /// Parse NDJSON (newline-delimited JSON) into a vector of DataflowInfo
-    pub fn parse_ndjson(input: &str) -> Vec<Self> {
-        input
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .filter_map(|line| serde_json::from_str(line).ok())
-            .collect()
-    }
-
-    /// Parse JSON array into a vector of DataflowInfo
-    pub fn parse_json_array(input: &str) -> Vec<Self> {
-        serde_json::from_str(input).unwrap_or_default()
-    }
-
-    /// Format memory in human-readable format
-    pub fn memory_formatted(&self) -> String {
-        if self.memory < 0.001 {
-            "0 B".to_string()
-        } else if self.memory < 1.0 {
-            format!("{:.0} MB", self.memory * 1024.0)
-        } else {
-            format!("{:.2} GB", self.memory)
-        }
-    }
-
-    /// Format CPU percentage
-    pub fn cpu_formatted(&self) -> String {
-        format!("{:.1}%", self.cpu)
-    }
-
-    /// Get short UUID (first 8 characters)
-    pub fn uuid_short(&self) -> String {
-        if self.uuid.len() > 8 {
-            format!("{}...", &self.uuid[..8])
-        } else {
-            self.uuid.clone()
-        }
-    }
-
-    /// Check if dataflow is running
-    pub fn is_running(&self) -> bool {
-        self.status.to_lowercase() == "running"
-    }
-}
-
 /// Actions emitted by the DataflowTable
 #[derive(Clone, Debug, DefaultNone)]
 pub enum DataflowTableAction {
     None,
     Refresh,
+    Restart(String),  // uuid
     Stop(String),     // uuid
     Destroy(String),  // uuid
     ViewLogs(String), // uuid
     SelectRow(usize), // row index
+    ViewChanged(TableView),
 }
 
 /// Loading state for the table
@@ -424,14 +553,34 @@ pub enum TableLoadingState {
 pub struct DataflowTable {
     #[deref]
     view: View,
+    /// Everything the coordinator listed
+    #[rust]
+    all_dataflows: Vec<DataflowInfo>,
+    /// The rows shown: `all_dataflows` filtered and sorted by `table_view`
     #[rust]
     dataflows: Vec<DataflowInfo>,
     #[rust]
+    table_view: TableView,
+    #[rust]
+    status_history: StatusHistory,
+    #[rust]
     loading_state: TableLoadingState,
     #[rust]
-    selected_row: Option<usize>,
+    selection: Selection,
+    /// Outcome of the last batch command, shown in the selection toolbar
+    #[rust]
+    batch_summary: String,
     #[rust]
     error_message: String,
+    /// UUID of the row to highlight, e.g. a freshly started dataflow
+    #[rust]
+    highlighted: Option<String>,
+    /// Recent CPU and memory values of each dataflow, for the sparklines
+    #[rust]
+    sparklines: HashMap<String, (Vec<f64>, Vec<f64>)>,
+    /// UUIDs of the dataflows started from Studio, which can be restarted
+    #[rust]
+    restartable: HashSet<String>,
 }
 
 impl Widget for DataflowTable {
@@ -470,12 +619,56 @@ impl WidgetMatchEvent for DataflowTable {
             cx.widget_action(self.widget_uid(), &scope.path, DataflowTableAction::Refresh);
         }
 
-        // Handle row action buttons via PortalList
+        for (button, column, _) in SORT_HEADERS {
+            if self.view.button(button).clicked(actions) {
+                self.change_table_view(cx, scope, |view| view.toggle_sort(column));
+            }
+        }
+        for (button, status) in STATUS_CHIPS {
+            if self.view.button(button).clicked(actions) {
+                self.change_table_view(cx, scope, |view| view.toggle_status(status));
+            }
+        }
+        if let Some(filter) = self.view.text_input(id!(filter_input)).changed(actions) {
+            self.change_table_view(cx, scope, |view| view.filter = filter);
+        }
+
+        if self.view.button(id!(clear_selection_button)).clicked(actions) {
+            self.selection.clear();
+            self.batch_summary.clear();
+            self.update_selection_toolbar(cx);
+        }
+
+        // Handle row clicks and row action buttons via PortalList
         let table_list = self.view.portal_list(id!(table_list));
         for (item_id, item) in table_list.items_with_actions(actions) {
             if item_id < self.dataflows.len() {
                 let uuid = self.dataflows[item_id].uuid.clone();
 
+                // Buttons capture their own clicks, so this is a click on the row itself
+                if let Some(fe) = item.as_view().finger_down(actions) {
+                    let modifiers = ClickModifiers {
+                        extend: fe.modifiers.shift,
+                        toggle: fe.modifiers.control || fe.modifiers.logo,
+                    };
+                    let rows: Vec<&str> = self.dataflows.iter().map(|df| df.uuid.as_str()).collect();
+                    self.selection.click(&rows, item_id, modifiers);
+                    self.update_selection_toolbar(cx);
+                    cx.widget_action(
+                        self.widget_uid(),
+                        &scope.path,
+                        DataflowTableAction::SelectRow(item_id),
+                    );
+                }
+
+                if item.button(id!(restart_button)).clicked(actions) {
+                    cx.widget_action(
+                        self.widget_uid(),
+                        &scope.path,
+                        DataflowTableAction::Restart(uuid.clone()),
+                    );
+                }
+
                 if item.button(id!(stop_button)).clicked(actions) {
                     cx.widget_action(
                         self.widget_uid(),
@@ -505,33 +698,102 @@ impl WidgetMatchEvent for DataflowTable {
 }
 
 impl DataflowTable {
-    /// Set the dataflows to display
-    pub fn set_dataflows(&mut self, cx: &mut Cx, dataflows: Vec<DataflowInfo>) {
+    /// Set the dataflows to display, with the newest values of their series
+    pub fn set_dataflows(&mut self, cx: &mut Cx, dataflows: Vec<DataflowInfo>, recent: &RecentSamples) {
         log!("[DataflowTable] set_dataflows: {} items", dataflows.len());
-        self.dataflows = dataflows;
+
+        self.status_history.observe(&dataflows, unix_now());
+        self.restartable = crate::launches::recorded(dataflows.iter().map(|df| df.uuid.as_str()));
+        self.all_dataflows = dataflows;
+        self.update_sparklines(recent);
         self.loading_state = TableLoadingState::Idle;
-        log!("[DataflowTable] calling redraw");
-        // Redraw the PortalList specifically to ensure it updates
+        self.apply_table_view(cx);
+    }
+
+    /// Set the newest values of the dataflows' series, for the sparklines
+    pub fn set_sparklines(&mut self, cx: &mut Cx, recent: &RecentSamples) {
+        self.update_sparklines(recent);
         self.view.portal_list(id!(table_list)).redraw(cx);
-        self.redraw(cx);
+    }
+
+    fn update_sparklines(&mut self, recent: &RecentSamples) {
+        self.sparklines = self
+            .all_dataflows
+            .iter()
+            .map(|df| {
+                let cpu = recent.values(&df.uuid, None, CPU);
+                let memory = recent.values(&df.uuid, None, MEMORY);
+                (df.uuid.clone(), (cpu, memory))
+            })
+            .collect();
     }
 
     /// Parse and set dataflows from NDJSON string
     pub fn set_from_ndjson(&mut self, cx: &mut Cx, ndjson: &str) {
-        self.dataflows = DataflowInfo::parse_ndjson(ndjson);
+        self.all_dataflows = DataflowInfo::parse_ndjson(ndjson);
         self.loading_state = TableLoadingState::Idle;
-        self.view.portal_list(id!(table_list)).redraw(cx);
-        self.redraw(cx);
+        self.apply_table_view(cx);
     }
 
     /// Parse and set dataflows from JSON array string
     pub fn set_from_json(&mut self, cx: &mut Cx, json: &str) {
-        self.dataflows = DataflowInfo::parse_json_array(json);
+        self.all_dataflows = DataflowInfo::parse_json_array(json);
         self.loading_state = TableLoadingState::Idle;
+        self.apply_table_view(cx);
+    }
+
+    /// Recompute the shown rows from all dataflows and the sort/filter state
+    fn apply_table_view(&mut self, cx: &mut Cx) {
+        self.dataflows = self.table_view.apply(&self.all_dataflows);
+        // Rows hidden by the filter stay selected, so only drop vanished ones
+        let all: Vec<&str> = self.all_dataflows.iter().map(|df| df.uuid.as_str()).collect();
+        self.selection.retain(&all);
+        self.update_selection_toolbar(cx);
+        log!("[DataflowTable] showing {} of {} dataflows", self.dataflows.len(), self.all_dataflows.len());
+        // Redraw the PortalList specifically to ensure it updates
         self.view.portal_list(id!(table_list)).redraw(cx);
         self.redraw(cx);
     }
 
+    /// Set the sort order and filters, e.g. from the settings
+    pub fn set_table_view(&mut self, cx: &mut Cx, table_view: TableView) {
+        self.view.text_input(id!(filter_input)).set_text(cx, &table_view.filter);
+        self.table_view = table_view;
+        self.update_table_view_controls(cx);
+        self.apply_table_view(cx);
+    }
+
+    /// Header arrows and chip colors for the current sort/filter state
+    fn update_table_view_controls(&mut self, cx: &mut Cx) {
+        for (button, column, text) in SORT_HEADERS {
+            self.view
+                .button(button)
+                .set_text(cx, &self.table_view.header_label(column, text));
+        }
+        for (button, status) in STATUS_CHIPS {
+            let color = if self.table_view.shows_status(status) {
+                status_colors(status).1
+            } else {
+                hex_color(0xcbd5e1)
+            };
+            self.view
+                .button(button)
+                .apply_over(cx, live! { draw_text: { color: (color) } });
+        }
+    }
+
+    /// Apply a change made with the header, chips or filter box and report it
+    fn change_table_view(&mut self, cx: &mut Cx, scope: &mut Scope, change: impl FnOnce(&mut TableView)) {
+        change(&mut self.table_view);
+        self.update_table_view_controls(cx);
+        self.apply_table_view(cx);
+        cx.widget_action(
+            self.widget_uid(),
+            &scope.path,
+            DataflowTableAction::ViewChanged(self.table_view.clone()),
+        );
+    }
+
     /// Set loading state
     pub fn set_loading(&mut self, cx: &mut Cx) {
         self.loading_state = TableLoadingState::Loading;
@@ -557,15 +819,62 @@ impl DataflowTable {
         self.dataflows.get(index)
     }
 
-    /// Get dataflow by UUID
+    /// Get dataflow by UUID, including rows hidden by the filter
     pub fn get_dataflow_by_uuid(&self, uuid: &str) -> Option<&DataflowInfo> {
-        self.dataflows.iter().find(|df| df.uuid == uuid)
+        self.all_dataflows.iter().find(|df| df.uuid == uuid)
+    }
+
+    fn row_ids(&self) -> Vec<&str> {
+        self.dataflows.iter().map(|df| df.uuid.as_str()).collect()
+    }
+
+    /// UUIDs of the selected rows, in table order
+    pub fn selected_uuids(&self) -> Vec<String> {
+        self.selection.uuids(&self.row_ids())
+    }
+
+    /// Show the outcome of a batch command in the selection toolbar
+    pub fn set_batch_summary(&mut self, cx: &mut Cx, summary: &str) {
+        self.batch_summary = summary.to_string();
+        self.update_selection_toolbar(cx);
+    }
+
+    fn update_selection_toolbar(&mut self, cx: &mut Cx) {
+        // Batch commands only act on the rows the filter shows
+        let count = self.selected_uuids().len();
+        let toolbar = self.view.view(id!(selection_toolbar));
+        toolbar.set_visible(cx, count > 0 || !self.batch_summary.is_empty());
+        self.view
+            .label(id!(selection_label))
+            .set_text(cx, &format!("{} selected", count));
+        for button in [
+            id!(batch_restart_button),
+            id!(batch_stop_button),
+            id!(batch_destroy_button),
+            id!(batch_logs_button),
+        ] {
+            self.view.button(button).set_enabled(cx, count > 0);
+        }
+        self.view
+            .label(id!(batch_summary_label))
+            .set_text(cx, &self.batch_summary);
+        self.view.portal_list(id!(table_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Highlight the row of `uuid`, once it is listed
+    pub fn highlight(&mut self, cx: &mut Cx, uuid: Option<&str>) {
+        self.highlighted = uuid.map(str::to_string);
+        self.view.portal_list(id!(table_list)).redraw(cx);
     }
 
     /// Clear all dataflows
     pub fn clear(&mut self, cx: &mut Cx) {
+        self.all_dataflows.clear();
         self.dataflows.clear();
-        self.selected_row = None;
+        self.selection.clear();
+        self.batch_summary.clear();
+        self.update_selection_toolbar(cx);
         self.loading_state = TableLoadingState::Idle;
         self.view.portal_list(id!(table_list)).redraw(cx);
         self.redraw(cx);
@@ -591,13 +900,33 @@ impl DataflowTable {
             return;
         }
 
+        // Show the error instead of an empty table
+        if self.loading_state == TableLoadingState::Error {
+            list.set_item_range(cx, 0, 1);
+            while let Some(item_id) = list.next_visible_item(cx) {
+                if item_id == 0 {
+                    let item = list.item(cx, item_id, live_id!(ErrorState));
+                    item.label(id!(error_label)).set_text(cx, &self.error_message);
+                    item.draw_all(cx, &mut Scope::empty());
+                }
+            }
+            return;
+        }
+
         // Show empty state if no dataflows
         if self.dataflows.is_empty() {
             log!("[DataflowTable] showing empty state");
+            let (title, hint) = if self.all_dataflows.is_empty() {
+                ("No dataflows running", "Start a dataflow to see it here")
+            } else {
+                ("No dataflows match the filter", "Clear the filter or enable more statuses")
+            };
             list.set_item_range(cx, 0, 1);
             while let Some(item_id) = list.next_visible_item(cx) {
                 if item_id == 0 {
                     let item = list.item(cx, item_id, live_id!(EmptyState));
+                    item.label(id!(empty_title)).set_text(cx, title);
+                    item.label(id!(empty_hint)).set_text(cx, hint);
                     item.draw_all(cx, &mut Scope::empty());
                 }
             }
@@ -621,13 +950,36 @@ impl DataflowTable {
 
                 let item = list.item(cx, item_id, template);
 
+                // Rows are reused, so the background is set every time
+                let row_bg = if self.selection.contains(&df.uuid) {
+                    hex_color(0xbfdbfe)
+                } else if self.highlighted.as_deref() == Some(df.uuid.as_str()) {
+                    hex_color(0xdcfce7)
+                } else if item_id % 2 == 0 {
+                    hex_color(0xffffff)
+                } else {
+                    hex_color(0xf8fafc)
+                };
+                item.apply_over(cx, live! { draw_bg: { color: (row_bg) } });
+
                 // Set row data
                 item.label(id!(uuid_label)).set_text(cx, &df.uuid_short());
                 item.label(id!(name_label)).set_text(cx, &df.name);
-                item.label(id!(status_label)).set_text(cx, &df.status);
+                let (badge_bg, badge_text) = status_colors(df.status);
+                let badge = item.view(id!(status_badge));
+                badge.apply_over(cx, live! { draw_bg: { color: (badge_bg) } });
+                let status_text = badge.label(id!(status_text));
+                status_text.apply_over(cx, live! { draw_text: { color: (badge_text) } });
+                status_text.set_text(cx, df.status.label());
                 item.label(id!(cpu_label)).set_text(cx, &df.cpu_formatted());
                 item.label(id!(memory_label))
                     .set_text(cx, &df.memory_formatted());
+                let (cpu, memory) = self.sparklines.get(&df.uuid).cloned().unwrap_or_default();
+                item.sparkline(id!(cpu_sparkline)).set_values(cx, cpu, format_cpu);
+                item.sparkline(id!(memory_sparkline)).set_values(cx, memory, format_memory);
+                // Only dataflows started from Studio have a YAML to restart
+                item.button(id!(restart_button))
+                    .set_visible(cx, self.restartable.contains(&df.uuid));
 
                 log!(
                     "[DataflowTable] Drawing row {}: uuid={}, name={}, status={}, cpu={}, mem={}",
@@ -645,21 +997,68 @@ impl DataflowTable {
     }
 }
 
+/// Sortable column headers with their label text
+const SORT_HEADERS: [(&[LiveId], SortColumn, &str); 4] = [
+    (id!(sort_name_button), SortColumn::Name, "NAME"),
+    (id!(sort_status_button), SortColumn::Status, "STATUS"),
+    (id!(sort_cpu_button), SortColumn::Cpu, "CPU"),
+    (id!(sort_memory_button), SortColumn::Memory, "MEM"),
+];
+
+/// Status chips of the filter bar
+const STATUS_CHIPS: [(&[LiveId], DataflowStatus); 6] = [
+    (id!(chip_pending), DataflowStatus::Pending),
+    (id!(chip_running), DataflowStatus::Running),
+    (id!(chip_finished), DataflowStatus::Finished),
+    (id!(chip_failed), DataflowStatus::Failed),
+    (id!(chip_stopped), DataflowStatus::Stopped),
+    (id!(chip_unknown), DataflowStatus::Unknown),
+];
+
+/// Badge background and text colors for a status, matching the STATUS_* colors above
+pub(super) fn status_colors(status: DataflowStatus) -> (Vec4, Vec4) {
+    let (bg, text) = match status {
+        DataflowStatus::Running => (0xdcfce7, 0x22c55e),
+        DataflowStatus::Finished => (0xdbeafe, 0x3b82f6),
+        DataflowStatus::Failed => (0xfee2e2, 0xef4444),
+        DataflowStatus::Stopped => (0xfef3c7, 0xf59e0b),
+        DataflowStatus::Pending => (0xede9fe, 0x8b5cf6),
+        DataflowStatus::Unknown => (0xf1f5f9, 0x64748b),
+    };
+    (hex_color(bg), hex_color(text))
+}
+
+pub(crate) fn hex_color(rgb: u32) -> Vec4 {
+    vec4(
+        ((rgb >> 16) & 0xff) as f32 / 255.0,
+        ((rgb >> 8) & 0xff) as f32 / 255.0,
+        (rgb & 0xff) as f32 / 255.0,
+        1.0,
+    )
+}
+
 impl DataflowTableRef {
-    /// Set the dataflows to display
-    pub fn set_dataflows(&self, cx: &mut Cx, dataflows: Vec<DataflowInfo>) {
+    /// Set the dataflows to display, with the newest values of their series
+    pub fn set_dataflows(&self, cx: &mut Cx, dataflows: Vec<DataflowInfo>, recent: &RecentSamples) {
         log!(
             "[DataflowTableRef] set_dataflows called with {} items",
             dataflows.len()
         );
         if let Some(mut inner) = self.borrow_mut() {
             log!("[DataflowTableRef] borrow_mut succeeded, setting dataflows");
-            inner.set_dataflows(cx, dataflows);
+            inner.set_dataflows(cx, dataflows, recent);
         } else {
             log!("[DataflowTableRef] borrow_mut returned None!");
         }
     }
 
+    /// Set the newest values of the dataflows' series, for the sparklines
+    pub fn set_sparklines(&self, cx: &mut Cx, recent: &RecentSamples) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_sparklines(cx, recent);
+        }
+    }
+
     /// Parse and set dataflows from NDJSON string
     pub fn set_from_ndjson(&self, cx: &mut Cx, ndjson: &str) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -695,6 +1094,31 @@ impl DataflowTableRef {
         }
     }
 
+    /// Highlight the row of `uuid`, `None` removes the highlight
+    pub fn highlight(&self, cx: &mut Cx, uuid: Option<&str>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.highlight(cx, uuid);
+        }
+    }
+
+    /// Fill the coordinator switcher and select an entry
+    pub fn set_coordinators(&self, cx: &mut Cx, labels: Vec<String>, selected: usize) {
+        if let Some(inner) = self.borrow() {
+            let dropdown = inner.view.drop_down(id!(coordinator_dropdown));
+            dropdown.set_labels(cx, labels);
+            dropdown.set_selected_item(cx, selected);
+        }
+    }
+
+    /// Check if another coordinator was picked, returns its index if so
+    pub fn coordinator_changed(&self, actions: &Actions) -> Option<usize> {
+        if let Some(inner) = self.borrow() {
+            inner.view.drop_down(id!(coordinator_dropdown)).selected(actions)
+        } else {
+            None
+        }
+    }
+
     /// Check if a DataflowTableAction was triggered
     pub fn action(&self, actions: &Actions) -> Option<DataflowTableAction> {
         if let Some(item) = actions.find_widget_action(self.widget_uid()) {
@@ -713,6 +1137,173 @@ impl DataflowTableRef {
         }
     }
 
+    /// Set the sort order and filters
+    pub fn set_table_view(&self, cx: &mut Cx, table_view: TableView) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_table_view(cx, table_view);
+        }
+    }
+
+    /// Check if the sort order or filters were changed, returns the new state if so
+    pub fn table_view_changed(&self, actions: &Actions) -> Option<TableView> {
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == self.widget_uid())
+            .filter_map(|action| match action.cast() {
+                DataflowTableAction::ViewChanged(table_view) => Some(table_view),
+                _ => None,
+            })
+            .last()
+    }
+
+    /// Check if a row was clicked, returns its UUID if so
+    pub fn row_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == self.widget_uid())
+            .filter_map(|action| match action.cast() {
+                DataflowTableAction::SelectRow(index) => inner.dataflows.get(index).map(|df| df.uuid.clone()),
+                _ => None,
+            })
+            .last()
+    }
+
+    /// Current info of a dataflow, including rows hidden by the filter
+    pub fn dataflow(&self, uuid: &str) -> Option<DataflowInfo> {
+        self.borrow()?.get_dataflow_by_uuid(uuid).cloned()
+    }
+
+    /// Status changes seen for a dataflow while Studio was running
+    pub fn status_history(&self, uuid: &str) -> Vec<(u64, DataflowStatus)> {
+        self.borrow()
+            .map(|inner| inner.status_history.get(uuid).to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Check if the start button was clicked
+    pub fn start_clicked(&self, actions: &Actions) -> bool {
+        if let Some(inner) = self.borrow() {
+            inner.view.button(id!(start_button)).clicked(actions)
+        } else {
+            false
+        }
+    }
+
+    /// Check if the system logs button was clicked
+    pub fn system_logs_clicked(&self, actions: &Actions) -> bool {
+        if let Some(inner) = self.borrow() {
+            inner.view.button(id!(system_logs_button)).clicked(actions)
+        } else {
+            false
+        }
+    }
+
+    /// Check if the telemetry button was clicked
+    pub fn telemetry_clicked(&self, actions: &Actions) -> bool {
+        if let Some(inner) = self.borrow() {
+            inner.view.button(id!(telemetry_button)).clicked(actions)
+        } else {
+            false
+        }
+    }
+
+    /// Check if the nodes button was clicked
+    pub fn nodes_clicked(&self, actions: &Actions) -> bool {
+        if let Some(inner) = self.borrow() {
+            inner.view.button(id!(nodes_button)).clicked(actions)
+        } else {
+            false
+        }
+    }
+
+    /// Check if the alerts button was clicked
+    pub fn alerts_clicked(&self, actions: &Actions) -> bool {
+        if let Some(inner) = self.borrow() {
+            inner.view.button(id!(alerts_button)).clicked(actions)
+        } else {
+            false
+        }
+    }
+
+    /// Show how many alerts are firing on the alerts button
+    pub fn set_alert_count(&self, cx: &mut Cx, firing: usize) {
+        if let Some(inner) = self.borrow() {
+            let text = match firing {
+                0 => "Alerts".to_string(),
+                n => format!("Alerts ({})", n),
+            };
+            inner.view.button(id!(alerts_button)).set_text(cx, &text);
+        }
+    }
+
+    /// UUID and label of every dataflow listed, for the telemetry dashboard and node list
+    pub fn dataflow_labels(&self) -> Vec<(String, String)> {
+        self.borrow()
+            .map(|inner| {
+                inner
+                    .all_dataflows
+                    .iter()
+                    .map(|df| {
+                        let label = if df.name.is_empty() { df.uuid_short() } else { df.name.clone() };
+                        (df.uuid.clone(), label)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Check if a selection toolbar button was clicked, returns the command
+    /// and the selected UUIDs if so
+    pub fn batch_clicked(&self, actions: &Actions) -> Option<(BatchCommand, Vec<String>)> {
+        let inner = self.borrow()?;
+        let command = if inner.view.button(id!(batch_restart_button)).clicked(actions) {
+            BatchCommand::Restart
+        } else if inner.view.button(id!(batch_stop_button)).clicked(actions) {
+            BatchCommand::Stop
+        } else if inner.view.button(id!(batch_destroy_button)).clicked(actions) {
+            BatchCommand::Destroy
+        } else {
+            return None;
+        };
+        let uuids = inner.selected_uuids();
+        (!uuids.is_empty()).then_some((command, uuids))
+    }
+
+    /// Check if "View logs" was clicked, returns the selected UUIDs if so
+    pub fn logs_selected(&self, actions: &Actions) -> Option<Vec<String>> {
+        let inner = self.borrow()?;
+        if !inner.view.button(id!(batch_logs_button)).clicked(actions) {
+            return None;
+        }
+        let uuids = inner.selected_uuids();
+        (!uuids.is_empty()).then_some(uuids)
+    }
+
+    /// Show the outcome of a batch command
+    pub fn set_batch_summary(&self, cx: &mut Cx, summary: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_batch_summary(cx, summary);
+        }
+    }
+
+    /// Check if a restart button was clicked, returns the UUID if so
+    pub fn restart_clicked(&self, actions: &Actions) -> Option<String> {
+        if let Some(inner) = self.borrow() {
+            let table_list = inner.view.portal_list(id!(table_list));
+            for (item_id, item) in table_list.items_with_actions(actions) {
+                if item_id < inner.dataflows.len() {
+                    if item.button(id!(restart_button)).clicked(actions) {
+                        return Some(inner.dataflows[item_id].uuid.clone());
+                    }
+                }
+            }
+        }
+        None
+    }
+
     /// Check if a stop button was clicked, returns the UUID if so
     pub fn stop_clicked(&self, actions: &Actions) -> Option<String> {
         if let Some(inner) = self.borrow() {
@@ -763,144 +1354,19 @@ impl DataflowTableRef {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_parse_ndjson() {
-        let input = r#"{"uuid":"abc","name":"test","status":"Running","nodes":3,"cpu":0.5,"memory":0.036}
-{"uuid":"def","name":"test2","status":"Failed","nodes":0,"cpu":0.0,"memory":0.0}"#;
-
-        let dataflows = DataflowInfo::parse_ndjson(input);
-        assert_eq!(dataflows.len(), 2);
-        assert_eq!(dataflows[0].name, "test");
-        assert_eq!(dataflows[0].status, "Running");
-        assert_eq!(dataflows[1].status, "Failed");
-    }
-
-    #[test]
-    fn test_parse_json_array() {
-        let input = r#"[
-            {"uuid":"abc123","name":"dataflow1","status":"Running","nodes":3,"cpu":25.5,"memory":0.5},
-            {"uuid":"def456","name":"dataflow2","status":"Stopped","nodes":0,"cpu":0.0,"memory":0.0}
-        ]"#;
-
-        let dataflows = DataflowInfo::parse_json_array(input);
-        assert_eq!(dataflows.len(), 2);
-        assert_eq!(dataflows[0].uuid, "abc123");
-        assert_eq!(dataflows[0].name, "dataflow1");
-        assert!(dataflows[0].is_running());
-        assert!(!dataflows[1].is_running());
-    }
-
-    #[test]
-    fn test_parse_json_array_empty() {
-        let input = "[]";
-        let dataflows = DataflowInfo::parse_json_array(input);
-        assert!(dataflows.is_empty());
-    }
-
-    #[test]
-    fn test_parse_json_array_invalid() {
-        let input = "invalid json";
-        let dataflows = DataflowInfo::parse_json_array(input);
-        assert!(dataflows.is_empty());
-    }
-
-    #[test]
-    fn test_memory_formatted() {
-        let df = DataflowInfo {
-            uuid: "test".to_string(),
-            name: "test".to_string(),
-            status: "Running".to_string(),
-            nodes: 1,
-            cpu: 0.0,
-            memory: 0.036,
-        };
-        assert_eq!(df.memory_formatted(), "37 MB");
-
-        let df2 = DataflowInfo {
-            memory: 1.5,
-            ..df.clone()
-        };
-        assert_eq!(df2.memory_formatted(), "1.50 GB");
-
-        let df3 = DataflowInfo {
-            memory: 0.0,
-            ..df.clone()
-        };
-        assert_eq!(df3.memory_formatted(), "0 B");
-    }
-
-    #[test]
-    fn test_cpu_formatted() {
-        let df = DataflowInfo {
-            uuid: "test".to_string(),
-            name: "test".to_string(),
-            status: "Running".to_string(),
-            nodes: 1,
-            cpu: 45.678,
-            memory: 0.0,
-        };
-        assert_eq!(df.cpu_formatted(), "45.7%");
-    }
-
-    #[test]
-    fn test_uuid_short() {
-        let df = DataflowInfo {
-            uuid: "abc123def456789".to_string(),
-            name: "test".to_string(),
-            status: "Running".to_string(),
-            nodes: 1,
-            cpu: 0.0,
-            memory: 0.0,
-        };
-        assert_eq!(df.uuid_short(), "abc123de...");
-
-        let df2 = DataflowInfo {
-            uuid: "short".to_string(),
-            ..df.clone()
-        };
-        assert_eq!(df2.uuid_short(), "short");
-    }
-
-    #[test]
-    fn test_is_running() {
-        let running = DataflowInfo {
-            uuid: "1".to_string(),
-            name: "test".to_string(),
-            status: "Running".to_string(),
-            nodes: 1,
-            cpu: 0.0,
-            memory: 0.0,
-        };
-        assert!(running.is_running());
-
-        let stopped = DataflowInfo {
-            status: "Stopped".to_string(),
-            ..running.clone()
-        };
-        assert!(!stopped.is_running());
-
-        // Case insensitive
-        let running_lower = DataflowInfo {
-            status: "running".to_string(),
-            ..running.clone()
-        };
-        assert!(running_lower.is_running());
-    }
-
-    #[test]
-    fn test_dataflow_info_default() {
-        let df = DataflowInfo::default();
-        assert!(df.uuid.is_empty());
-        assert!(df.name.is_empty());
-        assert!(df.status.is_empty());
-        assert_eq!(df.nodes, 0);
-        assert_eq!(df.cpu, 0.0);
-        assert_eq!(df.memory, 0.0);
-    }
-
     #[test]
     fn test_loading_state_default() {
         let state = TableLoadingState::default();
         assert_eq!(state, TableLoadingState::Idle);
     }
+
+    #[test]
+    fn test_status_colors_are_distinct() {
+        let colors: Vec<_> = DataflowStatus::ALL.iter().map(|s| status_colors(*s).1).collect();
+        for (i, a) in colors.iter().enumerate() {
+            for b in &colors[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
 }
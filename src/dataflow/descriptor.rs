@@ -0,0 +1,444 @@
+//! Node layout of a dataflow, from its YAML or the coordinator's logs.
+//!
+//! Studio has two sources for what a dataflow consists of: the YAML it
+//! recorded when starting it, and the `spawn_dataflow` span the coordinator
+//! logs on every start. That span holds the descriptor in Rust `Debug`
+//! format (`Descriptor { nodes: [Node { id: NodeId("camera"), ... }] }`),
+//! so a small parser for that format lives here too.
+
+use serde_yaml::Value as Yaml;
+
+/// One node of a dataflow descriptor
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeSpec {
+    pub id: String,
+    /// Executable, script or operator source
+    pub path: Option<String>,
+    /// `(input id, source)` pairs, the source as written in YAML (`camera/image`, `dora/timer/millis/20`)
+    pub inputs: Vec<(String, String)>,
+    pub outputs: Vec<String>,
+    pub env: Vec<(String, String)>,
+}
+
+/// Nodes of a parsed dataflow YAML
+pub fn nodes_from_yaml(descriptor: &Yaml) -> Vec<NodeSpec> {
+    let Some(nodes) = descriptor.get("nodes").and_then(|n| n.as_sequence()) else {
+        return Vec::new();
+    };
+    nodes
+        .iter()
+        .filter_map(|node| {
+            let id = yaml_text(node.get("id")?)?;
+            let path = node
+                .get("path")
+                .or_else(|| node.get("custom").and_then(|c| c.get("source")))
+                .or_else(|| node.get("operator").and_then(operator_source))
+                .and_then(yaml_text);
+            let inputs = node
+                .get("inputs")
+                .and_then(|i| i.as_mapping())
+                .map(|inputs| {
+                    inputs
+                        .iter()
+                        .filter_map(|(input, source)| {
+                            let source = source.get("source").unwrap_or(source);
+                            Some((yaml_text(input)?, yaml_text(source)?))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let outputs = node
+                .get("outputs")
+                .and_then(|o| o.as_sequence())
+                .map(|outputs| outputs.iter().filter_map(yaml_text).collect())
+                .unwrap_or_default();
+            let env = node
+                .get("env")
+                .and_then(|e| e.as_mapping())
+                .map(|env| {
+                    env.iter()
+                        .filter_map(|(key, value)| Some((yaml_text(key)?, yaml_text(value)?)))
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(NodeSpec {
+                id,
+                path,
+                inputs,
+                outputs,
+                env,
+            })
+        })
+        .collect()
+}
+
+fn operator_source(operator: &Yaml) -> Option<&Yaml> {
+    ["python", "shared-library", "wasm"]
+        .iter()
+        .find_map(|kind| operator.get(*kind))
+}
+
+fn yaml_text(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::String(s) => Some(s.clone()),
+        Yaml::Number(n) => Some(n.to_string()),
+        Yaml::Bool(b) => Some(b.to_string()),
+        Yaml::Mapping(m) => m.get("source").and_then(yaml_text),
+        _ => None,
+    }
+}
+
+/// The `Descriptor { .. }` of a coordinator `spawn_dataflow` JSON log line
+pub fn spawn_descriptor(line: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(line.trim()).ok()?;
+    let dataflow = value.pointer("/span/dataflow")?.as_str()?;
+    dataflow.starts_with("Descriptor").then(|| dataflow.to_string())
+}
+
+/// Nodes of a descriptor in `Debug` format, as logged by the coordinator
+pub fn nodes_from_debug(descriptor: &str) -> Result<Vec<NodeSpec>, String> {
+    let value = DebugParser::new(descriptor).parse()?;
+    let nodes = value.field("nodes").ok_or("descriptor has no nodes")?;
+    let DebugValue::List(nodes) = nodes else {
+        return Err("descriptor nodes are not a list".to_string());
+    };
+
+    Ok(nodes
+        .iter()
+        .filter_map(|node| {
+            let id = node.field("id")?.text()?;
+            let path = node
+                .field("path")
+                .and_then(DebugValue::text)
+                .or_else(|| node.field("custom").and_then(|c| c.field("source")).and_then(DebugValue::text));
+            let inputs = match node.field("inputs") {
+                Some(DebugValue::Map(inputs)) => inputs
+                    .iter()
+                    .filter_map(|(input, spec)| Some((input.text()?, input_source(spec)?)))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let outputs = match node.field("outputs") {
+                Some(DebugValue::List(outputs)) => outputs.iter().filter_map(DebugValue::text).collect(),
+                _ => Vec::new(),
+            };
+            let env = match node.field("env").map(DebugValue::unwrap_some) {
+                Some(DebugValue::Map(env)) => env
+                    .iter()
+                    .filter_map(|(key, value)| Some((key.text()?, value.text()?)))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            Some(NodeSpec {
+                id,
+                path,
+                inputs,
+                outputs,
+                env,
+            })
+        })
+        .collect())
+}
+
+/// `camera/image` for user inputs, `dora/timer/20ms` for timers
+fn input_source(spec: &DebugValue) -> Option<String> {
+    let mapping = spec.field("mapping")?;
+    match mapping {
+        DebugValue::Struct(name, _) if name == "Timer" => {
+            Some(format!("dora/timer/{}", mapping.field("interval")?.text()?))
+        }
+        DebugValue::Tuple(name, items) if name == "User" => {
+            let user = items.first()?;
+            Some(format!("{}/{}", user.field("source")?.text()?, user.field("output")?.text()?))
+        }
+        other => other.text(),
+    }
+}
+
+/// A value in Rust `Debug` format
+#[derive(Debug, Clone, PartialEq)]
+enum DebugValue {
+    /// Quoted string
+    Str(String),
+    /// Number, identifier or anything else unquoted (`20ms`, `None`, `Tcp`)
+    Atom(String),
+    /// `Name { field: value, .. }`
+    Struct(String, Vec<(String, DebugValue)>),
+    /// `Name(value, ..)`
+    Tuple(String, Vec<DebugValue>),
+    /// `[..]` and `{a, b}`
+    List(Vec<DebugValue>),
+    /// `{key: value, ..}`
+    Map(Vec<(DebugValue, DebugValue)>),
+}
+
+impl DebugValue {
+    fn field(&self, name: &str) -> Option<&DebugValue> {
+        match self {
+            DebugValue::Struct(_, fields) => fields.iter().find(|(f, _)| f == name).map(|(_, v)| v),
+            DebugValue::Tuple(_, items) if items.len() == 1 => items[0].field(name),
+            _ => None,
+        }
+    }
+
+    fn unwrap_some(&self) -> &DebugValue {
+        match self {
+            DebugValue::Tuple(name, items) if name == "Some" && items.len() == 1 => &items[0],
+            other => other,
+        }
+    }
+
+    /// Text of a string, atom or newtype like `NodeId("camera")` or `Integer(1)`
+    fn text(&self) -> Option<String> {
+        match self {
+            DebugValue::Str(s) => Some(s.clone()),
+            DebugValue::Atom(a) if a == "None" => None,
+            DebugValue::Atom(a) => Some(a.clone()),
+            DebugValue::Tuple(_, items) if items.len() == 1 => items[0].text(),
+            _ => None,
+        }
+    }
+}
+
+struct DebugParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> DebugParser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+        }
+    }
+
+    fn parse(mut self) -> Result<DebugValue, String> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        match self.chars.next() {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected `{}` after descriptor", c)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.chars.next();
+                Ok(())
+            }
+            Some(c) => Err(format!("expected `{}`, found `{}`", expected, c)),
+            None => Err(format!("expected `{}`, found end of input", expected)),
+        }
+    }
+
+    fn value(&mut self) -> Result<DebugValue, String> {
+        match self.peek().ok_or("unexpected end of input")? {
+            '"' => self.string().map(DebugValue::Str),
+            '[' => {
+                self.chars.next();
+                Ok(DebugValue::List(self.items(']')?))
+            }
+            '{' => self.braces(),
+            _ => {
+                let atom = self.atom();
+                if atom.is_empty() {
+                    return Err(format!("unexpected `{}`", self.peek().unwrap_or(' ')));
+                }
+                match self.peek() {
+                    Some('{') => {
+                        self.chars.next();
+                        Ok(DebugValue::Struct(atom, self.fields()?))
+                    }
+                    Some('(') => {
+                        self.chars.next();
+                        Ok(DebugValue::Tuple(atom, self.items(')')?))
+                    }
+                    _ => Ok(DebugValue::Atom(atom)),
+                }
+            }
+        }
+    }
+
+    fn atom(&mut self) -> String {
+        let mut atom = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || "{}[](),:\"".contains(c) {
+                break;
+            }
+            atom.push(c);
+            self.chars.next();
+        }
+        atom
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            match self.chars.next().ok_or("unterminated string")? {
+                '"' => return Ok(s),
+                '\\' => match self.chars.next().ok_or("unterminated string")? {
+                    'n' => s.push('\n'),
+                    't' => s.push('\t'),
+                    other => s.push(other),
+                },
+                c => s.push(c),
+            }
+        }
+    }
+
+    /// Comma-separated values up to `close`
+    fn items(&mut self, close: char) -> Result<Vec<DebugValue>, String> {
+        let mut items = Vec::new();
+        loop {
+            if self.peek() == Some(close) {
+                self.chars.next();
+                return Ok(items);
+            }
+            items.push(self.value()?);
+            if self.peek() == Some(',') {
+                self.chars.next();
+            }
+        }
+    }
+
+    /// `field: value` pairs up to `}`, skipping a trailing `..`
+    fn fields(&mut self) -> Result<Vec<(String, DebugValue)>, String> {
+        let mut fields = Vec::new();
+        loop {
+            match self.peek() {
+                Some('}') => {
+                    self.chars.next();
+                    return Ok(fields);
+                }
+                Some('.') => {
+                    self.atom();
+                }
+                _ => {
+                    let name = self.atom();
+                    self.expect(':')?;
+                    fields.push((name, self.value()?));
+                }
+            }
+            if self.peek() == Some(',') {
+                self.chars.next();
+            }
+        }
+    }
+
+    /// `{}`, `{a, b}` (set) or `{k: v}` (map)
+    fn braces(&mut self) -> Result<DebugValue, String> {
+        self.expect('{')?;
+        let mut set = Vec::new();
+        let mut map = Vec::new();
+        loop {
+            if self.peek() == Some('}') {
+                self.chars.next();
+                return Ok(if map.is_empty() {
+                    DebugValue::List(set)
+                } else {
+                    DebugValue::Map(map)
+                });
+            }
+            let key = self.value()?;
+            if self.peek() == Some(':') {
+                self.chars.next();
+                map.push((key, self.value()?));
+            } else {
+                set.push(key);
+            }
+            if self.peek() == Some(',') {
+                self.chars.next();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nodes_from_yaml() {
+        let yaml: Yaml = serde_yaml::from_str(
+            r#"
+nodes:
+  - id: camera
+    build: pip install opencv-video-capture
+    path: opencv-video-capture
+    inputs:
+      tick: dora/timer/millis/20
+    outputs:
+      - image
+    env:
+      CAPTURE_PATH: 0
+      IMAGE_WIDTH: 640
+  - id: plot
+    path: dora-rerun
+    inputs:
+      image:
+        source: camera/image
+        queue_size: 1
+  - id: op
+    operator:
+      python: op.py
+"#,
+        )
+        .unwrap();
+
+        let nodes = nodes_from_yaml(&yaml);
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[0].path.as_deref(), Some("opencv-video-capture"));
+        assert_eq!(nodes[0].inputs, vec![("tick".to_string(), "dora/timer/millis/20".to_string())]);
+        assert_eq!(nodes[0].outputs, vec!["image".to_string()]);
+        assert_eq!(nodes[0].env[1], ("IMAGE_WIDTH".to_string(), "640".to_string()));
+        assert_eq!(nodes[1].inputs[0].1, "camera/image");
+        assert_eq!(nodes[2].path.as_deref(), Some("op.py"));
+    }
+
+    #[test]
+    fn test_nodes_from_coordinator_log() {
+        let log = include_str!("../../out/dora-coordinator.txt");
+        let line = log.lines().find(|l| l.contains("triggered dataflow spawn")).unwrap();
+        let descriptor = spawn_descriptor(line).unwrap();
+
+        let nodes = nodes_from_debug(&descriptor).unwrap();
+        let ids: Vec<_> = nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["camera", "object-detection", "plot"]);
+
+        let camera = &nodes[0];
+        assert_eq!(camera.path.as_deref(), Some("opencv-video-capture"));
+        assert_eq!(camera.outputs, vec!["image".to_string()]);
+        assert_eq!(camera.inputs, vec![("tick".to_string(), "dora/timer/20ms".to_string())]);
+        assert!(camera.env.contains(&("IMAGE_WIDTH".to_string(), "640".to_string())));
+
+        let plot = &nodes[2];
+        assert!(plot.outputs.is_empty());
+        assert!(plot.env.is_empty());
+        assert!(plot.inputs.contains(&("boxes2d".to_string(), "object-detection/bbox".to_string())));
+    }
+
+    #[test]
+    fn test_debug_parser_edge_cases() {
+        let value = DebugParser::new(r#"A { s: "q\"uote", e: {}, n: Some(-1.5), x: Foo, .. }"#)
+            .parse()
+            .unwrap();
+        assert_eq!(value.field("s").unwrap().text().as_deref(), Some("q\"uote"));
+        assert_eq!(value.field("e"), Some(&DebugValue::List(vec![])));
+        assert_eq!(value.field("n").unwrap().text().as_deref(), Some("-1.5"));
+
+        assert!(nodes_from_debug("Descriptor { nodes: [").is_err());
+        assert!(spawn_descriptor("not json").is_none());
+    }
+}
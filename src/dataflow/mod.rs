@@ -1,16 +1,26 @@
+pub mod dataflow_detail;
 pub mod dataflow_table;
+pub mod descriptor;
+pub mod model;
+pub mod selection;
+pub mod start_dialog;
+pub mod table_view;
 
+pub use model::{DataflowInfo, DataflowStatus, NodeInfo, StatusHistory};
 pub use dataflow_table::{
-    DataflowInfo,
     DataflowTable,
     DataflowTableAction,
     DataflowTableRef,
     DataflowTableWidgetRefExt,
     TableLoadingState,
 };
+pub use dataflow_detail::{DataflowDetail, DataflowDetailRef, DataflowDetailWidgetRefExt, DetailAction};
+pub use start_dialog::{StartDialog, StartDialogRef, StartDialogWidgetRefExt};
 
 use makepad_widgets::*;
 
 pub fn live_design(cx: &mut Cx) {
     dataflow_table::live_design(cx);
+    dataflow_detail::live_design(cx);
+    start_dialog::live_design(cx);
 }
@@ -0,0 +1,704 @@
+//! Dataflow data model shared by the table, the coordinator client and the tools
+//!
+//! Kept free of UI types so it can be used from background threads.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Lifecycle state of a dataflow (or of a single node)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize)]
+pub enum DataflowStatus {
+    Pending,
+    Running,
+    Finished,
+    Failed,
+    Stopped,
+    #[default]
+    Unknown,
+}
+
+impl DataflowStatus {
+    /// All statuses, in display order
+    pub const ALL: [DataflowStatus; 6] = [
+        DataflowStatus::Pending,
+        DataflowStatus::Running,
+        DataflowStatus::Finished,
+        DataflowStatus::Failed,
+        DataflowStatus::Stopped,
+        DataflowStatus::Unknown,
+    ];
+
+    /// Parse a status name as printed by any dora version.
+    ///
+    /// Matching is case insensitive and only looks at the leading word, so
+    /// `"Failed: node camera exited"` or `"failed(1)"` are both `Failed`.
+    pub fn parse(input: &str) -> Self {
+        let word: String = input
+            .trim()
+            .chars()
+            .take_while(|c| c.is_ascii_alphabetic())
+            .collect::<String>()
+            .to_lowercase();
+
+        match word.as_str() {
+            "pending" | "spawning" | "starting" | "queued" | "created" | "initializing" => {
+                DataflowStatus::Pending
+            }
+            "running" | "started" | "spawned" | "active" => DataflowStatus::Running,
+            "finished" | "succeeded" | "success" | "completed" | "done" | "exited" => {
+                DataflowStatus::Finished
+            }
+            "failed" | "failure" | "error" | "errored" | "crashed" => DataflowStatus::Failed,
+            "stopped" | "stopping" | "killed" | "terminated" | "cancelled" | "canceled"
+            | "destroyed" => DataflowStatus::Stopped,
+            _ => DataflowStatus::Unknown,
+        }
+    }
+
+    /// Parse a status from JSON, returning the exit reason if one is attached.
+    ///
+    /// Accepts plain strings (`"Failed"`) as well as externally tagged enums
+    /// carrying a payload (`{"Failed": {"error": "..."}}`).
+    pub fn from_value(value: &serde_json::Value) -> (Self, Option<String>) {
+        match value {
+            serde_json::Value::String(s) => (Self::parse(s), None),
+            serde_json::Value::Object(map) => match map.iter().next() {
+                Some((key, payload)) => (Self::parse(key), reason_from_value(payload)),
+                None => (DataflowStatus::Unknown, None),
+            },
+            _ => (DataflowStatus::Unknown, None),
+        }
+    }
+
+    /// Human readable label
+    pub fn label(&self) -> &'static str {
+        match self {
+            DataflowStatus::Pending => "Pending",
+            DataflowStatus::Running => "Running",
+            DataflowStatus::Finished => "Finished",
+            DataflowStatus::Failed => "Failed",
+            DataflowStatus::Stopped => "Stopped",
+            DataflowStatus::Unknown => "Unknown",
+        }
+    }
+
+    /// Whether the dataflow is still (or about to be) running
+    pub fn is_active(&self) -> bool {
+        matches!(self, DataflowStatus::Pending | DataflowStatus::Running)
+    }
+}
+
+impl std::fmt::Display for DataflowStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl<'de> Deserialize<'de> for DataflowStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Ok(Self::from_value(&value).0)
+    }
+}
+
+/// Pull a human readable reason out of a status payload
+fn reason_from_value(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+        serde_json::Value::Object(map) => ["error", "reason", "message", "cause"]
+            .iter()
+            .find_map(|key| map.get(*key).and_then(reason_from_value)),
+        serde_json::Value::Array(items) => items.iter().find_map(reason_from_value),
+        _ => None,
+    }
+}
+
+/// A single node of a dataflow
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct NodeInfo {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub status: DataflowStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_reason: Option<String>,
+}
+
+impl NodeInfo {
+    /// Parse a node entry, either a bare node id or an object
+    pub fn from_value(value: &serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::String(id) => Some(NodeInfo {
+                id: id.clone(),
+                ..Default::default()
+            }),
+            serde_json::Value::Object(map) => {
+                let id = map.get("id").or_else(|| map.get("node_id"))?.as_str()?;
+                let (status, reason) = map
+                    .get("status")
+                    .map(DataflowStatus::from_value)
+                    .unwrap_or_default();
+                let exit_reason = map
+                    .get("exit_reason")
+                    .or_else(|| map.get("error"))
+                    .and_then(reason_from_value)
+                    .or(reason);
+                Some(NodeInfo {
+                    id: id.to_string(),
+                    status,
+                    exit_reason,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Dataflow information from dora list command
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct DataflowInfo {
+    #[serde(default)]
+    pub uuid: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub status: DataflowStatus,
+    /// Number of nodes, falls back to `node_entries.len()` when not reported
+    #[serde(default)]
+    pub nodes: u32,
+    #[serde(default)]
+    pub cpu: f64,
+    #[serde(default)]
+    pub memory: f64,
+    /// Start time in seconds since the Unix epoch
+    #[serde(
+        default,
+        alias = "start_time",
+        deserialize_with = "deserialize_timestamp",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub started_at: Option<u64>,
+    /// Why the dataflow stopped, if it did
+    #[serde(default, alias = "error", skip_serializing_if = "Option::is_none")]
+    pub exit_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub node_entries: Vec<NodeInfo>,
+}
+
+impl DataflowInfo {
+    /// Parse NDJSON (newline-delimited JSON) into a vector of DataflowInfo.
+    ///
+    /// Lines that fail to parse are skipped; use
+    /// `client::version::parse_json_list` where errors must be reported.
+    pub fn parse_ndjson(input: &str) -> Vec<Self> {
+        input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    /// Parse JSON array into a vector of DataflowInfo, empty if invalid
+    pub fn parse_json_array(input: &str) -> Vec<Self> {
+        serde_json::from_str(input).unwrap_or_default()
+    }
+
+    /// Format memory in human-readable format
+    pub fn memory_formatted(&self) -> String {
+        format_memory(self.memory)
+    }
+
+    /// Format CPU percentage
+    pub fn cpu_formatted(&self) -> String {
+        format_cpu(self.cpu)
+    }
+
+    /// Get short UUID (first 8 characters)
+    pub fn uuid_short(&self) -> String {
+        if self.uuid.len() > 8 {
+            format!("{}...", &self.uuid[..8])
+        } else {
+            self.uuid.clone()
+        }
+    }
+
+    /// Check if dataflow is running
+    pub fn is_running(&self) -> bool {
+        self.status == DataflowStatus::Running
+    }
+
+    /// Node count, using the per-node entries when no count was reported
+    pub fn node_count(&self) -> u32 {
+        if self.nodes == 0 {
+            self.node_entries.len() as u32
+        } else {
+            self.nodes
+        }
+    }
+
+    /// Format the time since start, e.g. `3m 12s`
+    pub fn uptime_formatted(&self, now: u64) -> String {
+        match self.started_at {
+            Some(started) => format_duration(now.saturating_sub(started)),
+            None => "-".to_string(),
+        }
+    }
+}
+
+/// Format a duration in seconds as a short human readable string
+pub fn format_duration(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else if secs < 86400 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else {
+        format!("{}d {}h", secs / 86400, (secs % 86400) / 3600)
+    }
+}
+
+/// Format a memory size in GB, e.g. `37 MB` or `1.50 GB`
+pub fn format_memory(gb: f64) -> String {
+    if gb < 0.001 {
+        "0 B".to_string()
+    } else if gb < 1.0 {
+        format!("{:.0} MB", gb * 1024.0)
+    } else {
+        format!("{:.2} GB", gb)
+    }
+}
+
+/// Format a CPU usage in percent, e.g. `45.7%`
+pub fn format_cpu(percent: f64) -> String {
+    format!("{:.1}%", percent)
+}
+
+/// Format Unix seconds as `YYYY-MM-DD HH:MM:SS UTC`
+pub fn format_utc(secs: u64) -> String {
+    let (days, rem) = ((secs / 86400) as i64, secs % 86400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+/// Whether `id` is a dataflow UUID rather than a name
+pub fn looks_like_uuid(id: &str) -> bool {
+    id.len() == 36
+        && id.chars().enumerate().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// Current time in seconds since the Unix epoch
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(parse_timestamp(&value))
+}
+
+/// Parse a timestamp into seconds since the Unix epoch.
+///
+/// Accepts epoch numbers in seconds, milliseconds, microseconds or
+/// nanoseconds (guessed from magnitude) and RFC 3339 strings.
+pub fn parse_timestamp(value: &serde_json::Value) -> Option<u64> {
+    match value {
+        serde_json::Value::Number(n) => n
+            .as_u64()
+            .or_else(|| n.as_f64().filter(|f| *f >= 0.0).map(|f| f as u64))
+            .map(epoch_to_secs),
+        serde_json::Value::String(s) => {
+            let s = s.trim();
+            match s.parse::<u64>() {
+                Ok(n) => Some(epoch_to_secs(n)),
+                Err(_) => parse_rfc3339(s),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn epoch_to_secs(n: u64) -> u64 {
+    if n >= 100_000_000_000_000_000 {
+        n / 1_000_000_000
+    } else if n >= 100_000_000_000_000 {
+        n / 1_000_000
+    } else if n >= 100_000_000_000 {
+        n / 1_000
+    } else {
+        n
+    }
+}
+
+/// Parse `YYYY-MM-DDTHH:MM:SS[.frac](Z|±HH:MM)` into epoch seconds
+pub fn parse_rfc3339(s: &str) -> Option<u64> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 19 || !matches!(bytes[10], b'T' | b't' | b' ') {
+        return None;
+    }
+    let num = |range: std::ops::Range<usize>| s.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Skip fractional seconds, then read the UTC offset
+    let rest = &s[19..];
+    let rest = rest.trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let offset = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let hours = rest.get(1..3)?.parse::<i64>().ok()?;
+            let minutes = rest.get(4..6)?.parse::<i64>().ok()?;
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    let secs = days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset;
+    u64::try_from(secs).ok()
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+// Inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Status changes seen for each dataflow, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct StatusHistory {
+    changes: std::collections::HashMap<String, Vec<(u64, DataflowStatus)>>,
+}
+
+impl StatusHistory {
+    /// Record the statuses of a fresh list at time `now`, keeping only changes
+    pub fn observe(&mut self, dataflows: &[DataflowInfo], now: u64) {
+        for df in dataflows {
+            let changes = self.changes.entry(df.uuid.clone()).or_default();
+            if changes.last().map(|(_, status)| *status) != Some(df.status) {
+                changes.push((now, df.status));
+            }
+        }
+    }
+
+    /// Changes of a dataflow as `(unix seconds, new status)`
+    pub fn get(&self, uuid: &str) -> &[(u64, DataflowStatus)] {
+        self.changes.get(uuid).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ndjson() {
+        let input = r#"{"uuid":"abc","name":"test","status":"Running","nodes":3,"cpu":0.5,"memory":0.036}
+{"uuid":"def","name":"test2","status":"Failed","nodes":0,"cpu":0.0,"memory":0.0}"#;
+
+        let dataflows = DataflowInfo::parse_ndjson(input);
+        assert_eq!(dataflows.len(), 2);
+        assert_eq!(dataflows[0].name, "test");
+        assert_eq!(dataflows[0].status, DataflowStatus::Running);
+        assert_eq!(dataflows[1].status, DataflowStatus::Failed);
+    }
+
+    #[test]
+    fn test_parse_json_array() {
+        let input = r#"[
+            {"uuid":"abc123","name":"dataflow1","status":"Running","nodes":3,"cpu":25.5,"memory":0.5},
+            {"uuid":"def456","name":"dataflow2","status":"Stopped","nodes":0,"cpu":0.0,"memory":0.0}
+        ]"#;
+
+        let dataflows = DataflowInfo::parse_json_array(input);
+        assert_eq!(dataflows.len(), 2);
+        assert_eq!(dataflows[0].uuid, "abc123");
+        assert_eq!(dataflows[0].name, "dataflow1");
+        assert!(dataflows[0].is_running());
+        assert!(!dataflows[1].is_running());
+    }
+
+    #[test]
+    fn test_parse_json_array_empty() {
+        let input = "[]";
+        let dataflows = DataflowInfo::parse_json_array(input);
+        assert!(dataflows.is_empty());
+    }
+
+    #[test]
+    fn test_parse_json_array_invalid() {
+        let input = "invalid json";
+        let dataflows = DataflowInfo::parse_json_array(input);
+        assert!(dataflows.is_empty());
+    }
+
+    #[test]
+    fn test_parse_extended_fields() {
+        let input = r#"{"uuid":"abc","name":"test","status":{"Failed":{"error":"boom"}},"start_time":"2024-05-01T12:00:00Z","error":"node camera exited with code 1","node_entries":[{"id":"camera","status":"Failed"}]}"#;
+
+        let dataflows = DataflowInfo::parse_ndjson(input);
+        assert_eq!(dataflows.len(), 1);
+        let df = &dataflows[0];
+        assert_eq!(df.status, DataflowStatus::Failed);
+        assert_eq!(df.started_at, Some(1714564800));
+        assert_eq!(df.exit_reason.as_deref(), Some("node camera exited with code 1"));
+        assert_eq!(df.node_count(), 1);
+        assert_eq!(df.node_entries[0].status, DataflowStatus::Failed);
+    }
+
+    #[test]
+    fn test_memory_formatted() {
+        let df = DataflowInfo {
+            uuid: "test".to_string(),
+            name: "test".to_string(),
+            status: DataflowStatus::Running,
+            nodes: 1,
+            cpu: 0.0,
+            memory: 0.036,
+            ..Default::default()
+        };
+        assert_eq!(df.memory_formatted(), "37 MB");
+
+        let df2 = DataflowInfo {
+            memory: 1.5,
+            ..df.clone()
+        };
+        assert_eq!(df2.memory_formatted(), "1.50 GB");
+
+        let df3 = DataflowInfo {
+            memory: 0.0,
+            ..df.clone()
+        };
+        assert_eq!(df3.memory_formatted(), "0 B");
+    }
+
+    #[test]
+    fn test_cpu_formatted() {
+        let df = DataflowInfo {
+            uuid: "test".to_string(),
+            name: "test".to_string(),
+            status: DataflowStatus::Running,
+            nodes: 1,
+            cpu: 45.678,
+            memory: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(df.cpu_formatted(), "45.7%");
+    }
+
+    #[test]
+    fn test_uuid_short() {
+        let df = DataflowInfo {
+            uuid: "abc123def456789".to_string(),
+            name: "test".to_string(),
+            status: DataflowStatus::Running,
+            nodes: 1,
+            cpu: 0.0,
+            memory: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(df.uuid_short(), "abc123de...");
+
+        let df2 = DataflowInfo {
+            uuid: "short".to_string(),
+            ..df.clone()
+        };
+        assert_eq!(df2.uuid_short(), "short");
+    }
+
+    #[test]
+    fn test_looks_like_uuid() {
+        assert!(looks_like_uuid("019be55a-8468-7d4b-9e95-db7938985bc7"));
+        assert!(!looks_like_uuid("camera-pipeline"));
+        assert!(!looks_like_uuid("019be55a84687d4b9e95db7938985bc7"));
+    }
+
+    #[test]
+    fn test_is_running() {
+        let running = DataflowInfo {
+            uuid: "1".to_string(),
+            name: "test".to_string(),
+            status: DataflowStatus::Running,
+            nodes: 1,
+            cpu: 0.0,
+            memory: 0.0,
+            ..Default::default()
+        };
+        assert!(running.is_running());
+
+        let stopped = DataflowInfo {
+            status: DataflowStatus::Stopped,
+            ..running.clone()
+        };
+        assert!(!stopped.is_running());
+
+        // Case insensitive
+        let running_lower: DataflowInfo =
+            serde_json::from_str(r#"{"uuid":"1","status":"running"}"#).unwrap();
+        assert!(running_lower.is_running());
+    }
+
+    #[test]
+    fn test_dataflow_info_default() {
+        let df = DataflowInfo::default();
+        assert!(df.uuid.is_empty());
+        assert!(df.name.is_empty());
+        assert_eq!(df.status, DataflowStatus::Unknown);
+        assert_eq!(df.nodes, 0);
+        assert_eq!(df.cpu, 0.0);
+        assert_eq!(df.memory, 0.0);
+        assert!(df.started_at.is_none());
+        assert!(df.node_entries.is_empty());
+    }
+
+    #[test]
+    fn test_status_parse_variants() {
+        assert_eq!(DataflowStatus::parse("Running"), DataflowStatus::Running);
+        assert_eq!(DataflowStatus::parse("  RUNNING "), DataflowStatus::Running);
+        assert_eq!(DataflowStatus::parse("Spawning"), DataflowStatus::Pending);
+        assert_eq!(DataflowStatus::parse("Succeeded"), DataflowStatus::Finished);
+        assert_eq!(DataflowStatus::parse("failed(1)"), DataflowStatus::Failed);
+        assert_eq!(DataflowStatus::parse("Failed: oom"), DataflowStatus::Failed);
+        assert_eq!(DataflowStatus::parse("killed"), DataflowStatus::Stopped);
+        assert_eq!(DataflowStatus::parse("who knows"), DataflowStatus::Unknown);
+        assert_eq!(DataflowStatus::parse(""), DataflowStatus::Unknown);
+    }
+
+    #[test]
+    fn test_status_from_value_extracts_reason() {
+        let (status, reason) =
+            DataflowStatus::from_value(&serde_json::json!({ "Failed": { "error": "node crashed" } }));
+        assert_eq!(status, DataflowStatus::Failed);
+        assert_eq!(reason.as_deref(), Some("node crashed"));
+
+        let (status, reason) = DataflowStatus::from_value(&serde_json::json!("Finished"));
+        assert_eq!(status, DataflowStatus::Finished);
+        assert!(reason.is_none());
+
+        let (status, _) = DataflowStatus::from_value(&serde_json::Value::Null);
+        assert_eq!(status, DataflowStatus::Unknown);
+    }
+
+    #[test]
+    fn test_status_serializes_as_name() {
+        let json = serde_json::to_string(&DataflowStatus::Failed).unwrap();
+        assert_eq!(json, "\"Failed\"");
+        let back: DataflowStatus = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, DataflowStatus::Failed);
+    }
+
+    #[test]
+    fn test_node_info_from_value() {
+        let bare = NodeInfo::from_value(&serde_json::json!("camera")).unwrap();
+        assert_eq!(bare.id, "camera");
+        assert_eq!(bare.status, DataflowStatus::Unknown);
+
+        let full = NodeInfo::from_value(&serde_json::json!({
+            "id": "detector",
+            "status": { "Failed": "exit code 137" }
+        }))
+        .unwrap();
+        assert_eq!(full.status, DataflowStatus::Failed);
+        assert_eq!(full.exit_reason.as_deref(), Some("exit code 137"));
+
+        assert!(NodeInfo::from_value(&serde_json::json!(42)).is_none());
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp(&serde_json::json!(1714564800)), Some(1714564800));
+        assert_eq!(parse_timestamp(&serde_json::json!(1714564800123u64)), Some(1714564800));
+        assert_eq!(
+            parse_timestamp(&serde_json::json!("2024-05-01T14:00:00.5+02:00")),
+            Some(1714564800)
+        );
+        assert_eq!(parse_timestamp(&serde_json::json!("1970-01-01T00:00:00Z")), Some(0));
+        assert_eq!(parse_timestamp(&serde_json::json!("yesterday")), None);
+        assert_eq!(parse_timestamp(&serde_json::Value::Null), None);
+    }
+
+    #[test]
+    fn test_uptime_formatted() {
+        let df = DataflowInfo {
+            started_at: Some(1000),
+            ..Default::default()
+        };
+        assert_eq!(df.uptime_formatted(1042), "42s");
+        assert_eq!(df.uptime_formatted(1000 + 192), "3m 12s");
+        assert_eq!(df.uptime_formatted(1000 + 7260), "2h 1m");
+        assert_eq!(DataflowInfo::default().uptime_formatted(5000), "-");
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00 UTC");
+        let secs = parse_timestamp(&serde_json::json!("2026-01-22T10:57:48.488377Z")).unwrap();
+        assert_eq!(format_utc(secs), "2026-01-22 10:57:48 UTC");
+        assert_eq!(format_utc(951_782_400), "2000-02-29 00:00:00 UTC");
+    }
+
+    #[test]
+    fn test_status_history_keeps_changes() {
+        let mut history = StatusHistory::default();
+        let at = |status| {
+            vec![DataflowInfo {
+                uuid: "a".to_string(),
+                status,
+                ..Default::default()
+            }]
+        };
+        history.observe(&at(DataflowStatus::Running), 10);
+        history.observe(&at(DataflowStatus::Running), 15);
+        history.observe(&at(DataflowStatus::Finished), 20);
+        assert_eq!(
+            history.get("a"),
+            &[(10, DataflowStatus::Running), (20, DataflowStatus::Finished)]
+        );
+        assert!(history.get("b").is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+//! Row selection for the dataflow table.
+//!
+//! Selected rows are kept by UUID so the selection survives refreshes that
+//! reorder, add or drop rows.
+
+use std::collections::BTreeSet;
+
+/// How a row was clicked
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ClickModifiers {
+    /// Shift: select the range from the last clicked row
+    pub extend: bool,
+    /// Ctrl/Cmd: add or remove the row, keeping the rest
+    pub toggle: bool,
+}
+
+/// UUIDs of the selected rows plus the anchor for shift ranges
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selection {
+    selected: BTreeSet<String>,
+    anchor: Option<String>,
+}
+
+impl Selection {
+    /// Apply a click on row `index` of `rows` (UUIDs in display order)
+    pub fn click(&mut self, rows: &[&str], index: usize, modifiers: ClickModifiers) {
+        let Some(uuid) = rows.get(index) else {
+            return;
+        };
+        let anchor_index = self
+            .anchor
+            .as_deref()
+            .and_then(|anchor| rows.iter().position(|row| *row == anchor));
+
+        match (modifiers.extend, anchor_index) {
+            (true, Some(anchor_index)) => {
+                if !modifiers.toggle {
+                    self.selected.clear();
+                }
+                let (from, to) = (anchor_index.min(index), anchor_index.max(index));
+                self.selected.extend(rows[from..=to].iter().map(|row| row.to_string()));
+                // The anchor stays put so the range can be adjusted
+                return;
+            }
+            _ if modifiers.toggle => {
+                if !self.selected.remove(*uuid) {
+                    self.selected.insert(uuid.to_string());
+                }
+            }
+            _ => {
+                self.selected.clear();
+                self.selected.insert(uuid.to_string());
+            }
+        }
+        self.anchor = Some(uuid.to_string());
+    }
+
+    pub fn contains(&self, uuid: &str) -> bool {
+        self.selected.contains(uuid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.selected.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.selected.clear();
+        self.anchor = None;
+    }
+
+    /// Drop UUIDs no longer in `rows`
+    pub fn retain(&mut self, rows: &[&str]) {
+        self.selected.retain(|uuid| rows.contains(&uuid.as_str()));
+        if self.anchor.as_deref().is_some_and(|anchor| !rows.contains(&anchor)) {
+            self.anchor = None;
+        }
+    }
+
+    /// Selected UUIDs in display order
+    pub fn uuids(&self, rows: &[&str]) -> Vec<String> {
+        rows.iter()
+            .filter(|row| self.selected.contains(**row))
+            .map(|row| row.to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROWS: [&str; 5] = ["a", "b", "c", "d", "e"];
+    const PLAIN: ClickModifiers = ClickModifiers { extend: false, toggle: false };
+    const SHIFT: ClickModifiers = ClickModifiers { extend: true, toggle: false };
+    const CTRL: ClickModifiers = ClickModifiers { extend: false, toggle: true };
+
+    #[test]
+    fn test_click_selects_single_row() {
+        let mut selection = Selection::default();
+        selection.click(&ROWS, 1, PLAIN);
+        selection.click(&ROWS, 3, PLAIN);
+        assert_eq!(selection.uuids(&ROWS), vec!["d"]);
+    }
+
+    #[test]
+    fn test_ctrl_click_toggles() {
+        let mut selection = Selection::default();
+        selection.click(&ROWS, 0, PLAIN);
+        selection.click(&ROWS, 2, CTRL);
+        selection.click(&ROWS, 4, CTRL);
+        selection.click(&ROWS, 0, CTRL);
+        assert_eq!(selection.uuids(&ROWS), vec!["c", "e"]);
+    }
+
+    #[test]
+    fn test_shift_click_selects_range_from_anchor() {
+        let mut selection = Selection::default();
+        selection.click(&ROWS, 3, PLAIN);
+        selection.click(&ROWS, 1, SHIFT);
+        assert_eq!(selection.uuids(&ROWS), vec!["b", "c", "d"]);
+
+        // Shrinking the range keeps the anchor
+        selection.click(&ROWS, 4, SHIFT);
+        assert_eq!(selection.uuids(&ROWS), vec!["d", "e"]);
+
+        // Without an anchor shift-click acts like a plain click
+        let mut fresh = Selection::default();
+        fresh.click(&ROWS, 2, SHIFT);
+        assert_eq!(fresh.uuids(&ROWS), vec!["c"]);
+    }
+
+    #[test]
+    fn test_selection_follows_uuids_across_refresh() {
+        let mut selection = Selection::default();
+        selection.click(&ROWS, 1, PLAIN);
+        selection.click(&ROWS, 3, CTRL);
+
+        let refreshed = ["d", "x", "b"];
+        assert_eq!(selection.uuids(&refreshed), vec!["d", "b"]);
+
+        selection.retain(&["b", "x"]);
+        assert_eq!(selection.len(), 1);
+        selection.click(&["b", "x"], 0, SHIFT);
+        assert_eq!(selection.uuids(&["b", "x"]), vec!["b"]);
+    }
+}
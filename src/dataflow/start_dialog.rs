@@ -0,0 +1,235 @@
+use makepad_widgets::*;
+use std::path::PathBuf;
+
+use crate::client::start::parse_env_overrides;
+use crate::client::StartRequest;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    DIALOG_BG = #ffffff
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    TEXT_ERROR = #dc2626
+
+    FieldLabel = <Label> {
+        width: Fit, height: Fit
+        margin: { top: 8 }
+        draw_text: {
+            color: (TEXT_SECONDARY),
+            text_style: { font_size: 11.0 }
+        }
+    }
+
+    FieldInput = <TextInput> {
+        width: Fill, height: Fit
+        draw_text: {
+            color: #000000
+            uniform color_hover: #000000
+            uniform color_focus: #000000
+            uniform color_down: #000000
+            uniform color_empty: #888888
+        }
+    }
+
+    pub StartDialog = {{StartDialog}} {
+        width: 520, height: Fit
+        flow: Down
+        padding: 20
+        spacing: 4
+        show_bg: true
+        draw_bg: { color: (DIALOG_BG) }
+
+        <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 16.0 }
+            }
+            text: "Start dataflow"
+        }
+
+        <FieldLabel> { text: "Dataflow YAML" }
+        yaml_input = <FieldInput> { empty_text: "/path/to/dataflow.yml" }
+        recent_dropdown = <DropDown> {
+            width: Fill, height: 28
+            labels: ["No recent dataflows"]
+            draw_text: { text_style: { font_size: 11.0 } }
+        }
+
+        <FieldLabel> { text: "Name (optional)" }
+        name_input = <FieldInput> { empty_text: "my-dataflow" }
+
+        <FieldLabel> { text: "Environment overrides (KEY=VALUE, one per line)" }
+        env_input = <FieldInput> {
+            height: 72
+            empty_text: "IMAGE_WIDTH=640"
+        }
+
+        attach_toggle = <CheckBox> {
+            margin: { top: 8 }
+            text: "Attach: stream logs after starting"
+        }
+
+        result_label = <Label> {
+            width: Fill, height: Fit
+            margin: { top: 8 }
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+                wrap: Word
+            }
+            text: ""
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            margin: { top: 12 }
+            spacing: 8
+            align: { x: 1.0 }
+
+            cancel_button = <Button> {
+                width: 80, height: 32
+                text: "Close"
+            }
+            start_button = <Button> {
+                width: 80, height: 32
+                text: "Start"
+            }
+        }
+    }
+}
+
+/// Form for starting a dataflow: YAML path, name, attach and env overrides
+#[derive(Live, LiveHook, Widget)]
+pub struct StartDialog {
+    #[deref]
+    view: View,
+    /// Paths behind the recent dropdown entries
+    #[rust]
+    recent: Vec<PathBuf>,
+}
+
+impl Widget for StartDialog {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for StartDialog {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        // Picking a recent file fills in the path
+        if let Some(index) = self.view.drop_down(id!(recent_dropdown)).selected(actions) {
+            if let Some(path) = self.recent.get(index) {
+                self.view
+                    .text_input(id!(yaml_input))
+                    .set_text(cx, &path.to_string_lossy());
+            }
+        }
+    }
+}
+
+impl StartDialog {
+    /// Reset the form for a new start, offering `recent` files
+    pub fn open(&mut self, cx: &mut Cx, recent: Vec<PathBuf>) {
+        let labels = if recent.is_empty() {
+            vec!["No recent dataflows".to_string()]
+        } else {
+            recent.iter().map(|p| p.display().to_string()).collect()
+        };
+        let dropdown = self.view.drop_down(id!(recent_dropdown));
+        dropdown.set_labels(cx, labels);
+        dropdown.set_selected_item(cx, 0);
+
+        let path = recent.first().map(|p| p.to_string_lossy().into_owned()).unwrap_or_default();
+        self.view.text_input(id!(yaml_input)).set_text(cx, &path);
+        self.recent = recent;
+        self.set_result(cx, "", false);
+        self.view.button(id!(start_button)).set_enabled(cx, true);
+    }
+
+    /// The request described by the form, or a message about what is wrong
+    fn request(&self, cx: &Cx) -> Result<StartRequest, String> {
+        let path = self.view.text_input(id!(yaml_input)).text();
+        let name = self.view.text_input(id!(name_input)).text();
+        let env = parse_env_overrides(&self.view.text_input(id!(env_input)).text())?;
+
+        let request = StartRequest {
+            name: Some(name.trim().to_string()).filter(|n| !n.is_empty()),
+            attach: self.view.check_box(id!(attach_toggle)).active(cx),
+            env,
+            ..StartRequest::new(path.trim())
+        };
+        request.validate()?;
+        Ok(request)
+    }
+
+    fn set_result(&mut self, cx: &mut Cx, text: &str, is_error: bool) {
+        let color = if is_error {
+            vec4(0.863, 0.149, 0.149, 1.0)
+        } else {
+            vec4(0.392, 0.455, 0.545, 1.0)
+        };
+        let label = self.view.label(id!(result_label));
+        label.apply_over(cx, live! { draw_text: { color: (color) } });
+        label.set_text(cx, text);
+        self.redraw(cx);
+    }
+}
+
+impl StartDialogRef {
+    /// Reset and fill the form before showing it
+    pub fn open(&self, cx: &mut Cx, recent: Vec<PathBuf>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.open(cx, recent);
+        }
+    }
+
+    /// Check if Start was clicked with a valid form, returns the request if so.
+    /// Problems with the form are shown in the dialog instead.
+    pub fn start_requested(&self, cx: &mut Cx, actions: &Actions) -> Option<StartRequest> {
+        let mut inner = self.borrow_mut()?;
+        if !inner.view.button(id!(start_button)).clicked(actions) {
+            return None;
+        }
+        match inner.request(cx) {
+            Ok(request) => {
+                inner.set_result(cx, &format!("Starting {}...", request.path.display()), false);
+                inner.view.button(id!(start_button)).set_enabled(cx, false);
+                Some(request)
+            }
+            Err(e) => {
+                inner.set_result(cx, &e, true);
+                None
+            }
+        }
+    }
+
+    /// Show the outcome of a start: the new UUID or the error
+    pub fn show_result(&self, cx: &mut Cx, result: &Result<String, String>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            match result {
+                Ok(uuid) => inner.set_result(cx, &format!("Started dataflow {}", uuid), false),
+                Err(e) => inner.set_result(cx, e, true),
+            }
+            inner.view.button(id!(start_button)).set_enabled(cx, true);
+        }
+    }
+
+    /// Check if the dialog was dismissed
+    pub fn cancelled(&self, actions: &Actions) -> bool {
+        if let Some(inner) = self.borrow() {
+            inner.view.button(id!(cancel_button)).clicked(actions)
+        } else {
+            false
+        }
+    }
+}
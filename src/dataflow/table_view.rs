@@ -0,0 +1,196 @@
+//! Sorting and filtering of the dataflow table.
+//!
+//! [`TableView`] is saved in the settings so the table looks the same after
+//! a refresh or a restart of Studio.
+
+use super::model::{DataflowInfo, DataflowStatus};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Column the table can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortColumn {
+    Name,
+    Status,
+    Cpu,
+    Memory,
+}
+
+/// Sort order, text filter and status chips of the dataflow table
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableView {
+    /// `None` keeps the order `dora list` returned
+    pub sort: Option<SortColumn>,
+    pub descending: bool,
+    /// Case-insensitive substring of the name or UUID
+    pub filter: String,
+    /// Statuses switched off with the chips
+    pub hidden_statuses: Vec<DataflowStatus>,
+}
+
+impl TableView {
+    /// Header click: sort by `column`, or flip the order if it already is
+    pub fn toggle_sort(&mut self, column: SortColumn) {
+        if self.sort == Some(column) {
+            self.descending = !self.descending;
+        } else {
+            self.sort = Some(column);
+            self.descending = false;
+        }
+    }
+
+    /// Chip click: show or hide dataflows with `status`
+    pub fn toggle_status(&mut self, status: DataflowStatus) {
+        if let Some(i) = self.hidden_statuses.iter().position(|s| *s == status) {
+            self.hidden_statuses.remove(i);
+        } else {
+            self.hidden_statuses.push(status);
+        }
+    }
+
+    pub fn shows_status(&self, status: DataflowStatus) -> bool {
+        !self.hidden_statuses.contains(&status)
+    }
+
+    /// Whether a dataflow passes the text filter and the status chips
+    pub fn matches(&self, df: &DataflowInfo) -> bool {
+        if !self.shows_status(df.status) {
+            return false;
+        }
+        let filter = self.filter.trim().to_lowercase();
+        filter.is_empty()
+            || df.name.to_lowercase().contains(&filter)
+            || df.uuid.to_lowercase().contains(&filter)
+    }
+
+    /// The dataflows to display, filtered and sorted
+    pub fn apply(&self, dataflows: &[DataflowInfo]) -> Vec<DataflowInfo> {
+        let mut rows: Vec<_> = dataflows.iter().filter(|df| self.matches(df)).cloned().collect();
+        if let Some(column) = self.sort {
+            // Stable sort, so ties keep the coordinator's order
+            rows.sort_by(|a, b| {
+                let order = compare(column, a, b);
+                if self.descending {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+        }
+        rows
+    }
+
+    /// Header text with an arrow on the sorted column
+    pub fn header_label(&self, column: SortColumn, text: &str) -> String {
+        match (self.sort == Some(column), self.descending) {
+            (true, false) => format!("{} ↑", text),
+            (true, true) => format!("{} ↓", text),
+            (false, _) => text.to_string(),
+        }
+    }
+}
+
+fn compare(column: SortColumn, a: &DataflowInfo, b: &DataflowInfo) -> Ordering {
+    match column {
+        SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        SortColumn::Status => status_rank(a.status).cmp(&status_rank(b.status)),
+        SortColumn::Cpu => a.cpu.total_cmp(&b.cpu),
+        SortColumn::Memory => a.memory.total_cmp(&b.memory),
+    }
+}
+
+fn status_rank(status: DataflowStatus) -> usize {
+    DataflowStatus::ALL.iter().position(|s| *s == status).unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataflow(uuid: &str, name: &str, status: DataflowStatus, cpu: f64) -> DataflowInfo {
+        DataflowInfo {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            status,
+            cpu,
+            ..Default::default()
+        }
+    }
+
+    fn sample() -> Vec<DataflowInfo> {
+        vec![
+            dataflow("019be55a-0001", "camera", DataflowStatus::Running, 12.0),
+            dataflow("019be55a-0002", "Yolo", DataflowStatus::Finished, 0.0),
+            dataflow("019be55a-0003", "audio", DataflowStatus::Failed, 3.5),
+            dataflow("019be55a-0004", "plot", DataflowStatus::Running, 40.0),
+        ]
+    }
+
+    fn names(rows: &[DataflowInfo]) -> Vec<&str> {
+        rows.iter().map(|df| df.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_keeps_order() {
+        assert_eq!(names(&TableView::default().apply(&sample())), vec!["camera", "Yolo", "audio", "plot"]);
+    }
+
+    #[test]
+    fn test_toggle_sort() {
+        let mut view = TableView::default();
+        view.toggle_sort(SortColumn::Name);
+        assert_eq!(names(&view.apply(&sample())), vec!["audio", "camera", "plot", "Yolo"]);
+        assert_eq!(view.header_label(SortColumn::Name, "NAME"), "NAME ↑");
+        assert_eq!(view.header_label(SortColumn::Cpu, "CPU"), "CPU");
+
+        view.toggle_sort(SortColumn::Name);
+        assert_eq!(names(&view.apply(&sample())), vec!["Yolo", "plot", "camera", "audio"]);
+
+        view.toggle_sort(SortColumn::Cpu);
+        assert!(!view.descending);
+        assert_eq!(names(&view.apply(&sample())), vec!["Yolo", "audio", "camera", "plot"]);
+    }
+
+    #[test]
+    fn test_sort_by_status_is_stable() {
+        let mut view = TableView::default();
+        view.toggle_sort(SortColumn::Status);
+        assert_eq!(names(&view.apply(&sample())), vec!["camera", "plot", "Yolo", "audio"]);
+    }
+
+    #[test]
+    fn test_filter_and_status_chips() {
+        let mut view = TableView {
+            filter: " 0003".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(names(&view.apply(&sample())), vec!["audio"]);
+
+        view.filter = "YO".to_string();
+        assert_eq!(names(&view.apply(&sample())), vec!["Yolo"]);
+
+        view.filter.clear();
+        view.toggle_status(DataflowStatus::Finished);
+        view.toggle_status(DataflowStatus::Failed);
+        assert_eq!(names(&view.apply(&sample())), vec!["camera", "plot"]);
+        view.toggle_status(DataflowStatus::Failed);
+        assert!(view.shows_status(DataflowStatus::Failed));
+        assert_eq!(view.apply(&sample()).len(), 3);
+    }
+
+    #[test]
+    fn test_table_view_roundtrip() {
+        let view = TableView {
+            sort: Some(SortColumn::Memory),
+            descending: true,
+            filter: "cam".to_string(),
+            hidden_statuses: vec![DataflowStatus::Finished],
+        };
+        let json = serde_json::to_string(&view).unwrap();
+        assert!(json.contains("\"memory\""));
+        assert_eq!(serde_json::from_str::<TableView>(&json).unwrap(), view);
+        assert_eq!(serde_json::from_str::<TableView>("{}").unwrap(), TableView::default());
+    }
+}
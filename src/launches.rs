@@ -0,0 +1,222 @@
+//! What Studio started, so dataflows can be restarted.
+//!
+//! `dora list` does not say which YAML a dataflow came from. Every start
+//! made through [`start`] is therefore recorded here, keyed by the UUID the
+//! coordinator returned: the request (path, name, env) and a snapshot of the
+//! YAML as it was sent. [`restart`] stops a dataflow and starts its snapshot
+//! again. Records are stored as JSON in `launches.json` next to the settings.
+
+use crate::client::{self, ClientError, StartRequest};
+use crate::dataflow::model::unix_now;
+use crate::settings::studio_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Cached records, loaded on first access
+static LAUNCHES: Mutex<Option<Launches>> = Mutex::new(None);
+
+/// Number of records kept, oldest are dropped first
+pub const MAX_LAUNCHES: usize = 200;
+
+/// One dataflow started by Studio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchRecord {
+    /// The request as sent, with `yaml` holding the content snapshot
+    pub request: StartRequest,
+    /// Unix time of the start
+    pub started_at: u64,
+    /// UUID of the dataflow this one restarted, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart_of: Option<String>,
+}
+
+/// All launch records by dataflow UUID
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Launches {
+    records: BTreeMap<String, LaunchRecord>,
+}
+
+impl Launches {
+    /// Load records from `path`, empty if missing or unreadable
+    pub fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write records to `path`, creating parent directories
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize launches: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Record of a dataflow
+    pub fn get(&self, uuid: &str) -> Option<&LaunchRecord> {
+        self.records.get(uuid)
+    }
+
+    /// Add a record, dropping the oldest beyond [`MAX_LAUNCHES`]
+    pub fn insert(&mut self, uuid: String, record: LaunchRecord) {
+        self.records.insert(uuid, record);
+        while self.records.len() > MAX_LAUNCHES {
+            let oldest = self
+                .records
+                .iter()
+                .min_by_key(|(_, r)| r.started_at)
+                .map(|(uuid, _)| uuid.clone());
+            match oldest {
+                Some(uuid) => self.records.remove(&uuid),
+                None => break,
+            };
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+/// Path of the launch records file
+pub fn launches_path() -> PathBuf {
+    studio_dir().join("launches.json")
+}
+
+/// Record of a dataflow started by Studio
+pub fn get(uuid: &str) -> Option<LaunchRecord> {
+    LAUNCHES
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| Launches::load_from(&launches_path()))
+        .get(uuid)
+        .cloned()
+}
+
+/// Which of the dataflows can be restarted
+pub fn recorded<'a>(uuids: impl IntoIterator<Item = &'a str>) -> HashSet<String> {
+    let mut launches = LAUNCHES.lock().unwrap();
+    let launches = launches.get_or_insert_with(|| Launches::load_from(&launches_path()));
+    uuids
+        .into_iter()
+        .filter(|uuid| launches.get(uuid).is_some())
+        .map(str::to_string)
+        .collect()
+}
+
+fn record(uuid: &str, record: LaunchRecord) {
+    let mut launches = LAUNCHES.lock().unwrap();
+    let launches = launches.get_or_insert_with(|| Launches::load_from(&launches_path()));
+    launches.insert(uuid.to_string(), record);
+    if let Err(e) = launches.save_to(&launches_path()) {
+        eprintln!("[Launches] {}", e);
+    }
+}
+
+/// Snapshot the YAML of `request`, start it and record the launch
+pub fn start(request: &StartRequest) -> Result<String, ClientError> {
+    start_recorded(request, None)
+}
+
+fn start_recorded(request: &StartRequest, restart_of: Option<String>) -> Result<String, ClientError> {
+    let snapshot = StartRequest {
+        yaml: Some(request.read_yaml()?),
+        ..request.clone()
+    };
+    let uuid = client::with_client(|c| c.start(&snapshot))?;
+    record(
+        &uuid,
+        LaunchRecord {
+            request: snapshot,
+            started_at: unix_now(),
+            restart_of,
+        },
+    );
+    Ok(uuid)
+}
+
+/// Stop a dataflow (by UUID or name) if it still runs and start its
+/// recorded YAML again with the same name and env. Returns the new UUID.
+pub fn restart(id: &str) -> Result<String, String> {
+    let dataflows = client::with_client(|c| c.list_dataflows()).map_err(|e| e.to_string())?;
+    let current = dataflows
+        .iter()
+        .find(|df| df.uuid == id)
+        .or_else(|| dataflows.iter().find(|df| df.name == id));
+    let uuid = current.map_or(id, |df| df.uuid.as_str());
+
+    let record = get(uuid).ok_or_else(|| {
+        format!("No launch record for {}: only dataflows started from Studio can be restarted", id)
+    })?;
+
+    if current.is_some_and(|df| df.status.is_active()) {
+        client::with_client(|c| c.stop_dataflow(uuid))
+            .map_err(|e| format!("Failed to stop {}: {}", uuid, e))?;
+    }
+
+    start_recorded(&record.request, Some(uuid.to_string()))
+        .map_err(|e| format!("Starting {} again failed: {}", uuid, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launch(path: &str, started_at: u64) -> LaunchRecord {
+        LaunchRecord {
+            request: StartRequest {
+                name: Some("camera".to_string()),
+                yaml: Some("nodes: []".to_string()),
+                ..StartRequest::new(path)
+            },
+            started_at,
+            restart_of: None,
+        }
+    }
+
+    #[test]
+    fn test_launches_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("launches.json");
+
+        let mut launches = Launches::default();
+        launches.insert("a".to_string(), launch("/flows/a.yml", 1));
+        let mut restarted = launch("/flows/a.yml", 2);
+        restarted.request.env.insert("IMAGE_WIDTH".to_string(), "640".to_string());
+        restarted.restart_of = Some("a".to_string());
+        launches.insert("b".to_string(), restarted);
+        launches.save_to(&path).unwrap();
+
+        let loaded = Launches::load_from(&path);
+        assert_eq!(loaded, launches);
+        assert_eq!(loaded.get("b").unwrap().request.env["IMAGE_WIDTH"], "640");
+        assert_eq!(loaded.get("b").unwrap().restart_of.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_launches_drop_oldest() {
+        let mut launches = Launches::default();
+        for i in 0..MAX_LAUNCHES as u64 + 5 {
+            launches.insert(format!("flow-{}", i), launch("/flows/a.yml", 1000 - i));
+        }
+        assert_eq!(launches.len(), MAX_LAUNCHES);
+        assert!(launches.get("flow-0").is_some());
+        assert!(launches.get(&format!("flow-{}", MAX_LAUNCHES + 4)).is_none());
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        assert!(Launches::load_from(Path::new("/nonexistent/launches.json")).is_empty());
+    }
+}
@@ -3,8 +3,24 @@ pub use makepad_widgets;
 pub mod app;
 pub mod chat;
 pub mod dataflow;
+pub mod logs;
+pub mod metrics;
 pub mod api;
 
-// Tools module only available on native platforms (uses shell commands)
+// Alerts, coordinator client, launch records, poller, settings, tools and log watcher only available on native platforms (TCP, files and shell commands)
+#[cfg(not(target_arch = "wasm32"))]
+pub mod alerts;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod client;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod launches;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod poller;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod settings;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod supervisor;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod tools;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watcher;
@@ -0,0 +1,337 @@
+//! Writing log lines to JSONL, CSV or plain text files.
+
+use super::line::LogLine;
+use super::search::short_id;
+use std::path::{Path, PathBuf};
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON object per line, every field of the line
+    #[default]
+    Jsonl,
+    /// dataflow, node, level, timestamp, target, message and fields columns
+    Csv,
+    /// The lines as the viewer shows them
+    Text,
+}
+
+/// Formats in the order the viewer lists them
+pub const EXPORT_FORMATS: [ExportFormat; 3] = [ExportFormat::Jsonl, ExportFormat::Csv, ExportFormat::Text];
+
+impl ExportFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "jsonl" | "json" | "ndjson" => Some(ExportFormat::Jsonl),
+            "csv" => Some(ExportFormat::Csv),
+            "text" | "txt" | "log" => Some(ExportFormat::Text),
+            _ => None,
+        }
+    }
+
+    /// Format matching the file extension of `path`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::parse(path.extension()?.to_str()?)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Jsonl => "JSONL",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Text => "Text",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Text => "txt",
+        }
+    }
+}
+
+/// Keep lines logged between `since` and `until`, both inclusive.
+///
+/// Timestamps are compared as text, which orders RFC 3339 UTC timestamps
+/// correctly and lets a prefix like `2026-01-20T18:25` stand for a minute.
+/// Lines without a timestamp go with the line before them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeRange {
+    pub since: Option<String>,
+    pub until: Option<String>,
+}
+
+impl TimeRange {
+    pub fn is_empty(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    fn contains(&self, timestamp: &str) -> bool {
+        // A prefix bound covers everything that starts with it
+        self.since.as_deref().is_none_or(|since| timestamp >= since)
+            && self
+                .until
+                .as_deref()
+                .is_none_or(|until| timestamp <= until || timestamp.starts_with(until))
+    }
+
+    pub fn apply(&self, lines: Vec<LogLine>) -> Vec<LogLine> {
+        if self.is_empty() {
+            return lines;
+        }
+        let mut keep = self.since.is_none();
+        lines
+            .into_iter()
+            .filter(|line| {
+                if let Some(timestamp) = &line.timestamp {
+                    keep = self.contains(timestamp);
+                }
+                keep
+            })
+            .collect()
+    }
+}
+
+/// What an export wrote
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportSummary {
+    pub path: PathBuf,
+    pub format: ExportFormat,
+    pub lines: usize,
+    /// First and last timestamp of the lines, if they have any
+    pub first: Option<String>,
+    pub last: Option<String>,
+}
+
+impl std::fmt::Display for ExportSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Exported {} lines as {} to {}",
+            self.lines,
+            self.format.label(),
+            self.path.display()
+        )?;
+        if let (Some(first), Some(last)) = (&self.first, &self.last) {
+            write!(f, " ({} to {})", first, last)?;
+        }
+        Ok(())
+    }
+}
+
+/// Render lines in `format`, with a trailing newline unless empty
+pub fn render(lines: &[LogLine], format: ExportFormat) -> String {
+    let mut out = String::new();
+    match format {
+        ExportFormat::Jsonl => {
+            for line in lines {
+                // A LogLine has only strings and maps, it always serializes
+                out.push_str(&serde_json::to_string(line).unwrap_or_default());
+                out.push('\n');
+            }
+        }
+        ExportFormat::Csv => {
+            out.push_str("dataflow,node,level,timestamp,target,message,fields\n");
+            for line in lines {
+                let fields = line
+                    .fields
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let row = [
+                    line.dataflow_id.as_deref().unwrap_or(""),
+                    line.node.as_deref().unwrap_or(""),
+                    line.level.label(),
+                    line.timestamp.as_deref().unwrap_or(""),
+                    line.target.as_deref().unwrap_or(""),
+                    &line.text,
+                    &fields,
+                ];
+                out.push_str(&row.map(csv_field).join(","));
+                out.push('\n');
+            }
+        }
+        ExportFormat::Text => {
+            // Tell dataflows apart when there is more than one
+            let first_id = lines.iter().find_map(|line| line.dataflow_id.as_deref());
+            let merged = lines
+                .iter()
+                .filter_map(|line| line.dataflow_id.as_deref())
+                .any(|id| Some(id) != first_id);
+            for line in lines {
+                match (&line.dataflow_id, merged) {
+                    (Some(id), true) => out.push_str(&format!("{{{}}} {}", short_id(id), line.display())),
+                    _ => out.push_str(&line.display()),
+                }
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// Quote a CSV field when it holds a comma, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Check where an export may go: an absolute file path (`~/` is expanded)
+/// in an existing folder, not a folder itself, and not an existing file
+/// unless `overwrite`.
+pub fn export_path(path: &str, overwrite: bool) -> Result<PathBuf, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("No export path given".to_string());
+    }
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => match std::env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(rest),
+            None => return Err("Cannot expand ~: HOME is not set".to_string()),
+        },
+        None => PathBuf::from(path),
+    };
+    if !path.is_absolute() {
+        return Err(format!("Export path must be absolute: {}", path.display()));
+    }
+    if path.is_dir() {
+        return Err(format!("{} is a folder, not a file", path.display()));
+    }
+    match path.parent() {
+        Some(parent) if parent.is_dir() => {}
+        Some(parent) => return Err(format!("Folder {} does not exist", parent.display())),
+        None => return Err(format!("Invalid export path: {}", path.display())),
+    }
+    if path.exists() && !overwrite {
+        return Err(format!("{} already exists", path.display()));
+    }
+    Ok(path)
+}
+
+/// Write `lines` to `path`. The file is written next to its final name and
+/// renamed into place, so a failed export never leaves half a file behind.
+pub fn write_export(path: &str, lines: &[LogLine], format: ExportFormat, overwrite: bool) -> Result<ExportSummary, String> {
+    let path = export_path(path, overwrite)?;
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("logs");
+    let temp = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let written = std::fs::write(&temp, render(lines, format)).and_then(|_| std::fs::rename(&temp, &path));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&temp);
+        return Err(format!("Failed to write {}: {}", path.display(), e));
+    }
+
+    let first = lines.iter().find_map(|line| line.timestamp.clone());
+    let last = lines.iter().rev().find_map(|line| line.timestamp.clone());
+    Ok(ExportSummary {
+        path,
+        format,
+        lines: lines.len(),
+        first,
+        last,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::LogLevel;
+
+    fn lines() -> Vec<LogLine> {
+        vec![
+            LogLine {
+                dataflow_id: Some("0a1b2c3d-0000".to_string()),
+                node: Some("camera".to_string()),
+                level: LogLevel::Warn,
+                timestamp: Some("2026-01-20T18:25:28Z".to_string()),
+                text: "frame \"late\", dropped".to_string(),
+                ..Default::default()
+            },
+            LogLine {
+                dataflow_id: Some("0a1b2c3d-0000".to_string()),
+                text: "continued".to_string(),
+                ..Default::default()
+            },
+            LogLine {
+                dataflow_id: Some("9f8e7d6c-0000".to_string()),
+                level: LogLevel::Error,
+                timestamp: Some("2026-01-20T18:26:01Z".to_string()),
+                target: Some("dora_daemon".to_string()),
+                text: "node exited".to_string(),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let csv = render(&lines(), ExportFormat::Csv);
+        let rows: Vec<_> = csv.lines().collect();
+        assert_eq!(rows[0], "dataflow,node,level,timestamp,target,message,fields");
+        assert_eq!(
+            rows[1],
+            r#"0a1b2c3d-0000,camera,WARN,2026-01-20T18:25:28Z,,"frame ""late"", dropped","#
+        );
+        assert_eq!(rows.len(), 4);
+
+        let jsonl = render(&lines(), ExportFormat::Jsonl);
+        let first: LogLine = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+        assert_eq!(first, lines()[0]);
+
+        let text = render(&lines(), ExportFormat::Text);
+        assert!(text.lines().nth(2).unwrap().starts_with("{9f8e7d6c} "));
+        assert_eq!(render(&lines()[..2], ExportFormat::Text).lines().nth(1), Some("continued"));
+    }
+
+    #[test]
+    fn test_time_range() {
+        let range = TimeRange {
+            since: None,
+            until: Some("2026-01-20T18:25".to_string()),
+        };
+        let texts: Vec<_> = range.apply(lines()).into_iter().map(|l| l.text).collect();
+        assert_eq!(texts, vec!["frame \"late\", dropped", "continued"]);
+
+        let range = TimeRange {
+            since: Some("2026-01-20T18:26".to_string()),
+            until: None,
+        };
+        assert_eq!(range.apply(lines()).len(), 1);
+    }
+
+    #[test]
+    fn test_write_export_fails_safely() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs.csv");
+        let path_str = path.to_str().unwrap();
+
+        let summary = write_export(path_str, &lines(), ExportFormat::Csv, false).unwrap();
+        assert_eq!(summary.lines, 3);
+        assert_eq!(summary.first.as_deref(), Some("2026-01-20T18:25:28Z"));
+        assert_eq!(summary.last.as_deref(), Some("2026-01-20T18:26:01Z"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 4);
+
+        // Existing files are kept unless asked to overwrite
+        assert!(write_export(path_str, &[], ExportFormat::Csv, false)
+            .unwrap_err()
+            .contains("already exists"));
+        assert!(write_export(path_str, &[], ExportFormat::Csv, true).is_ok());
+
+        let missing = dir.path().join("missing").join("logs.csv");
+        assert!(write_export(missing.to_str().unwrap(), &lines(), ExportFormat::Csv, false)
+            .unwrap_err()
+            .contains("does not exist"));
+        assert!(export_path(dir.path().to_str().unwrap(), true).unwrap_err().contains("is a folder"));
+        assert!(export_path("logs.csv", false).unwrap_err().contains("must be absolute"));
+        assert!(export_path("  ", false).is_err());
+
+        // Only the export itself is left in the folder
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert_eq!(ExportFormat::from_path(&path), Some(ExportFormat::Csv));
+    }
+}
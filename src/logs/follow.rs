@@ -0,0 +1,363 @@
+//! Live log following.
+//!
+//! A [`LogFollower`] reads a dataflow's log stream on its own thread into a
+//! bounded [`LogBuffer`] that the UI drains. When the UI falls behind, the
+//! oldest lines are dropped and counted instead of piling up in memory, and
+//! only one "lines ready" notification is outstanding at a time.
+
+use super::line::{LogLevel, LogLine};
+use crate::client::{self, CliClient, ClientError, LogMessage, LogSubscription};
+use crate::settings::CoordinatorEndpoint;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Lines kept between two drains
+pub const FOLLOW_BUFFER_LINES: usize = 5_000;
+
+/// Posted by the follower when new lines (or the end of the stream) can be drained
+#[derive(Debug, Clone)]
+pub struct FollowReady {
+    pub dataflow_id: String,
+}
+
+/// Ring buffer of the newest lines, counting the ones it had to drop
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    lines: VecDeque<LogLine>,
+    capacity: usize,
+    dropped: u64,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity.min(1024)),
+            capacity: capacity.max(1),
+            dropped: 0,
+        }
+    }
+
+    /// Add a line, dropping the oldest one when full
+    pub fn push(&mut self, line: LogLine) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Lines dropped since the last drain
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Take all lines and reset the drop counter
+    pub fn drain(&mut self) -> (Vec<LogLine>, u64) {
+        let dropped = std::mem::take(&mut self.dropped);
+        (self.lines.drain(..).collect(), dropped)
+    }
+}
+
+/// What a drain returns
+#[derive(Debug, Clone, Default)]
+pub struct FollowBatch {
+    pub lines: Vec<LogLine>,
+    /// Lines lost because the buffer was full
+    pub dropped: u64,
+    /// The stream ended, no more lines will come
+    pub finished: bool,
+    /// Why the stream could not be opened or broke off
+    pub error: Option<String>,
+}
+
+/// A stream of log lines plus a way to end it from another thread
+pub struct FollowSource {
+    lines: Box<dyn Iterator<Item = LogLine> + Send>,
+    close: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl FollowSource {
+    /// Lines that end on their own, e.g. in tests
+    pub fn new(lines: impl Iterator<Item = LogLine> + Send + 'static) -> Self {
+        Self {
+            lines: Box::new(lines),
+            close: None,
+        }
+    }
+
+    /// Native coordinator subscription
+    pub fn from_subscription(subscription: LogSubscription) -> Result<Self, ClientError> {
+        let close = subscription.closer()?;
+        Ok(Self {
+            lines: Box::new(subscription.map(|message| LogLine::from(&message))),
+            close: Some(Box::new(close)),
+        })
+    }
+
+    /// `dora logs --follow` output, for coordinators only the CLI can follow
+    pub fn from_cli(client: &CliClient, dataflow_id: &str, known_nodes: Vec<String>) -> Result<Self, ClientError> {
+        let mut child = client.follow_logs(dataflow_id)?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ClientError::CommandFailed("dora logs has no stdout".to_string()))?;
+        let lines = BufReader::new(stdout)
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .map(move |line| LogLine::parse(&line, &known_nodes));
+        Ok(Self {
+            lines: Box::new(lines),
+            close: Some(Box::new(move || {
+                let _ = child.kill();
+                let _ = child.wait();
+            })),
+        })
+    }
+
+    /// Open the best available stream for a dataflow
+    pub fn open(endpoint: &CoordinatorEndpoint, dataflow_id: &str, known_nodes: Vec<String>) -> Result<Self, ClientError> {
+        match client::client_for(endpoint).subscribe_logs(dataflow_id) {
+            Ok(subscription) => Self::from_subscription(subscription),
+            Err(ClientError::Unsupported(_)) => {
+                Self::from_cli(&CliClient::with_coordinator(endpoint.clone()), dataflow_id, known_nodes)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl From<&LogMessage> for LogLine {
+    fn from(message: &LogMessage) -> Self {
+        LogLine {
+            node: message.node_id.clone(),
+            level: LogLevel::parse(&message.level).unwrap_or_default(),
+            timestamp: message.timestamp.clone(),
+            text: message.message.clone(),
+            target: message.target.clone(),
+            dataflow_id: message.dataflow_id.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+struct Shared {
+    buffer: Mutex<LogBuffer>,
+    /// A notification was sent and the buffer was not drained since
+    pending: AtomicBool,
+    received: AtomicU64,
+    stopped: AtomicBool,
+    finished: AtomicBool,
+    error: Mutex<Option<String>>,
+    close: Mutex<Option<Box<dyn FnOnce() + Send>>>,
+}
+
+/// Follows one log stream on a background thread. Dropping it stops the stream.
+pub struct LogFollower {
+    shared: Arc<Shared>,
+}
+
+impl LogFollower {
+    /// Open a source with `open` on a new thread and buffer up to `capacity`
+    /// of its lines. `notify` runs when lines are ready after a drain.
+    pub fn spawn(
+        capacity: usize,
+        notify: impl Fn() + Send + 'static,
+        open: impl FnOnce() -> Result<FollowSource, ClientError> + Send + 'static,
+    ) -> Self {
+        let shared = Arc::new(Shared {
+            buffer: Mutex::new(LogBuffer::new(capacity)),
+            pending: AtomicBool::new(false),
+            received: AtomicU64::new(0),
+            stopped: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            error: Mutex::new(None),
+            close: Mutex::new(None),
+        });
+
+        let thread_shared = shared.clone();
+        std::thread::spawn(move || {
+            let shared = thread_shared;
+            let notify_once = || {
+                if !shared.pending.swap(true, Ordering::AcqRel) {
+                    notify();
+                }
+            };
+
+            match open() {
+                Ok(source) => {
+                    let FollowSource { lines, close } = source;
+                    *shared.close.lock().unwrap() = close;
+                    // `stop` may have run while the source was opening
+                    if shared.stopped.load(Ordering::Acquire) {
+                        if let Some(close) = shared.close.lock().unwrap().take() {
+                            close();
+                        }
+                    }
+                    for line in lines {
+                        if shared.stopped.load(Ordering::Acquire) {
+                            break;
+                        }
+                        shared.buffer.lock().unwrap().push(line);
+                        shared.received.fetch_add(1, Ordering::Release);
+                        notify_once();
+                    }
+                }
+                Err(e) => {
+                    eprintln!("[LogFollower] Failed to open log stream: {}", e);
+                    *shared.error.lock().unwrap() = Some(e.to_string());
+                }
+            }
+            shared.finished.store(true, Ordering::Release);
+            // The end of the stream is news even with nothing buffered
+            shared.pending.store(false, Ordering::Release);
+            notify_once();
+        });
+
+        Self { shared }
+    }
+
+    /// Follow a dataflow's logs on `endpoint`, posting [`FollowReady`] through `notify`
+    pub fn follow_dataflow(
+        endpoint: CoordinatorEndpoint,
+        dataflow_id: &str,
+        known_nodes: Vec<String>,
+        notify: impl Fn(FollowReady) + Send + 'static,
+    ) -> Self {
+        let id = dataflow_id.to_string();
+        let ready = FollowReady {
+            dataflow_id: dataflow_id.to_string(),
+        };
+        Self::spawn(
+            FOLLOW_BUFFER_LINES,
+            move || notify(ready.clone()),
+            move || FollowSource::open(&endpoint, &id, known_nodes),
+        )
+    }
+
+    /// Take the buffered lines. The next line will notify again.
+    pub fn drain(&self) -> FollowBatch {
+        let (lines, dropped) = {
+            let mut buffer = self.shared.buffer.lock().unwrap();
+            self.shared.pending.store(false, Ordering::Release);
+            buffer.drain()
+        };
+        FollowBatch {
+            lines,
+            dropped,
+            finished: self.shared.finished.load(Ordering::Acquire),
+            error: self.shared.error.lock().unwrap().clone(),
+        }
+    }
+
+    /// Lines read from the stream so far, including dropped ones
+    pub fn received(&self) -> u64 {
+        self.shared.received.load(Ordering::Acquire)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.shared.finished.load(Ordering::Acquire)
+    }
+
+    /// End the stream. Lines already buffered can still be drained.
+    pub fn stop(&self) {
+        self.shared.stopped.store(true, Ordering::Release);
+        if let Some(close) = self.shared.close.lock().unwrap().take() {
+            close();
+        }
+    }
+}
+
+impl Drop for LogFollower {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+
+    fn line(text: &str) -> LogLine {
+        LogLine {
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        for _ in 0..200 {
+            if condition() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        panic!("condition never became true");
+    }
+
+    #[test]
+    fn test_log_buffer_drops_oldest() {
+        let mut buffer = LogBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(line(&i.to_string()));
+        }
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.dropped(), 2);
+
+        let (lines, dropped) = buffer.drain();
+        let texts: Vec<_> = lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, vec!["2", "3", "4"]);
+        assert_eq!(dropped, 2);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.dropped(), 0);
+    }
+
+    #[test]
+    fn test_follower_notifies_once_per_drain() {
+        let (tx, rx) = channel();
+        let (lines_tx, lines_rx) = channel::<LogLine>();
+        let follower = LogFollower::spawn(10, move || tx.send(()).unwrap(), move || {
+            Ok(FollowSource::new(lines_rx.into_iter()))
+        });
+
+        for i in 0..3 {
+            lines_tx.send(line(&i.to_string())).unwrap();
+        }
+        wait_until(|| follower.received() == 3);
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(rx.try_recv().is_err());
+
+        assert_eq!(follower.drain().lines.len(), 3);
+        lines_tx.send(line("3")).unwrap();
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+
+        // Closing the source finishes the follower and notifies once more
+        drop(lines_tx);
+        wait_until(|| follower.is_finished());
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        let batch = follower.drain();
+        assert!(batch.finished);
+        assert_eq!(batch.lines.len(), 1);
+    }
+
+    #[test]
+    fn test_follower_reports_open_errors() {
+        let follower = LogFollower::spawn(10, || {}, || Err(ClientError::Unsupported("no stream".to_string())));
+        wait_until(|| follower.is_finished());
+        let batch = follower.drain();
+        assert!(batch.finished);
+        assert!(batch.error.unwrap().contains("no stream"));
+    }
+}
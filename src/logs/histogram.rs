@@ -0,0 +1,137 @@
+//! Log lines per level over time.
+
+use super::line::{LogLevel, LogLine};
+use crate::dataflow::model::parse_rfc3339;
+
+/// Bucket widths in seconds, the smallest that fits is used
+const BUCKET_STEPS: [u64; 18] = [
+    1, 2, 5, 10, 15, 30, 60, 120, 300, 600, 900, 1800, 3600, 7200, 10800, 21600, 43200, 86400,
+];
+
+/// Counts of lines per level in equal time buckets
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LevelHistogram {
+    /// Start of the first bucket, in epoch seconds
+    pub start: u64,
+    pub bucket_secs: u64,
+    /// Counts per bucket, indexed like [`LogLevel::ALL`]
+    pub buckets: Vec<[usize; 5]>,
+}
+
+impl LevelHistogram {
+    /// Bucket `lines` into at most `max_buckets` buckets. Lines without a
+    /// timestamp count in the bucket of the line before them.
+    pub fn build<'a>(lines: impl IntoIterator<Item = &'a LogLine>, max_buckets: usize) -> Self {
+        let mut stamped = Vec::new();
+        let mut last = None;
+        for line in lines {
+            if let Some(at) = line.timestamp.as_deref().and_then(parse_rfc3339) {
+                last = Some(at);
+            }
+            if let Some(at) = last {
+                stamped.push((at, line.level));
+            }
+        }
+        let (Some(min), Some(max)) = (
+            stamped.iter().map(|(at, _)| *at).min(),
+            stamped.iter().map(|(at, _)| *at).max(),
+        ) else {
+            return Self::default();
+        };
+
+        let max_buckets = max_buckets.max(1) as u64;
+        let bucket_secs = BUCKET_STEPS
+            .into_iter()
+            .find(|step| (max / step - min / step) < max_buckets)
+            .unwrap_or_else(|| (max - min) / max_buckets + 1);
+        let start = min / bucket_secs * bucket_secs;
+        let mut buckets = vec![[0; 5]; ((max - start) / bucket_secs + 1) as usize];
+        for (at, level) in stamped {
+            buckets[((at - start) / bucket_secs) as usize][level_index(level)] += 1;
+        }
+        Self {
+            start,
+            bucket_secs,
+            buckets,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Lines in the fullest bucket
+    pub fn max_total(&self) -> usize {
+        self.buckets.iter().map(|counts| counts.iter().sum()).max().unwrap_or(0)
+    }
+
+    /// Lines of `level` in bucket `index`
+    pub fn count(&self, index: usize, level: LogLevel) -> usize {
+        self.buckets.get(index).map_or(0, |counts| counts[level_index(level)])
+    }
+
+    /// Start of bucket `index`, in epoch seconds
+    pub fn bucket_start(&self, index: usize) -> u64 {
+        self.start + index as u64 * self.bucket_secs
+    }
+
+    /// Lines of each level over all buckets
+    pub fn totals(&self) -> [usize; 5] {
+        let mut totals = [0; 5];
+        for counts in &self.buckets {
+            for (total, count) in totals.iter_mut().zip(counts) {
+                *total += count;
+            }
+        }
+        totals
+    }
+}
+
+fn level_index(level: LogLevel) -> usize {
+    LogLevel::ALL.iter().position(|l| *l == level).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(timestamp: Option<&str>, level: LogLevel) -> LogLine {
+        LogLine {
+            timestamp: timestamp.map(str::to_string),
+            level,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_buckets_by_level() {
+        let lines = vec![
+            line(None, LogLevel::Info),
+            line(Some("2026-01-22T10:57:01Z"), LogLevel::Info),
+            line(None, LogLevel::Info),
+            line(Some("2026-01-22T10:57:09.5Z"), LogLevel::Warn),
+            line(Some("2026-01-22T10:58:59Z"), LogLevel::Error),
+        ];
+        let histogram = LevelHistogram::build(&lines, 60);
+        // Two minutes in at most 60 buckets: 2 second buckets from 10:57:00
+        assert_eq!(histogram.bucket_secs, 2);
+        assert_eq!(histogram.buckets.len(), 60);
+        assert_eq!(histogram.count(0, LogLevel::Info), 2);
+        assert_eq!(histogram.count(4, LogLevel::Warn), 1);
+        assert_eq!(histogram.count(59, LogLevel::Error), 1);
+        assert_eq!(histogram.max_total(), 2);
+        // The line before the first timestamp has no time
+        assert_eq!(histogram.totals(), [0, 0, 2, 1, 1]);
+
+        let coarse = LevelHistogram::build(&lines, 10);
+        assert_eq!(coarse.bucket_secs, 15);
+        assert_eq!(coarse.buckets.len(), 8);
+        assert_eq!(coarse.bucket_start(1) - coarse.bucket_start(0), 15);
+    }
+
+    #[test]
+    fn test_no_timestamps() {
+        let lines = vec![line(None, LogLevel::Error)];
+        assert!(LevelHistogram::build(&lines, 60).is_empty());
+    }
+}
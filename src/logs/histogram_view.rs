@@ -0,0 +1,174 @@
+use makepad_widgets::*;
+
+use super::histogram::LevelHistogram;
+use super::line::LogLevel;
+use crate::dataflow::dataflow_table::hex_color;
+use crate::dataflow::model::format_utc;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    // Stacked bars of lines per level over time, errors on top
+    pub LevelHistogramView = {{LevelHistogramView}} {
+        width: Fill, height: 44
+        margin: { left: 12, right: 12, bottom: 4 }
+        draw_bg: { color: #f8fafc }
+        draw_bar: { color: #2563eb }
+    }
+}
+
+/// Bar colors from the bottom up: info and below, warnings, errors
+const BAR_COLORS: [(LogLevel, u32); 5] = [
+    (LogLevel::Trace, 0xcbd5e1),
+    (LogLevel::Debug, 0xcbd5e1),
+    (LogLevel::Info, 0x93c5fd),
+    (LogLevel::Warn, 0xfbbf24),
+    (LogLevel::Error, 0xef4444),
+];
+
+/// Gap between bars, in pixels
+const BAR_GAP: f64 = 1.0;
+
+/// What hovering the histogram tells the viewer
+#[derive(Clone, Debug, PartialEq, DefaultNone)]
+pub enum LevelHistogramAction {
+    None,
+    /// Time and counts of the hovered bar
+    Hover(String),
+    Leave,
+}
+
+/// Level histogram above the log list. Hovering a bar tells its time and counts.
+#[derive(Live, LiveHook, Widget)]
+pub struct LevelHistogramView {
+    #[walk]
+    walk: Walk,
+    #[redraw]
+    #[live]
+    draw_bg: DrawColor,
+    #[live]
+    draw_bar: DrawColor,
+    #[rust]
+    histogram: LevelHistogram,
+    /// Where the bars were last drawn
+    #[rust]
+    rect: Rect,
+    #[rust]
+    hovered: Option<usize>,
+}
+
+impl Widget for LevelHistogramView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        let uid = self.widget_uid();
+        match event.hits(cx, self.draw_bg.area()) {
+            Hit::FingerHoverIn(e) | Hit::FingerHoverOver(e) => {
+                let hovered = self.bucket_at(e.abs.x);
+                if hovered != self.hovered {
+                    self.hovered = hovered;
+                    let action = match self.hover_text() {
+                        Some(text) => LevelHistogramAction::Hover(text),
+                        None => LevelHistogramAction::Leave,
+                    };
+                    cx.widget_action(uid, &scope.path, action);
+                    self.redraw(cx);
+                }
+            }
+            Hit::FingerHoverOut(_) => {
+                self.hovered = None;
+                cx.widget_action(uid, &scope.path, LevelHistogramAction::Leave);
+                self.redraw(cx);
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle(walk);
+        self.rect = rect;
+        self.draw_bg.draw_abs(cx, rect);
+
+        let max = self.histogram.max_total();
+        let count = self.histogram.buckets.len();
+        if max == 0 || count == 0 {
+            return DrawStep::done();
+        }
+        let bar_width = rect.size.x / count as f64;
+        for index in 0..count {
+            let x = rect.pos.x + index as f64 * bar_width;
+            let mut bottom = rect.pos.y + rect.size.y;
+            for (level, color) in BAR_COLORS {
+                let lines = self.histogram.count(index, level);
+                if lines == 0 {
+                    continue;
+                }
+                let height = rect.size.y * lines as f64 / max as f64;
+                let mut color = hex_color(color);
+                if self.hovered == Some(index) {
+                    color.w = 0.7;
+                }
+                self.draw_bar.color = color;
+                self.draw_bar.draw_abs(
+                    cx,
+                    Rect {
+                        pos: dvec2(x, bottom - height),
+                        size: dvec2((bar_width - BAR_GAP).max(1.0), height),
+                    },
+                );
+                bottom -= height;
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl LevelHistogramView {
+    fn bucket_at(&self, x: f64) -> Option<usize> {
+        let count = self.histogram.buckets.len();
+        if count == 0 || self.rect.size.x <= 0.0 {
+            return None;
+        }
+        let index = ((x - self.rect.pos.x) / self.rect.size.x * count as f64).floor();
+        (index >= 0.0 && (index as usize) < count).then_some(index as usize)
+    }
+
+    /// "2026-01-22 10:57:00 UTC +15s: 3 errors, 12 warnings, 240 lines" for the hovered bar
+    fn hover_text(&self) -> Option<String> {
+        let index = self.hovered?;
+        let counts = self.histogram.buckets.get(index)?;
+        let total: usize = counts.iter().sum();
+        let errors = self.histogram.count(index, LogLevel::Error);
+        let warnings = self.histogram.count(index, LogLevel::Warn);
+        Some(format!(
+            "{} +{}s: {} errors, {} warnings, {} lines",
+            format_utc(self.histogram.bucket_start(index)),
+            self.histogram.bucket_secs,
+            errors,
+            warnings,
+            total
+        ))
+    }
+}
+
+impl LevelHistogramViewRef {
+    /// Show a new histogram
+    pub fn set_histogram(&self, cx: &mut Cx, histogram: LevelHistogram) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.histogram = histogram;
+            inner.hovered = None;
+            inner.redraw(cx);
+        }
+    }
+
+    /// Check if a bar was hovered or left
+    pub fn hover(&self, actions: &Actions) -> Option<LevelHistogramAction> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .map(|action| action.cast())
+            .find(|action| *action != LevelHistogramAction::None)
+    }
+}
@@ -0,0 +1,412 @@
+//! Log lines as shown in the log viewer.
+//!
+//! The coordinator and daemon log in `tracing`'s JSON format, one object per
+//! line with `timestamp`, `level`, `target`, `fields` and the current `span`.
+//! [`LogLine::parse`] reads those into structured lines and falls back to
+//! plain text for node output and older CLIs.
+
+use crate::dataflow::model::looks_like_uuid;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Severity of a log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// All levels, least severe first
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warn,
+        LogLevel::Error,
+    ];
+
+    /// Parse a level name (`INFO`, `warning`, `Stderr`...)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "TRACE" => Some(LogLevel::Trace),
+            "DEBUG" => Some(LogLevel::Debug),
+            "INFO" | "STDOUT" => Some(LogLevel::Info),
+            "WARN" | "WARNING" => Some(LogLevel::Warn),
+            "ERROR" | "ERR" | "STDERR" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Span keys that can hold the dataflow UUID
+const DATAFLOW_KEYS: [&str; 3] = ["dataflow_id", "dataflow", "uuid"];
+
+/// Span keys that can hold the node id
+const NODE_KEYS: [&str; 3] = ["node_id", "node", "self.node_id"];
+
+/// One line of a dataflow's logs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogLine {
+    /// Node that wrote the line, if known
+    pub node: Option<String>,
+    pub level: LogLevel,
+    /// Timestamp as written in the log, if any
+    pub timestamp: Option<String>,
+    /// Message, or the whole line for plain text
+    pub text: String,
+    /// Module that logged the line, e.g. `dora_daemon::coordinator`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Dataflow the line belongs to, when its span says so
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dataflow_id: Option<String>,
+    /// Event fields besides the message
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+    /// Fields of the span the line was logged in, with its `name`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub span: BTreeMap<String, String>,
+}
+
+impl LogLine {
+    /// Parse one line of logs, JSON tracing or plain text
+    pub fn parse(line: &str, known_nodes: &[String]) -> Self {
+        Self::parse_json(line).unwrap_or_else(|| Self::parse_text(line, known_nodes))
+    }
+
+    /// Parse a JSON tracing line. `None` for anything else, including JSON
+    /// without a `level` that a node printed.
+    pub fn parse_json(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if !line.starts_with('{') {
+            return None;
+        }
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        let object = value.as_object()?;
+        let level = LogLevel::parse(object.get("level")?.as_str()?)?;
+
+        let mut fields = object
+            .get("fields")
+            .and_then(|f| f.as_object())
+            .map(string_map)
+            .unwrap_or_default();
+        // `flatten_event` puts the message next to the level
+        let text = fields
+            .remove("message")
+            .or_else(|| object.get("message").map(value_text))
+            .unwrap_or_default();
+
+        let span = object
+            .get("span")
+            .and_then(|s| s.as_object())
+            .map(string_map)
+            .unwrap_or_default();
+        // Enclosing spans, innermost last; the current span is searched first
+        let mut scopes = vec![span.clone(), fields.clone()];
+        if let Some(spans) = object.get("spans").and_then(|s| s.as_array()) {
+            scopes.extend(spans.iter().rev().filter_map(|s| s.as_object()).map(string_map));
+        }
+        let dataflow_id = scopes.iter().find_map(|scope| {
+            DATAFLOW_KEYS
+                .iter()
+                .filter_map(|key| scope.get(*key))
+                .find_map(|value| uuid_in(value))
+        });
+        let node = scopes.iter().find_map(|scope| {
+            NODE_KEYS
+                .iter()
+                .filter_map(|key| scope.get(*key))
+                .find_map(|value| debug_inner(value))
+        });
+
+        Some(Self {
+            node,
+            level,
+            timestamp: object.get("timestamp").and_then(|t| t.as_str()).map(str::to_string),
+            text,
+            target: object.get("target").and_then(|t| t.as_str()).map(str::to_string),
+            dataflow_id,
+            fields,
+            span,
+        })
+    }
+
+    /// Parse one line of plain-text logs.
+    ///
+    /// Recognizes a `[node]` or `node:` prefix when `node` is one of
+    /// `known_nodes`, and a level word among the first few tokens.
+    pub fn parse_text(line: &str, known_nodes: &[String]) -> Self {
+        let mut text = line.trim_end();
+        let mut node = None;
+
+        if let Some(rest) = text.strip_prefix('[') {
+            if let Some((prefix, rest)) = rest.split_once(']') {
+                if known_nodes.iter().any(|n| n == prefix) {
+                    node = Some(prefix.to_string());
+                    text = rest.trim_start();
+                }
+            }
+        }
+        if node.is_none() {
+            if let Some((prefix, rest)) = text.split_once(": ") {
+                if known_nodes.iter().any(|n| n == prefix) {
+                    node = Some(prefix.to_string());
+                    text = rest;
+                }
+            }
+        }
+
+        let level = text
+            .split_whitespace()
+            .take(4)
+            .find_map(|token| LogLevel::parse(token.trim_matches(|c: char| !c.is_ascii_alphabetic())))
+            .unwrap_or_default();
+
+        Self {
+            node,
+            level,
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Lines of a `dora logs` reply. `node` is set on every line when the
+    /// logs were fetched for a single node.
+    pub fn parse_all(text: &str, node: Option<&str>, known_nodes: &[String]) -> Vec<Self> {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let mut parsed = Self::parse(line, known_nodes);
+                if parsed.node.is_none() {
+                    parsed.node = node.map(str::to_string);
+                }
+                parsed
+            })
+            .collect()
+    }
+
+    /// The line as plain text, e.g. for the clipboard. Structured lines get
+    /// their timestamp, level and target back.
+    pub fn display(&self) -> String {
+        let mut out = String::new();
+        if let Some(timestamp) = &self.timestamp {
+            out.push_str(&format!("{} {} ", timestamp, self.level));
+            if let Some(target) = &self.target {
+                out.push_str(&format!("{}: ", target));
+            }
+        }
+        if let Some(node) = &self.node {
+            out.push_str(&format!("[{}] ", node));
+        }
+        out.push_str(&self.text);
+        for (key, value) in &self.fields {
+            out.push_str(&format!(" {}={}", key, value));
+        }
+        out
+    }
+
+    /// Case-insensitive search in the message, node, target and fields.
+    /// `needle` must already be lowercase.
+    pub fn contains(&self, needle: &str) -> bool {
+        let hit = |text: &str| text.to_lowercase().contains(needle);
+        hit(&self.text)
+            || self.node.as_deref().is_some_and(hit)
+            || self.target.as_deref().is_some_and(hit)
+            || self.fields.values().any(|value| hit(value))
+    }
+}
+
+/// JSON object values as text
+fn string_map(object: &serde_json::Map<String, serde_json::Value>) -> BTreeMap<String, String> {
+    object.iter().map(|(key, value)| (key.clone(), value_text(value))).collect()
+}
+
+/// Strings without quotes, anything else as JSON
+fn value_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The dataflow UUID in a span value like `019be55a-...` or
+/// `DataflowId(019be55a-...)`. Long values such as whole descriptors are skipped.
+fn uuid_in(value: &str) -> Option<String> {
+    if value.len() > 64 {
+        return None;
+    }
+    value
+        .split(|c: char| !(c.is_ascii_hexdigit() || c == '-'))
+        .find(|token| looks_like_uuid(token))
+        .map(str::to_string)
+}
+
+/// `camera` from `camera`, `NodeId("camera")` or `Some(NodeId("camera"))`
+fn debug_inner(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value == "None" || value.len() > 128 {
+        return None;
+    }
+    let inner = match (value.find('"'), value.rfind('"')) {
+        (Some(start), Some(end)) if end > start => &value[start + 1..end],
+        _ => value,
+    };
+    Some(inner.to_string())
+}
+
+/// Which lines the log viewer shows
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogFilter {
+    /// Only lines of this node
+    pub node: Option<String>,
+    /// Only lines at least this severe
+    pub min_level: Option<LogLevel>,
+}
+
+impl LogFilter {
+    pub fn matches(&self, line: &LogLine) -> bool {
+        if self.node.is_some() && line.node != self.node {
+            return false;
+        }
+        self.min_level.is_none_or(|min| line.level >= min)
+    }
+
+    /// Indices of the lines that pass the filter
+    pub fn apply(&self, lines: &[LogLine]) -> Vec<usize> {
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| self.matches(line))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes() -> Vec<String> {
+        vec!["camera".to_string(), "plot".to_string()]
+    }
+
+    #[test]
+    fn test_parse_text() {
+        let line = LogLine::parse_text("[camera] 10:57:48 WARN frame dropped", &nodes());
+        assert_eq!(line.node.as_deref(), Some("camera"));
+        assert_eq!(line.level, LogLevel::Warn);
+        assert_eq!(line.text, "10:57:48 WARN frame dropped");
+
+        let line = LogLine::parse_text("plot: ERROR: no image", &nodes());
+        assert_eq!(line.node.as_deref(), Some("plot"));
+        assert_eq!(line.level, LogLevel::Error);
+
+        // Unknown prefixes stay part of the text
+        let line = LogLine::parse_text("[main] started", &nodes());
+        assert_eq!(line.node, None);
+        assert_eq!(line.level, LogLevel::Info);
+        assert_eq!(line.display(), "[main] started");
+    }
+
+    #[test]
+    fn test_parse_all_uses_node_hint() {
+        let lines = LogLine::parse_all("first\n\n[plot] second\n", Some("camera"), &nodes());
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].node.as_deref(), Some("camera"));
+        assert_eq!(lines[1].node.as_deref(), Some("plot"));
+    }
+
+    #[test]
+    fn test_parse_json_tracing_line() {
+        let line = LogLine::parse(
+            r#"{"timestamp":"2026-01-20T18:25:28.879364Z","level":"WARN","fields":{"message":"Daemon listen port already in use.","port":53291},"target":"dora_daemon::local_listener","span":{"dataflow_id":"019be55a-71c5-798d-a3ee-e549624e874b","node_id":"NodeId(\"camera\")","name":"spawn_node"},"spans":[{"name":"run_inner"}]}"#,
+            &nodes(),
+        );
+        assert_eq!(line.level, LogLevel::Warn);
+        assert_eq!(line.timestamp.as_deref(), Some("2026-01-20T18:25:28.879364Z"));
+        assert_eq!(line.target.as_deref(), Some("dora_daemon::local_listener"));
+        assert_eq!(line.text, "Daemon listen port already in use.");
+        assert_eq!(line.fields.get("port").map(String::as_str), Some("53291"));
+        assert_eq!(line.span.get("name").map(String::as_str), Some("spawn_node"));
+        assert_eq!(line.dataflow_id.as_deref(), Some("019be55a-71c5-798d-a3ee-e549624e874b"));
+        assert_eq!(line.node.as_deref(), Some("camera"));
+        assert_eq!(
+            line.display(),
+            "2026-01-20T18:25:28.879364Z WARN dora_daemon::local_listener: [camera] Daemon listen port already in use. port=53291"
+        );
+        assert!(line.contains("listen port"));
+        assert!(line.contains("local_listener"));
+        assert!(!line.contains("zenoh"));
+    }
+
+    #[test]
+    fn test_parse_falls_back_to_text() {
+        // JSON a node printed is not a tracing record
+        let line = LogLine::parse(r#"{"frame": 3}"#, &nodes());
+        assert_eq!(line.text, r#"{"frame": 3}"#);
+        assert_eq!(line.timestamp, None);
+
+        let line = LogLine::parse("[plot] ERROR no image", &nodes());
+        assert_eq!(line.node.as_deref(), Some("plot"));
+        assert_eq!(line.level, LogLevel::Error);
+        assert_eq!(line.target, None);
+    }
+
+    #[test]
+    fn test_parse_coordinator_logs() {
+        // The spawn span carries the whole descriptor, which is not an id
+        let lines = LogLine::parse_all(include_str!("../../out/dora-coordinator.txt"), None, &[]);
+        assert!(!lines.is_empty());
+        assert!(lines.iter().all(|line| line.timestamp.is_some() && line.target.is_some()));
+        let spawn = lines
+            .iter()
+            .find(|line| line.text.starts_with("successfully triggered dataflow spawn"))
+            .unwrap();
+        assert_eq!(spawn.span.get("name").map(String::as_str), Some("spawn_dataflow"));
+        assert_eq!(spawn.dataflow_id, None);
+    }
+
+    #[test]
+    fn test_log_filter() {
+        let lines = LogLine::parse_all(
+            "[camera] INFO a\n[camera] ERROR b\n[plot] WARN c\n[plot] DEBUG d",
+            None,
+            &nodes(),
+        );
+        assert_eq!(LogFilter::default().apply(&lines), vec![0, 1, 2, 3]);
+
+        let warn = LogFilter {
+            min_level: Some(LogLevel::Warn),
+            ..Default::default()
+        };
+        assert_eq!(warn.apply(&lines), vec![1, 2]);
+
+        let plot = LogFilter {
+            node: Some("plot".to_string()),
+            min_level: Some(LogLevel::Info),
+        };
+        assert_eq!(plot.apply(&lines), vec![2]);
+    }
+}
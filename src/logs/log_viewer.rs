@@ -0,0 +1,969 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+
+use super::export::{write_export, ExportFormat, EXPORT_FORMATS};
+use super::histogram::LevelHistogram;
+use super::histogram_view::{LevelHistogramAction, LevelHistogramViewWidgetRefExt};
+use super::line::{LogFilter, LogLevel, LogLine};
+use super::patterns::{self, LogPattern};
+use super::search::{merge_by_timestamp, short_id, LogSearch, MatchCursor};
+#[cfg(not(target_arch = "wasm32"))]
+use super::system::SystemLogs;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::logs::histogram_view::LevelHistogramView;
+
+    PANEL_BG = #ffffff
+    TOOLBAR_BG = #f1f5f9
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    ROW_BG = #ffffff
+
+    ViewerButton = <Button> {
+        width: Fit, height: 28
+        padding: { left: 10, right: 10 }
+        draw_text: { text_style: { font_size: 11.0 } }
+    }
+
+    // One log line: level, node, then the text
+    LogLineRow = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        spacing: 8
+        padding: { left: 12, right: 12, top: 2, bottom: 2 }
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+
+        level_label = <Label> {
+            width: 44, height: Fit
+            draw_text: { text_style: { font_size: 10.0 } }
+        }
+        node_label = <Label> {
+            width: 110, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+                wrap: Ellipsis
+            }
+        }
+        text_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 10.0 }
+                wrap: Ellipsis
+            }
+        }
+    }
+
+    // One pattern: how often, worst level, template, when
+    PatternRow = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        spacing: 8
+        padding: { left: 12, right: 12, top: 3, bottom: 3 }
+
+        count_label = <Label> {
+            width: 52, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 10.0 }
+            }
+        }
+        level_label = <Label> {
+            width: 44, height: Fit
+            draw_text: { text_style: { font_size: 10.0 } }
+        }
+        template_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 10.0 }
+                wrap: Word
+            }
+        }
+        seen_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+            }
+        }
+    }
+
+    pub LogViewer = {{LogViewer}} {
+        visible: false
+        width: 520, height: Fill
+        flow: Down
+        show_bg: true
+        draw_bg: { color: (PANEL_BG) }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+            padding: { left: 12, right: 12, top: 8, bottom: 8 }
+            show_bg: true
+            draw_bg: { color: (TOOLBAR_BG) }
+
+            // Dataflow logs, or coordinator and daemon logs
+            dataflow_tab = <ViewerButton> { text: "Dataflow" }
+            system_tab = <ViewerButton> { text: "System" }
+            title_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 13.0 }
+                    wrap: Ellipsis
+                }
+                text: "Logs"
+            }
+            reload_button = <ViewerButton> { text: "Reload" }
+            dock_button = <ViewerButton> { text: "Dock bottom" }
+            close_button = <ViewerButton> { text: "Close" }
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+            padding: { left: 12, right: 12, top: 6, bottom: 6 }
+
+            node_dropdown = <DropDown> {
+                width: 150, height: 28
+                labels: ["All nodes"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            level_dropdown = <DropDown> {
+                width: 130, height: 28
+                labels: ["All levels", "Errors", "Warnings+", "Info+", "Debug+"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            follow_toggle = <CheckBox> { text: "Follow" }
+            wrap_toggle = <CheckBox> { text: "Wrap" }
+            patterns_toggle = <CheckBox> { text: "Patterns" }
+            <View> { width: Fill, height: Fit }
+            copy_button = <ViewerButton> { text: "Copy" }
+            export_toggle_button = <ViewerButton> { text: "Export" }
+        }
+
+        // Where and how to export the filtered lines, shown by "Export"
+        export_row = <View> {
+            visible: false
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+            padding: { left: 12, right: 12, bottom: 6 }
+
+            export_path_input = <TextInput> {
+                width: Fill, height: Fit
+                empty_text: "Export to, e.g. ~/dora-logs.jsonl"
+                draw_text: {
+                    color: #000000
+                    uniform color_hover: #000000
+                    uniform color_focus: #000000
+                    uniform color_down: #000000
+                    uniform color_empty: #888888
+                }
+            }
+            export_format_dropdown = <DropDown> {
+                width: 90, height: 28
+                labels: ["JSONL", "CSV", "Text"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            overwrite_toggle = <CheckBox> { text: "Overwrite" }
+            export_button = <ViewerButton> { text: "Save" }
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+            padding: { left: 12, right: 12, bottom: 6 }
+
+            search_input = <TextInput> {
+                width: Fill, height: Fit
+                empty_text: "Search logs"
+                draw_text: {
+                    color: #000000
+                    uniform color_hover: #000000
+                    uniform color_focus: #000000
+                    uniform color_down: #000000
+                    uniform color_empty: #888888
+                }
+            }
+            regex_toggle = <CheckBox> { text: "Regex" }
+            match_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 }
+                }
+                text: ""
+            }
+            prev_match_button = <ViewerButton> { text: "Prev" }
+            next_match_button = <ViewerButton> { text: "Next" }
+        }
+
+        status_label = <Label> {
+            width: Fill, height: Fit
+            padding: { left: 12, right: 12, bottom: 4 }
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+            }
+            text: ""
+        }
+
+        histogram = <LevelHistogramView> {}
+
+        // Only the lines on screen are drawn, logs can be long.
+        // Stays at the end while following unless scrolled up.
+        log_list = <PortalList> {
+            width: Fill, height: Fill
+            flow: Down
+            auto_tail: true
+
+            LogLineRow = <LogLineRow> {}
+            PatternRow = <PatternRow> {}
+        }
+    }
+}
+
+/// Lines kept in the viewer while following, older ones scroll out
+const MAX_VIEWER_LINES: usize = 20_000;
+
+/// Bars of the level histogram
+const HISTOGRAM_BUCKETS: usize = 60;
+
+/// Minimum levels behind the level dropdown, in label order
+const LEVEL_CHOICES: [Option<LogLevel>; 5] = [
+    None,
+    Some(LogLevel::Error),
+    Some(LogLevel::Warn),
+    Some(LogLevel::Info),
+    Some(LogLevel::Debug),
+];
+
+/// Row backgrounds: plain, search match, current match
+const ROW_COLOR: u32 = 0xffffff;
+const MATCH_COLOR: u32 = 0xfef9c3;
+const CURRENT_MATCH_COLOR: u32 = 0xfed7aa;
+
+/// Text color of a level label
+fn level_color(level: LogLevel) -> Vec4 {
+    let hex = match level {
+        LogLevel::Error => 0xdc2626,
+        LogLevel::Warn => 0xd97706,
+        LogLevel::Info => 0x2563eb,
+        LogLevel::Debug | LogLevel::Trace => 0x94a3b8,
+    };
+    crate::dataflow::dataflow_table::hex_color(hex)
+}
+
+/// Where the viewer is docked next to the dataflow table
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LogDock {
+    #[default]
+    Right,
+    Bottom,
+}
+
+/// Requests from the viewer the app has to act on
+#[derive(Clone, Debug, PartialEq, DefaultNone)]
+pub enum LogViewerAction {
+    None,
+    /// Fetch logs again, for one node or (`None`) the whole dataflows
+    Fetch {
+        dataflow_ids: Vec<String>,
+        node: Option<String>,
+    },
+    /// Read the coordinator and daemon logs again
+    FetchSystem,
+    /// Start streaming new lines of a dataflow
+    Follow(String),
+    /// Stop streaming
+    Unfollow,
+    Dock(LogDock),
+    Close,
+}
+
+/// Logs of one dataflow, or of several merged by time, with node and level
+/// filters and search. The System tab shows coordinator and daemon logs
+/// with the same filters, the node filter picking their source.
+#[derive(Live, LiveHook, Widget)]
+pub struct LogViewer {
+    #[deref]
+    view: View,
+    /// Dataflows shown, empty while closed or opened on system logs only
+    #[rust]
+    dataflow_ids: Vec<String>,
+    #[rust]
+    nodes: Vec<String>,
+    #[rust]
+    title: String,
+    /// On the System tab
+    #[rust]
+    system: bool,
+    /// Processes and files of the system logs
+    #[rust]
+    system_sources: Vec<String>,
+    /// Fetched lines of each dataflow, in `dataflow_ids` order
+    #[rust]
+    sources: Vec<(String, Vec<LogLine>)>,
+    /// All dataflows' lines merged by time
+    #[rust]
+    lines: Vec<LogLine>,
+    #[rust]
+    filter: LogFilter,
+    /// Indices into `lines` that pass `filter`
+    #[rust]
+    visible_lines: Vec<usize>,
+    #[rust]
+    search: LogSearch,
+    #[rust]
+    export_open: bool,
+    #[rust]
+    export_format: ExportFormat,
+    /// Rows of `visible_lines` that match `search`
+    #[rust]
+    matches: MatchCursor,
+    #[rust]
+    wrap: bool,
+    /// Patterns of the filtered lines are listed instead of the lines
+    #[rust]
+    show_patterns: bool,
+    #[rust]
+    patterns: Vec<LogPattern>,
+    #[rust]
+    dock: LogDock,
+    #[rust]
+    following: bool,
+    /// Scrolled away from the end while following
+    #[rust]
+    paused: bool,
+    /// Lines the follow stream dropped because the viewer fell behind
+    #[rust]
+    dropped: u64,
+}
+
+impl Widget for LogViewer {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_lines(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for LogViewer {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        let uid = self.widget_uid();
+
+        if let Some(index) = self.view.drop_down(id!(node_dropdown)).selected(actions) {
+            // Index 0 is "All nodes"
+            if self.system {
+                // All sources are loaded already
+                self.filter.node = index.checked_sub(1).and_then(|i| self.system_sources.get(i)).cloned();
+                self.update_visible(cx);
+            } else {
+                self.filter.node = index.checked_sub(1).and_then(|i| self.nodes.get(i)).cloned();
+                self.update_visible(cx);
+                self.fetch(cx, scope);
+            }
+        }
+
+        if self.view.button(id!(system_tab)).clicked(actions) && !self.system {
+            if self.following {
+                cx.widget_action(uid, &scope.path, LogViewerAction::Unfollow);
+            }
+            self.show_system(cx);
+            self.fetch(cx, scope);
+        }
+
+        if self.view.button(id!(dataflow_tab)).clicked(actions) && self.system && !self.dataflow_ids.is_empty() {
+            self.show_dataflows(cx);
+            self.fetch(cx, scope);
+        }
+
+        if self.view.button(id!(reload_button)).clicked(actions) {
+            self.fetch(cx, scope);
+        }
+
+        if let Some(index) = self.view.drop_down(id!(level_dropdown)).selected(actions) {
+            self.filter.min_level = LEVEL_CHOICES.get(index).copied().flatten();
+            self.update_visible(cx);
+        }
+
+        if let Some(follow) = self.view.check_box(id!(follow_toggle)).changed(actions) {
+            // Only a single dataflow can be followed
+            match (&self.dataflow_ids[..], follow) {
+                ([dataflow_id], true) => {
+                    let dataflow_id = dataflow_id.clone();
+                    self.start_following(cx);
+                    cx.widget_action(uid, &scope.path, LogViewerAction::Follow(dataflow_id));
+                }
+                _ => {
+                    self.following = false;
+                    cx.widget_action(uid, &scope.path, LogViewerAction::Unfollow);
+                }
+            }
+            self.show_counts(cx);
+        }
+
+        let list = self.view.portal_list(id!(log_list));
+        if self.following && list.scrolled(actions) {
+            // Like auto_tail: scrolling up pauses, scrolling back to the end resumes
+            let paused = !list.is_at_end();
+            if paused != self.paused {
+                self.paused = paused;
+                self.show_counts(cx);
+            }
+        }
+
+        let search_input = self.view.text_input(id!(search_input));
+        let regex_changed = self.view.check_box(id!(regex_toggle)).changed(actions).is_some();
+        if search_input.changed(actions).is_some() || regex_changed {
+            let regex = self.view.check_box(id!(regex_toggle)).active(cx);
+            match LogSearch::new(&search_input.text(), regex) {
+                Ok(search) => {
+                    self.search = search;
+                    self.update_matches(cx);
+                }
+                Err(e) => {
+                    self.search = LogSearch::Empty;
+                    self.update_matches(cx);
+                    self.view.label(id!(match_label)).set_text(cx, &e);
+                }
+            }
+        }
+
+        // Enter in the search box moves to the next match too
+        if self.view.button(id!(next_match_button)).clicked(actions) || search_input.returned(actions).is_some() {
+            let row = self.matches.next_match();
+            self.scroll_to_match(cx, row);
+        }
+        if self.view.button(id!(prev_match_button)).clicked(actions) {
+            let row = self.matches.prev_match();
+            self.scroll_to_match(cx, row);
+        }
+
+        if let Some(show) = self.view.check_box(id!(patterns_toggle)).changed(actions) {
+            self.show_patterns = show;
+            self.update_visible(cx);
+        }
+
+        match self.view.level_histogram_view(id!(histogram)).hover(actions) {
+            Some(LevelHistogramAction::Hover(text)) => self.set_status(cx, &text),
+            Some(LevelHistogramAction::Leave) => self.show_counts(cx),
+            _ => {}
+        }
+
+        if let Some(wrap) = self.view.check_box(id!(wrap_toggle)).changed(actions) {
+            self.wrap = wrap;
+            self.view.portal_list(id!(log_list)).redraw(cx);
+        }
+
+        if self.view.button(id!(copy_button)).clicked(actions) {
+            let text = self
+                .visible_lines
+                .iter()
+                .filter_map(|i| self.lines.get(*i))
+                .map(LogLine::display)
+                .collect::<Vec<_>>()
+                .join("\n");
+            cx.copy_to_clipboard(&text);
+            self.set_status(cx, &format!("Copied {} lines", self.visible_lines.len()));
+        }
+
+        if self.view.button(id!(export_toggle_button)).clicked(actions) {
+            self.export_open = !self.export_open;
+            self.view.view(id!(export_row)).set_visible(cx, self.export_open);
+            let path_input = self.view.text_input(id!(export_path_input));
+            if self.export_open && path_input.text().is_empty() {
+                let name = match &self.dataflow_ids[..] {
+                    _ if self.system => "dora-system-logs".to_string(),
+                    [dataflow_id] => format!("dora-logs-{}", short_id(dataflow_id)),
+                    _ => "dora-logs".to_string(),
+                };
+                path_input.set_text(cx, &format!("~/{}.{}", name, self.export_format.extension()));
+            }
+            self.redraw(cx);
+        }
+
+        if let Some(index) = self.view.drop_down(id!(export_format_dropdown)).selected(actions) {
+            self.export_format = EXPORT_FORMATS.get(index).copied().unwrap_or_default();
+            // Keep the file extension in step with the format
+            let path_input = self.view.text_input(id!(export_path_input));
+            let path = path_input.text();
+            let extension = self.export_format.extension();
+            if let Some((stem, old)) = path.rsplit_once('.') {
+                if ExportFormat::parse(old).is_some() && !stem.is_empty() {
+                    path_input.set_text(cx, &format!("{}.{}", stem, extension));
+                }
+            }
+        }
+
+        if self.view.button(id!(export_button)).clicked(actions) {
+            self.export(cx);
+        }
+
+        if self.view.button(id!(dock_button)).clicked(actions) {
+            self.dock = match self.dock {
+                LogDock::Right => LogDock::Bottom,
+                LogDock::Bottom => LogDock::Right,
+            };
+            let label = match self.dock {
+                LogDock::Right => "Dock bottom",
+                LogDock::Bottom => "Dock right",
+            };
+            self.view.button(id!(dock_button)).set_text(cx, label);
+            cx.widget_action(uid, &scope.path, LogViewerAction::Dock(self.dock));
+        }
+
+        if self.view.button(id!(close_button)).clicked(actions) {
+            self.dataflow_ids.clear();
+            self.stop_following(cx);
+            self.view.set_visible(cx, false);
+            cx.widget_action(uid, &scope.path, LogViewerAction::Close);
+        }
+    }
+}
+
+impl LogViewer {
+    /// Show the viewer for some dataflows, empty until logs arrive. Several
+    /// dataflows are merged by timestamp.
+    pub fn open(&mut self, cx: &mut Cx, dataflow_ids: Vec<String>, title: &str, nodes: Vec<String>) {
+        if self.dataflow_ids != dataflow_ids {
+            self.lines.clear();
+            self.stop_following(cx);
+        }
+        self.dataflow_ids = dataflow_ids;
+        self.nodes = nodes;
+        self.title = title.to_string();
+        self.show_dataflows(cx);
+        self.view.set_visible(cx, true);
+        self.redraw(cx);
+    }
+
+    /// Show the viewer on the System tab, empty until logs arrive
+    pub fn open_system(&mut self, cx: &mut Cx) {
+        self.show_system(cx);
+        self.view.set_visible(cx, true);
+        self.redraw(cx);
+    }
+
+    /// Switch to the dataflow logs, refetched by the caller
+    fn show_dataflows(&mut self, cx: &mut Cx) {
+        if self.system {
+            self.system = false;
+            self.lines.clear();
+        }
+        self.sources = self.dataflow_ids.iter().map(|id| (id.clone(), Vec::new())).collect();
+        self.set_node_choices(cx, "All nodes", self.nodes.clone());
+        self.view
+            .check_box(id!(follow_toggle))
+            .set_visible(cx, self.dataflow_ids.len() == 1);
+        self.view.button(id!(dataflow_tab)).set_visible(cx, true);
+        self.view
+            .label(id!(title_label))
+            .set_text(cx, &format!("Logs: {}", self.title));
+        self.update_visible(cx);
+        self.set_status(cx, "Loading...");
+    }
+
+    /// Switch to the coordinator and daemon logs, fetched by the caller
+    fn show_system(&mut self, cx: &mut Cx) {
+        self.system = true;
+        self.stop_following(cx);
+        self.lines.clear();
+        self.set_node_choices(cx, "All sources", self.system_sources.clone());
+        self.view.check_box(id!(follow_toggle)).set_visible(cx, false);
+        self.view
+            .button(id!(dataflow_tab))
+            .set_visible(cx, !self.dataflow_ids.is_empty());
+        self.view.label(id!(title_label)).set_text(cx, "System logs");
+        self.update_visible(cx);
+        self.set_status(cx, "Loading...");
+    }
+
+    /// Fill the node filter and show everything
+    fn set_node_choices(&mut self, cx: &mut Cx, all: &str, choices: Vec<String>) {
+        self.filter.node = None;
+        let mut labels = vec![all.to_string()];
+        labels.extend(choices);
+        let dropdown = self.view.drop_down(id!(node_dropdown));
+        dropdown.set_labels(cx, labels);
+        dropdown.set_selected_item(cx, 0);
+    }
+
+    /// Ask the app for the logs of the current tab
+    fn fetch(&mut self, cx: &mut Cx, scope: &mut Scope) {
+        let action = if self.system {
+            LogViewerAction::FetchSystem
+        } else if !self.dataflow_ids.is_empty() {
+            LogViewerAction::Fetch {
+                dataflow_ids: self.dataflow_ids.clone(),
+                node: self.filter.node.clone(),
+            }
+        } else {
+            return;
+        };
+        self.set_status(cx, "Loading...");
+        cx.widget_action(self.widget_uid(), &scope.path, action);
+    }
+
+    /// Show coordinator and daemon logs, if the System tab is still open
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_system_logs(&mut self, cx: &mut Cx, result: &Result<SystemLogs, String>) {
+        if !self.system {
+            return;
+        }
+        match result {
+            Ok(logs) => {
+                if logs.sources != self.system_sources {
+                    self.system_sources = logs.sources.clone();
+                    self.set_node_choices(cx, "All sources", self.system_sources.clone());
+                }
+                self.lines = logs.lines.clone();
+                self.update_visible(cx);
+                if !logs.errors.is_empty() {
+                    self.set_status(cx, &format!("Could not read {}", logs.errors.join("; ")));
+                }
+            }
+            Err(e) => {
+                self.lines.clear();
+                self.update_visible(cx);
+                self.set_status(cx, e);
+            }
+        }
+    }
+
+    /// Replace a dataflow's lines with logs fetched for `node` (or all nodes)
+    pub fn set_logs(&mut self, cx: &mut Cx, dataflow_id: &str, node: Option<&str>, result: &Result<String, String>) {
+        if self.system || self.filter.node.as_deref() != node {
+            // A reply for a node or tab no longer shown
+            return;
+        }
+        let Some(index) = self.sources.iter().position(|(id, _)| id == dataflow_id) else {
+            return;
+        };
+        match result {
+            Ok(text) => {
+                self.sources[index].1 = LogLine::parse_all(text, node, &self.nodes);
+                self.lines = self.merged_lines();
+                self.update_visible(cx);
+            }
+            Err(e) => {
+                self.sources[index].1.clear();
+                self.lines = self.merged_lines();
+                self.update_visible(cx);
+                self.set_status(cx, &format!("Error getting logs of {}: {}", short_id(dataflow_id), e));
+            }
+        }
+    }
+
+    /// Add streamed lines. `dropped` counts lines lost before they got here.
+    pub fn append_lines(&mut self, cx: &mut Cx, dataflow_id: &str, lines: Vec<LogLine>, dropped: u64) {
+        if !self.following || self.system || self.dataflow_ids != [dataflow_id] {
+            return;
+        }
+        self.lines.extend(lines);
+        if self.lines.len() > MAX_VIEWER_LINES {
+            let excess = self.lines.len() - MAX_VIEWER_LINES;
+            self.lines.drain(..excess);
+        }
+        self.dropped += dropped;
+        self.update_visible(cx);
+    }
+
+    /// The follow stream ended, with the reason if it failed
+    pub fn follow_ended(&mut self, cx: &mut Cx, dataflow_id: &str, error: Option<&str>) {
+        if !self.following || self.dataflow_ids != [dataflow_id] {
+            return;
+        }
+        self.stop_following(cx);
+        match error {
+            Some(e) => self.set_status(cx, &format!("Following stopped: {}", e)),
+            None => self.show_counts(cx),
+        }
+    }
+
+    /// Show streamed lines as they arrive, the caller starts the stream
+    pub fn start_following(&mut self, cx: &mut Cx) {
+        self.following = true;
+        self.paused = false;
+        self.dropped = 0;
+        self.view.check_box(id!(follow_toggle)).set_active(cx, true);
+    }
+
+    fn stop_following(&mut self, cx: &mut Cx) {
+        self.following = false;
+        self.paused = false;
+        self.view.check_box(id!(follow_toggle)).set_active(cx, false);
+    }
+
+    /// Write the lines that pass the filters to the export path
+    fn export(&mut self, cx: &mut Cx) {
+        let single = match &self.dataflow_ids[..] {
+            [dataflow_id] if !self.system => Some(dataflow_id),
+            _ => None,
+        };
+        let lines: Vec<LogLine> = self
+            .visible_lines
+            .iter()
+            .filter_map(|i| self.lines.get(*i))
+            .map(|line| {
+                let mut line = line.clone();
+                if line.dataflow_id.is_none() {
+                    line.dataflow_id = single.cloned();
+                }
+                line
+            })
+            .collect();
+        let path = self.view.text_input(id!(export_path_input)).text();
+        let overwrite = self.view.check_box(id!(overwrite_toggle)).active(cx);
+        match write_export(&path, &lines, self.export_format, overwrite) {
+            Ok(summary) => {
+                log!("[LogViewer] {}", summary);
+                self.set_status(cx, &summary.to_string());
+            }
+            Err(e) => {
+                log!("[LogViewer] Export failed: {}", e);
+                self.set_status(cx, &format!("Export failed: {}", e));
+            }
+        }
+    }
+
+    /// One dataflow's lines as they are, several merged by time
+    fn merged_lines(&self) -> Vec<LogLine> {
+        match &self.sources[..] {
+            [(_, lines)] => lines.clone(),
+            sources => merge_by_timestamp(sources),
+        }
+    }
+
+    fn update_visible(&mut self, cx: &mut Cx) {
+        self.visible_lines = self.filter.apply(&self.lines);
+        let visible = self.visible_lines.iter().filter_map(|i| self.lines.get(*i));
+        let histogram = LevelHistogram::build(visible.clone(), HISTOGRAM_BUCKETS);
+        self.view.level_histogram_view(id!(histogram)).set_histogram(cx, histogram);
+        self.patterns = if self.show_patterns {
+            patterns::mine(visible)
+        } else {
+            Vec::new()
+        };
+        self.show_counts(cx);
+        self.update_matches(cx);
+    }
+
+    fn update_matches(&mut self, cx: &mut Cx) {
+        self.matches.set_rows(self.search.find(&self.lines, &self.visible_lines));
+        let label = if self.search.is_empty() {
+            String::new()
+        } else {
+            self.matches.label()
+        };
+        self.view.label(id!(match_label)).set_text(cx, &label);
+        self.view.portal_list(id!(log_list)).redraw(cx);
+    }
+
+    /// Bring the current match into view
+    fn scroll_to_match(&mut self, cx: &mut Cx, row: Option<usize>) {
+        let Some(row) = row.filter(|_| !self.show_patterns) else {
+            return;
+        };
+        if self.following {
+            // Jumping back stops the tail, like scrolling up does
+            self.paused = true;
+            self.show_counts(cx);
+        }
+        let list = self.view.portal_list(id!(log_list));
+        list.set_first_id_and_scroll(row.saturating_sub(2), 0.0);
+        self.view.label(id!(match_label)).set_text(cx, &self.matches.label());
+        list.redraw(cx);
+    }
+
+    /// Line counts plus the follow state in the status line
+    fn show_counts(&mut self, cx: &mut Cx) {
+        let mut status = if self.visible_lines.len() == self.lines.len() {
+            format!("{} lines", self.lines.len())
+        } else {
+            format!("{} of {} lines", self.visible_lines.len(), self.lines.len())
+        };
+        if self.following {
+            status.push_str(if self.paused {
+                " - paused, scroll to the end to resume"
+            } else {
+                " - following"
+            });
+        }
+        if self.dropped > 0 {
+            status.push_str(&format!(" - {} lines dropped", self.dropped));
+        }
+        if self.show_patterns {
+            status.push_str(&format!(" in {} patterns", self.patterns.len()));
+        }
+        self.set_status(cx, &status);
+    }
+
+    fn set_status(&mut self, cx: &mut Cx, status: &str) {
+        self.view.label(id!(status_label)).set_text(cx, status);
+    }
+
+    fn draw_lines(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        if self.show_patterns {
+            return self.draw_patterns(cx, list);
+        }
+        list.set_item_range(cx, 0, self.visible_lines.len());
+        while let Some(item_id) = list.next_visible_item(cx) {
+            let Some(line) = self.visible_lines.get(item_id).and_then(|i| self.lines.get(*i)) else {
+                continue;
+            };
+            let item = list.item(cx, item_id, live_id!(LogLineRow));
+
+            let background = if self.matches.current_row() == Some(item_id) {
+                CURRENT_MATCH_COLOR
+            } else if self.matches.is_match(item_id) {
+                MATCH_COLOR
+            } else {
+                ROW_COLOR
+            };
+            let background = crate::dataflow::dataflow_table::hex_color(background);
+            item.apply_over(cx, live! { draw_bg: { color: (background) } });
+
+            let level_label = item.label(id!(level_label));
+            let color = level_color(line.level);
+            level_label.apply_over(cx, live! { draw_text: { color: (color) } });
+            level_label.set_text(cx, line.level.label());
+            // Coordinator and daemon lines have no node, show where they come from
+            let mut source = line.node.as_deref().or(line.target.as_deref()).unwrap_or("").to_string();
+            if self.dataflow_ids.len() > 1 && !self.system {
+                if let Some(dataflow_id) = &line.dataflow_id {
+                    source = format!("{}/{}", short_id(dataflow_id), source);
+                }
+            }
+            item.label(id!(node_label)).set_text(cx, &source);
+
+            let text_label = item.label(id!(text_label));
+            if self.wrap {
+                text_label.apply_over(cx, live! { draw_text: { wrap: Word } });
+            } else {
+                text_label.apply_over(cx, live! { draw_text: { wrap: Ellipsis } });
+            }
+            text_label.set_text(cx, &line.text);
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+
+    fn draw_patterns(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        list.set_item_range(cx, 0, self.patterns.len());
+        while let Some(item_id) = list.next_visible_item(cx) {
+            let Some(pattern) = self.patterns.get(item_id) else {
+                continue;
+            };
+            let item = list.item(cx, item_id, live_id!(PatternRow));
+            item.label(id!(count_label)).set_text(cx, &format!("{}x", pattern.count));
+            let level_label = item.label(id!(level_label));
+            let color = level_color(pattern.level);
+            level_label.apply_over(cx, live! { draw_text: { color: (color) } });
+            level_label.set_text(cx, pattern.level.label());
+            item.label(id!(template_label)).set_text(cx, &pattern.template);
+            let seen = match (&pattern.first_seen, &pattern.last_seen) {
+                (Some(first), Some(last)) if first != last => format!("{} - {}", clock(first), clock(last)),
+                (Some(first), _) => clock(first).to_string(),
+                _ => String::new(),
+            };
+            item.label(id!(seen_label)).set_text(cx, &seen);
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+}
+
+/// Time of day of an RFC 3339 timestamp, `10:57:48`
+fn clock(timestamp: &str) -> &str {
+    timestamp.get(11..19).unwrap_or(timestamp)
+}
+
+impl LogViewerRef {
+    /// Show the viewer for one or more dataflows. `nodes` fill the node filter.
+    pub fn open(&self, cx: &mut Cx, dataflow_ids: Vec<String>, title: &str, nodes: Vec<String>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.open(cx, dataflow_ids, title, nodes);
+        }
+    }
+
+    /// Show fetched logs. Replies for another dataflow or node are ignored.
+    pub fn set_logs(&self, cx: &mut Cx, dataflow_id: &str, node: Option<&str>, result: &Result<String, String>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_logs(cx, dataflow_id, node, result);
+        }
+    }
+
+    /// Show the viewer on coordinator and daemon logs
+    pub fn open_system(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.open_system(cx);
+        }
+    }
+
+    /// Show system logs read for the System tab
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_system_logs(&self, cx: &mut Cx, result: &Result<SystemLogs, String>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_system_logs(cx, result);
+        }
+    }
+
+    /// Dataflows shown, empty when the viewer is closed
+    pub fn dataflow_ids(&self) -> Vec<String> {
+        self.borrow().map(|inner| inner.dataflow_ids.clone()).unwrap_or_default()
+    }
+
+    /// Follow the shown dataflow, the caller starts the stream
+    pub fn start_following(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.start_following(cx);
+            inner.show_counts(cx);
+        }
+    }
+
+    /// Add lines streamed while following
+    pub fn append_lines(&self, cx: &mut Cx, dataflow_id: &str, lines: Vec<LogLine>, dropped: u64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.append_lines(cx, dataflow_id, lines, dropped);
+        }
+    }
+
+    /// The follow stream ended
+    pub fn follow_ended(&self, cx: &mut Cx, dataflow_id: &str, error: Option<&str>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.follow_ended(cx, dataflow_id, error);
+        }
+    }
+
+    /// Check if the viewer asked for a fetch, to follow, a new dock or to close
+    pub fn action(&self, actions: &Actions) -> Option<LogViewerAction> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .map(|action| action.cast())
+            .find(|action| *action != LogViewerAction::None)
+    }
+}
@@ -0,0 +1,31 @@
+// Following streams from the coordinator or the CLI, native only
+#[cfg(not(target_arch = "wasm32"))]
+pub mod follow;
+pub mod export;
+pub mod histogram;
+pub mod histogram_view;
+pub mod line;
+pub mod log_viewer;
+pub mod patterns;
+pub mod search;
+// Coordinator and daemon logs, from files and supervised processes
+#[cfg(not(target_arch = "wasm32"))]
+pub mod system;
+
+pub use export::{ExportFormat, ExportSummary, TimeRange};
+pub use histogram::LevelHistogram;
+pub use line::{LogFilter, LogLevel, LogLine};
+#[cfg(not(target_arch = "wasm32"))]
+pub use follow::{FollowReady, LogFollower};
+#[cfg(not(target_arch = "wasm32"))]
+pub use system::SystemLogs;
+pub use log_viewer::{LogDock, LogViewer, LogViewerAction, LogViewerRef, LogViewerWidgetRefExt};
+pub use patterns::{LogPattern, PatternMiner};
+pub use search::{interleave_by_timestamp, merge_by_timestamp, short_id, LogSearch, MatchCursor};
+
+use makepad_widgets::*;
+
+pub fn live_design(cx: &mut Cx) {
+    histogram_view::live_design(cx);
+    log_viewer::live_design(cx);
+}
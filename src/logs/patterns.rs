@@ -0,0 +1,238 @@
+//! Grouping near-identical log lines into patterns.
+//!
+//! A small take on Drain (He et al., ICWS 2017): lines are split into
+//! tokens, tokens with digits are masked as `<*>`, and each line joins the
+//! most similar pattern with the same token count and first token, or starts
+//! a new one. Tokens where a pattern's lines differ become `<*>` too, so
+//! `frame 12 dropped after 3 retries` and `frame 40 dropped after 1 retries`
+//! end up as `frame <*> dropped after <*> retries`.
+
+use super::line::{LogLevel, LogLine};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+
+/// Placeholder for tokens that vary
+pub const WILDCARD: &str = "<*>";
+
+/// Share of tokens a line must have in common with a pattern to join it
+const SIMILARITY: f64 = 0.4;
+
+/// A group of lines that differ only in their variable parts
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogPattern {
+    pub template: String,
+    pub count: usize,
+    /// Most severe level among the lines
+    pub level: LogLevel,
+    /// Nodes that wrote the lines, sorted
+    pub nodes: Vec<String>,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    /// The first line of the pattern, unmasked
+    pub example: String,
+}
+
+#[derive(Debug, Clone)]
+struct Cluster {
+    tokens: Vec<String>,
+    count: usize,
+    level: LogLevel,
+    nodes: BTreeSet<String>,
+    first_seen: Option<String>,
+    last_seen: Option<String>,
+    example: String,
+}
+
+/// Mines patterns from lines added one at a time
+#[derive(Debug, Clone, Default)]
+pub struct PatternMiner {
+    /// Clusters by token count and first token
+    groups: HashMap<(usize, String), Vec<usize>>,
+    clusters: Vec<Cluster>,
+}
+
+impl PatternMiner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a line, returning the index of the pattern it joined
+    pub fn add(&mut self, line: &LogLine) -> usize {
+        let tokens = tokenize(&line.text);
+        let key = (tokens.len(), tokens.first().cloned().unwrap_or_default());
+        let group = self.groups.entry(key).or_default();
+
+        let best = group
+            .iter()
+            .map(|&index| (index, similarity(&self.clusters[index].tokens, &tokens)))
+            .filter(|(_, score)| *score >= SIMILARITY || tokens.is_empty())
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        let index = match best {
+            Some((index, _)) => {
+                let cluster = &mut self.clusters[index];
+                for (template, token) in cluster.tokens.iter_mut().zip(&tokens) {
+                    if template != token {
+                        *template = WILDCARD.to_string();
+                    }
+                }
+                cluster.count += 1;
+                cluster.level = cluster.level.max(line.level);
+                index
+            }
+            None => {
+                self.clusters.push(Cluster {
+                    tokens,
+                    count: 1,
+                    level: line.level,
+                    nodes: BTreeSet::new(),
+                    first_seen: None,
+                    last_seen: None,
+                    example: line.text.clone(),
+                });
+                group.push(self.clusters.len() - 1);
+                self.clusters.len() - 1
+            }
+        };
+
+        let cluster = &mut self.clusters[index];
+        if let Some(node) = &line.node {
+            cluster.nodes.insert(node.clone());
+        }
+        if let Some(timestamp) = &line.timestamp {
+            if cluster.first_seen.as_ref().is_none_or(|first| timestamp < first) {
+                cluster.first_seen = Some(timestamp.clone());
+            }
+            if cluster.last_seen.as_ref().is_none_or(|last| timestamp > last) {
+                cluster.last_seen = Some(timestamp.clone());
+            }
+        }
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.clusters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    /// Patterns, most frequent first
+    pub fn patterns(&self) -> Vec<LogPattern> {
+        let mut patterns: Vec<LogPattern> = self
+            .clusters
+            .iter()
+            .map(|cluster| LogPattern {
+                template: cluster.tokens.join(" "),
+                count: cluster.count,
+                level: cluster.level,
+                nodes: cluster.nodes.iter().cloned().collect(),
+                first_seen: cluster.first_seen.clone(),
+                last_seen: cluster.last_seen.clone(),
+                example: cluster.example.clone(),
+            })
+            .collect();
+        // Stable, so equal counts keep the order they first appeared in
+        patterns.sort_by_key(|pattern| Reverse(pattern.count));
+        patterns
+    }
+}
+
+/// Patterns of `lines`, most frequent first
+pub fn mine<'a>(lines: impl IntoIterator<Item = &'a LogLine>) -> Vec<LogPattern> {
+    let mut miner = PatternMiner::new();
+    for line in lines {
+        miner.add(line);
+    }
+    miner.patterns()
+}
+
+/// Whitespace tokens, with any token holding a digit masked
+fn tokenize(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|token| {
+            if token.chars().any(|c| c.is_ascii_digit()) {
+                WILDCARD.to_string()
+            } else {
+                token.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Share of positions where the template has the line's token
+fn similarity(template: &[String], tokens: &[String]) -> f64 {
+    if tokens.is_empty() {
+        return 1.0;
+    }
+    let same = template.iter().zip(tokens).filter(|(a, b)| a == b).count();
+    same as f64 / tokens.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(node: &str, level: LogLevel, timestamp: &str, text: &str) -> LogLine {
+        LogLine {
+            node: Some(node.to_string()),
+            level,
+            timestamp: Some(timestamp.to_string()),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_mine_groups_variable_lines() {
+        let mut lines = Vec::new();
+        for i in 0..100 {
+            lines.push(line(
+                "camera",
+                LogLevel::Info,
+                &format!("2026-01-22T10:57:{:02}Z", i % 60),
+                &format!("frame {} sent in {}ms", i, i * 3),
+            ));
+        }
+        lines.push(line("detector", LogLevel::Warn, "2026-01-22T10:58:30Z", "queue full, dropping input image"));
+        lines.push(line("detector", LogLevel::Error, "2026-01-22T10:58:31Z", "queue full, dropping input depth"));
+        lines.push(line("camera", LogLevel::Info, "2026-01-22T10:58:32Z", "stream closed"));
+
+        let patterns = mine(&lines);
+        assert_eq!(patterns.len(), 3);
+        assert_eq!(patterns[0].template, "frame <*> sent in <*>");
+        assert_eq!(patterns[0].count, 100);
+        assert_eq!(patterns[0].first_seen.as_deref(), Some("2026-01-22T10:57:00Z"));
+        assert_eq!(patterns[0].last_seen.as_deref(), Some("2026-01-22T10:57:59Z"));
+        assert_eq!(patterns[0].example, "frame 0 sent in 0ms");
+
+        // Differing words become wildcards, the level is the worst seen
+        assert_eq!(patterns[1].template, "queue full, dropping input <*>");
+        assert_eq!(patterns[1].count, 2);
+        assert_eq!(patterns[1].level, LogLevel::Error);
+        assert_eq!(patterns[1].nodes, vec!["detector"]);
+        assert_eq!(patterns[2].template, "stream closed");
+    }
+
+    #[test]
+    fn test_dissimilar_lines_stay_apart() {
+        let mut miner = PatternMiner::new();
+        let a = miner.add(&LogLine {
+            text: "connected to coordinator".to_string(),
+            ..Default::default()
+        });
+        let b = miner.add(&LogLine {
+            text: "connected daemon listening now".to_string(),
+            ..Default::default()
+        });
+        let c = miner.add(&LogLine {
+            text: "connected to daemon".to_string(),
+            ..Default::default()
+        });
+        assert_ne!(a, b);
+        assert_eq!(a, c);
+        assert_eq!(miner.len(), 2);
+        assert_eq!(miner.patterns()[0].template, "connected to <*>");
+    }
+}
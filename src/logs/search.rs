@@ -0,0 +1,248 @@
+//! Searching log lines and merging the logs of several dataflows.
+
+use super::line::LogLine;
+use regex::{Regex, RegexBuilder};
+
+/// A search query: case-insensitive substring or regular expression
+#[derive(Debug, Clone, Default)]
+pub enum LogSearch {
+    /// Matches nothing, nothing is highlighted
+    #[default]
+    Empty,
+    /// Lowercase needle for [`LogLine::contains`]
+    Substring(String),
+    Regex(Regex),
+}
+
+impl LogSearch {
+    /// Build a search, failing on invalid regular expressions
+    pub fn new(query: &str, regex: bool) -> Result<Self, String> {
+        if query.is_empty() {
+            return Ok(LogSearch::Empty);
+        }
+        if !regex {
+            return Ok(LogSearch::Substring(query.to_lowercase()));
+        }
+        RegexBuilder::new(query)
+            .case_insensitive(true)
+            .build()
+            .map(LogSearch::Regex)
+            .map_err(|e| format!("Invalid regex: {}", e))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, LogSearch::Empty)
+    }
+
+    /// Whether the message, node, target or a field of `line` matches
+    pub fn is_match(&self, line: &LogLine) -> bool {
+        match self {
+            LogSearch::Empty => false,
+            LogSearch::Substring(needle) => line.contains(needle),
+            LogSearch::Regex(regex) => {
+                regex.is_match(&line.text)
+                    || line.node.as_deref().is_some_and(|node| regex.is_match(node))
+                    || line.target.as_deref().is_some_and(|target| regex.is_match(target))
+                    || line.fields.values().any(|value| regex.is_match(value))
+            }
+        }
+    }
+
+    /// Indices of the `shown` lines that match
+    pub fn find(&self, lines: &[LogLine], shown: &[usize]) -> Vec<usize> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        shown
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| lines.get(**i).is_some_and(|line| self.is_match(line)))
+            .map(|(row, _)| row)
+            .collect()
+    }
+}
+
+/// Matching rows of the log list and the one next/previous moved to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MatchCursor {
+    rows: Vec<usize>,
+    current: Option<usize>,
+}
+
+impl MatchCursor {
+    /// New matches. The current match is kept when its row still matches.
+    pub fn set_rows(&mut self, rows: Vec<usize>) {
+        let current_row = self.current_row();
+        self.current = current_row.and_then(|row| rows.binary_search(&row).ok());
+        self.rows = rows;
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn is_match(&self, row: usize) -> bool {
+        self.rows.binary_search(&row).is_ok()
+    }
+
+    /// Row of the current match
+    pub fn current_row(&self) -> Option<usize> {
+        self.current.and_then(|i| self.rows.get(i).copied())
+    }
+
+    /// Move to the next match, wrapping around, and return its row
+    pub fn next_match(&mut self) -> Option<usize> {
+        if self.rows.is_empty() {
+            return None;
+        }
+        self.current = Some(self.current.map_or(0, |i| (i + 1) % self.rows.len()));
+        self.current_row()
+    }
+
+    /// Move to the previous match, wrapping around, and return its row
+    pub fn prev_match(&mut self) -> Option<usize> {
+        if self.rows.is_empty() {
+            return None;
+        }
+        let last = self.rows.len() - 1;
+        self.current = Some(self.current.map_or(last, |i| if i == 0 { last } else { i - 1 }));
+        self.current_row()
+    }
+
+    /// "3 of 12", "12 matches" or "No matches"
+    pub fn label(&self) -> String {
+        match (self.current, self.rows.len()) {
+            (_, 0) => "No matches".to_string(),
+            (Some(i), n) => format!("{} of {}", i + 1, n),
+            (None, 1) => "1 match".to_string(),
+            (None, n) => format!("{} matches", n),
+        }
+    }
+}
+
+/// First block of a dataflow UUID, enough to tell merged dataflows apart
+pub fn short_id(dataflow_id: &str) -> &str {
+    dataflow_id.split('-').next().unwrap_or(dataflow_id)
+}
+
+/// Interleave the logs of several dataflows by timestamp.
+///
+/// Each line is tagged with its dataflow. Lines without a timestamp (plain
+/// node output) stay right after the line before them in their own log, so
+/// the order within each dataflow is kept. RFC 3339 UTC timestamps, as dora
+/// writes them, sort correctly as text.
+pub fn merge_by_timestamp(sources: &[(String, Vec<LogLine>)]) -> Vec<LogLine> {
+    let tagged: Vec<Vec<LogLine>> = sources
+        .iter()
+        .map(|(dataflow_id, lines)| {
+            lines
+                .iter()
+                .cloned()
+                .map(|mut line| {
+                    line.dataflow_id.get_or_insert_with(|| dataflow_id.clone());
+                    line
+                })
+                .collect()
+        })
+        .collect();
+    interleave_by_timestamp(tagged)
+}
+
+/// Interleave logs by timestamp, keeping the order within each log, like
+/// [`merge_by_timestamp`] but without tagging lines
+pub fn interleave_by_timestamp(sources: Vec<Vec<LogLine>>) -> Vec<LogLine> {
+    let mut keyed = Vec::new();
+    for (source_index, lines) in sources.into_iter().enumerate() {
+        let mut last_timestamp = String::new();
+        for (line_index, line) in lines.into_iter().enumerate() {
+            if let Some(timestamp) = &line.timestamp {
+                last_timestamp.clone_from(timestamp);
+            }
+            keyed.push(((last_timestamp.clone(), source_index, line_index), line));
+        }
+    }
+    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+    keyed.into_iter().map(|(_, line)| line).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(timestamp: Option<&str>, text: &str) -> LogLine {
+        LogLine {
+            timestamp: timestamp.map(str::to_string),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_substring_and_regex_search() {
+        let lines = vec![
+            line(None, "frame dropped"),
+            LogLine {
+                target: Some("dora_daemon::spawn".to_string()),
+                ..line(None, "spawned node")
+            },
+            line(None, "Error 42: no image"),
+        ];
+        let shown = [0, 1, 2];
+
+        let search = LogSearch::new("DROPPED", false).unwrap();
+        assert_eq!(search.find(&lines, &shown), vec![0]);
+
+        let search = LogSearch::new(r"error \d+|daemon::", true).unwrap();
+        assert_eq!(search.find(&lines, &shown), vec![1, 2]);
+        // Rows are positions among the shown lines
+        assert_eq!(search.find(&lines, &[2]), vec![0]);
+
+        assert!(LogSearch::new("(", true).unwrap_err().starts_with("Invalid regex"));
+        assert!(LogSearch::new("", true).unwrap().find(&lines, &shown).is_empty());
+    }
+
+    #[test]
+    fn test_match_cursor_wraps() {
+        let mut cursor = MatchCursor::default();
+        cursor.set_rows(vec![2, 5, 9]);
+        assert_eq!(cursor.label(), "3 matches");
+        assert_eq!(cursor.next_match(), Some(2));
+        assert_eq!(cursor.next_match(), Some(5));
+        assert_eq!(cursor.label(), "2 of 3");
+        assert_eq!(cursor.prev_match(), Some(2));
+        assert_eq!(cursor.prev_match(), Some(9));
+        assert_eq!(cursor.next_match(), Some(2));
+
+        // New lines keep the current match when it still matches
+        cursor.set_rows(vec![2, 5, 9, 12]);
+        assert_eq!(cursor.current_row(), Some(2));
+        cursor.set_rows(vec![5]);
+        assert_eq!(cursor.current_row(), None);
+        assert!(cursor.is_match(5));
+
+        cursor.set_rows(Vec::new());
+        assert_eq!(cursor.next_match(), None);
+        assert_eq!(cursor.label(), "No matches");
+    }
+
+    #[test]
+    fn test_merge_by_timestamp() {
+        let a = vec![
+            line(Some("2026-01-22T10:57:48.1Z"), "a1"),
+            line(None, "a1 continued"),
+            line(Some("2026-01-22T10:57:50.0Z"), "a2"),
+        ];
+        let b = vec![
+            line(Some("2026-01-22T10:57:49.0Z"), "b1"),
+            line(Some("2026-01-22T10:57:51.0Z"), "b2"),
+        ];
+        let merged = merge_by_timestamp(&[("a".to_string(), a), ("b".to_string(), b)]);
+        let texts: Vec<_> = merged.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, vec!["a1", "a1 continued", "b1", "a2", "b2"]);
+        assert_eq!(merged[2].dataflow_id.as_deref(), Some("b"));
+    }
+}
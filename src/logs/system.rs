@@ -0,0 +1,190 @@
+//! Coordinator and daemon logs.
+//!
+//! Problems often sit in dora's own processes rather than in nodes: spawn
+//! failures, zenoh endpoints, build steps. Their logs come from the log files
+//! in the settings and from the output the [`supervisor`] captured, and are
+//! parsed like dataflow logs. The `node` of a system line names the process
+//! or file it came from; a node id found in its span stays in `span`.
+
+use super::line::LogLine;
+use super::search::interleave_by_timestamp;
+use crate::settings;
+use crate::supervisor::{self, ProcessKind};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Lines read from each source by default
+pub const SYSTEM_TAIL_LINES: usize = 2_000;
+/// How far from the end of a log file reading starts
+const TAIL_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Where system lines come from
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemSource {
+    /// Output captured from a process Studio supervises
+    Supervised(ProcessKind),
+    /// A coordinator or daemon log file from the settings
+    File(PathBuf),
+}
+
+impl SystemSource {
+    /// Short name, shown in the node column
+    pub fn name(&self) -> String {
+        match self {
+            SystemSource::Supervised(kind) => kind.name().to_string(),
+            SystemSource::File(path) => path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+        }
+    }
+}
+
+/// Lines of all sources, merged by time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemLogs {
+    pub lines: Vec<LogLine>,
+    /// Names of the sources read, for the source filter
+    pub sources: Vec<String>,
+    /// Sources that could not be read, with why
+    pub errors: Vec<String>,
+}
+
+/// Supervised processes with captured output, then the configured log files
+pub fn sources() -> Vec<SystemSource> {
+    let output = supervisor::recent_output(usize::MAX);
+    let mut sources: Vec<SystemSource> = [ProcessKind::Coordinator, ProcessKind::Daemon]
+        .into_iter()
+        .filter(|kind| output.iter().any(|line| line.source == *kind))
+        .map(SystemSource::Supervised)
+        .collect();
+    sources.extend(settings::get().log_files.into_iter().map(SystemSource::File));
+    sources
+}
+
+/// Read the last `limit` lines of each source
+pub fn read_system_logs(limit: usize) -> Result<SystemLogs, String> {
+    let sources = sources();
+    if sources.is_empty() {
+        return Err(
+            "No system logs: Studio is not running dora itself and no coordinator or daemon log files are set in the settings"
+                .to_string(),
+        );
+    }
+    Ok(read_sources(&sources, limit))
+}
+
+/// Read the last `limit` lines of each of `sources`
+pub fn read_sources(sources: &[SystemSource], limit: usize) -> SystemLogs {
+    let mut logs = SystemLogs::default();
+    let mut per_source = Vec::new();
+    for source in sources {
+        let name = source.name();
+        let raw = match source {
+            SystemSource::Supervised(kind) => Ok(supervisor::recent_output(usize::MAX)
+                .into_iter()
+                .filter(|line| line.source == *kind)
+                .map(|line| line.line)
+                .collect::<Vec<_>>()),
+            SystemSource::File(path) => tail_lines(path, limit),
+        };
+        match raw {
+            Ok(raw) => {
+                let skip = raw.len().saturating_sub(limit);
+                per_source.push(parse_source(&name, raw[skip..].iter().map(String::as_str)));
+                logs.sources.push(name);
+            }
+            Err(e) => {
+                eprintln!("[SystemLogs] Failed to read {}: {}", name, e);
+                logs.errors.push(format!("{}: {}", name, e));
+            }
+        }
+    }
+    logs.lines = interleave_by_timestamp(per_source);
+    logs
+}
+
+/// Parse a source's lines, naming the source as their node
+pub fn parse_source<'a>(name: &str, lines: impl Iterator<Item = &'a str>) -> Vec<LogLine> {
+    lines
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| LogLine {
+            node: Some(name.to_string()),
+            ..LogLine::parse(line, &[])
+        })
+        .collect()
+}
+
+/// The last `limit` lines of a file, reading at most [`TAIL_BYTES`]
+fn tail_lines(path: &Path, limit: usize) -> Result<Vec<String>, String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    let start = len.saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(start)).map_err(|e| e.to_string())?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+
+    let text = String::from_utf8_lossy(&bytes);
+    let mut lines: Vec<&str> = text.lines().collect();
+    if start > 0 && !lines.is_empty() {
+        // Started in the middle of a line
+        lines.remove(0);
+    }
+    let skip = lines.len().saturating_sub(limit);
+    Ok(lines[skip..].iter().map(|line| line.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::LogLevel;
+
+    #[test]
+    fn test_read_daemon_and_coordinator_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let coordinator = dir.path().join("dora-coordinator.txt");
+        let daemon = dir.path().join("dora-daemon.txt");
+        std::fs::write(&coordinator, include_str!("../../out/dora-coordinator.txt")).unwrap();
+        std::fs::write(&daemon, include_str!("../../out/dora-daemon.txt")).unwrap();
+        let sources = [
+            SystemSource::File(coordinator),
+            SystemSource::File(daemon),
+            SystemSource::File(dir.path().join("missing.txt")),
+        ];
+        let logs = read_sources(&sources, 10);
+        assert_eq!(logs.sources, vec!["dora-coordinator.txt", "dora-daemon.txt"]);
+        assert_eq!(logs.errors.len(), 1);
+        assert!(logs.errors[0].starts_with("missing.txt: "));
+        assert_eq!(logs.lines.len(), 20);
+
+        // Interleaved by time, each line named after its file
+        let timestamps: Vec<_> = logs.lines.iter().filter_map(|l| l.timestamp.clone()).collect();
+        assert!(timestamps.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(logs.lines.iter().any(|l| l.node.as_deref() == Some("dora-daemon.txt")));
+    }
+
+    #[test]
+    fn test_parse_source_names_lines() {
+        let lines = [
+            r#"{"timestamp":"2026-01-22T10:53:43Z","level":"WARN","fields":{"message":"port in use"},"target":"dora_daemon"}"#,
+            "",
+            "plain build output",
+        ];
+        let parsed = parse_source("daemon", lines.into_iter());
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].level, LogLevel::Warn);
+        assert_eq!(parsed[0].text, "port in use");
+        assert!(parsed.iter().all(|l| l.node.as_deref() == Some("daemon")));
+    }
+
+    #[test]
+    fn test_tail_lines_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.log");
+        let text: String = (0..50).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(&path, text).unwrap();
+        let lines = tail_lines(&path, 3).unwrap();
+        assert_eq!(lines, vec!["line 47", "line 48", "line 49"]);
+    }
+}
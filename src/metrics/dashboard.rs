@@ -0,0 +1,481 @@
+use makepad_widgets::*;
+
+use super::line_chart::LineChartWidgetRefExt;
+use super::telemetry::{RangePreset, TelemetryData, TelemetryRequest, TimeWindow};
+use crate::dataflow::model::{format_cpu, format_memory, format_utc, unix_now};
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::metrics::line_chart::LineChart;
+
+    PANEL_BG = #ffffff
+    TOOLBAR_BG = #f1f5f9
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+
+    DashboardButton = <Button> {
+        width: Fit, height: 28
+        padding: { left: 10, right: 10 }
+        draw_text: { text_style: { font_size: 11.0 } }
+    }
+
+    DashboardInput = <TextInput> {
+        width: 170, height: Fit
+        draw_text: {
+            color: #000000
+            uniform color_hover: #000000
+            uniform color_focus: #000000
+            uniform color_down: #000000
+            uniform color_empty: #888888
+        }
+    }
+
+    ChartTitle = <Label> {
+        width: Fill, height: Fit
+        padding: { left: 12, top: 6, bottom: 2 }
+        draw_text: {
+            color: (TEXT_PRIMARY),
+            text_style: { font_size: 11.0 }
+        }
+    }
+
+    // CPU and memory history of a dataflow, or one of its nodes, optionally
+    // next to a second dataflow
+    pub TelemetryDashboard = {{TelemetryDashboard}} {
+        visible: false
+        width: Fill, height: Fit
+        flow: Down
+        margin: { top: 12 }
+        show_bg: true
+        draw_bg: { color: (PANEL_BG) }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+            padding: { left: 12, right: 12, top: 8, bottom: 8 }
+            show_bg: true
+            draw_bg: { color: (TOOLBAR_BG) }
+
+            <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 13.0 }
+                }
+                text: "Telemetry"
+            }
+            dataflow_dropdown = <DropDown> {
+                width: 180, height: 28
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            compare_dropdown = <DropDown> {
+                width: 180, height: 28
+                labels: ["Compare with..."]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            node_dropdown = <DropDown> {
+                width: 150, height: 28
+                labels: ["All nodes"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            <View> { width: Fill, height: Fit }
+            five_minutes_button = <DashboardButton> { text: "5m" }
+            hour_button = <DashboardButton> { text: "1h" }
+            day_button = <DashboardButton> { text: "24h" }
+            reset_button = <DashboardButton> { text: "Reset zoom" }
+            close_button = <DashboardButton> { text: "Close" }
+        }
+
+        // A custom range in UTC, also filled in by zooming and panning
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+            padding: { left: 12, right: 12, top: 6, bottom: 2 }
+
+            since_input = <DashboardInput> { empty_text: "From 2026-01-22 10:00" }
+            until_input = <DashboardInput> { empty_text: "To (empty for now)" }
+            apply_button = <DashboardButton> { text: "Apply" }
+            status_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 }
+                    wrap: Ellipsis
+                }
+                text: ""
+            }
+        }
+
+        <ChartTitle> { text: "CPU" }
+        cpu_chart = <LineChart> {}
+        <ChartTitle> { text: "Memory" }
+        memory_chart = <LineChart> { margin: { bottom: 8 } }
+    }
+}
+
+/// Points asked for per series, about one per pixel column of a wide chart
+const CHART_POINTS: usize = 400;
+
+/// Requests from the dashboard the app has to act on
+#[derive(Clone, Debug, PartialEq, DefaultNone)]
+pub enum TelemetryAction {
+    None,
+    /// Load the recorded history for these series
+    Load(TelemetryRequest),
+    Close,
+}
+
+/// Charts of the recorded CPU and memory history. Each change of dataflow,
+/// node or range asks the app to load the window again.
+#[derive(Live, LiveHook, Widget)]
+pub struct TelemetryDashboard {
+    #[deref]
+    view: View,
+    /// UUID and label of each dataflow in the dropdowns, empty while closed
+    #[rust]
+    dataflows: Vec<(String, String)>,
+    /// Index into `dataflows` of the dataflow shown
+    #[rust]
+    selected: usize,
+    /// Index into `dataflows` of the one drawn next to it
+    #[rust]
+    compare: Option<usize>,
+    #[rust]
+    node: Option<String>,
+    /// Nodes with recorded samples, in the node dropdown
+    #[rust]
+    nodes: Vec<String>,
+    #[rust]
+    preset: RangePreset,
+    /// The range "Reset zoom" goes back to: a preset, or the applied custom range
+    #[rust]
+    home: Option<(RangePreset, TimeWindow)>,
+    #[rust]
+    window: Option<TimeWindow>,
+    /// Request whose data is awaited, older replies are dropped
+    #[rust]
+    pending: Option<TelemetryRequest>,
+    /// Request of the series drawn
+    #[rust]
+    shown: Option<TelemetryRequest>,
+}
+
+impl Widget for TelemetryDashboard {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for TelemetryDashboard {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        let uid = self.widget_uid();
+
+        if let Some(index) = self.view.drop_down(id!(dataflow_dropdown)).selected(actions) {
+            self.selected = index;
+            self.set_node(cx, None);
+            self.load(cx, scope);
+        }
+
+        if let Some(index) = self.view.drop_down(id!(compare_dropdown)).selected(actions) {
+            // Index 0 is "Compare with..."
+            self.compare = index.checked_sub(1);
+            self.load(cx, scope);
+        }
+
+        if let Some(index) = self.view.drop_down(id!(node_dropdown)).selected(actions) {
+            // Index 0 is "All nodes"
+            self.node = index.checked_sub(1).and_then(|i| self.nodes.get(i).cloned());
+            self.load(cx, scope);
+        }
+
+        for (button, preset) in [
+            (id!(five_minutes_button), RangePreset::FiveMinutes),
+            (id!(hour_button), RangePreset::Hour),
+            (id!(day_button), RangePreset::Day),
+        ] {
+            if self.view.button(button).clicked(actions) {
+                self.set_preset(cx, preset);
+                self.load(cx, scope);
+            }
+        }
+
+        if self.view.button(id!(apply_button)).clicked(actions)
+            || self.view.text_input(id!(since_input)).returned(actions).is_some()
+            || self.view.text_input(id!(until_input)).returned(actions).is_some()
+        {
+            let since = self.view.text_input(id!(since_input)).text();
+            let until = self.view.text_input(id!(until_input)).text();
+            match TimeWindow::parse(&since, &until, unix_now()) {
+                Ok(window) => {
+                    self.preset = RangePreset::Custom;
+                    self.home = Some((RangePreset::Custom, window));
+                    self.set_window(cx, window);
+                    self.load(cx, scope);
+                }
+                Err(e) => self.set_status(cx, &e),
+            }
+        }
+
+        if self.view.button(id!(reset_button)).clicked(actions) {
+            match self.home {
+                Some((RangePreset::Custom, window)) => {
+                    self.preset = RangePreset::Custom;
+                    self.set_window(cx, window);
+                }
+                Some((preset, _)) => self.set_preset(cx, preset),
+                None => self.set_preset(cx, RangePreset::default()),
+            }
+            self.load(cx, scope);
+        }
+
+        // Zooming or panning either chart moves both
+        let zoomed = self
+            .view
+            .line_chart(id!(cpu_chart))
+            .window_changed(actions)
+            .or_else(|| self.view.line_chart(id!(memory_chart)).window_changed(actions));
+        if let Some(window) = zoomed {
+            self.preset = RangePreset::Custom;
+            self.set_window(cx, window);
+            self.load(cx, scope);
+        }
+
+        if self.view.button(id!(close_button)).clicked(actions) {
+            self.pending = None;
+            self.shown = None;
+            self.dataflows.clear();
+            self.view.set_visible(cx, false);
+            cx.widget_action(uid, &scope.path, TelemetryAction::Close);
+        }
+    }
+}
+
+impl TelemetryDashboard {
+    /// Show the dashboard for one of `dataflows`, over the last five minutes
+    /// unless a range was picked before
+    pub fn open(
+        &mut self,
+        cx: &mut Cx,
+        dataflows: Vec<(String, String)>,
+        selected: Option<&str>,
+    ) -> Option<TelemetryRequest> {
+        if dataflows.is_empty() {
+            return None;
+        }
+        let uuid = selected.or_else(|| self.selected_uuid()).map(str::to_string);
+        self.set_dataflows(cx, dataflows);
+        if let Some(index) = uuid.and_then(|uuid| self.dataflows.iter().position(|(u, _)| *u == uuid)) {
+            self.selected = index;
+            self.view.drop_down(id!(dataflow_dropdown)).set_selected_item(cx, index);
+        }
+        self.set_node(cx, None);
+        match self.home {
+            Some((RangePreset::Custom, window)) => {
+                self.preset = RangePreset::Custom;
+                self.set_window(cx, window);
+            }
+            Some((preset, _)) => self.set_preset(cx, preset),
+            None => self.set_preset(cx, RangePreset::default()),
+        }
+        self.view.set_visible(cx, true);
+        self.redraw(cx);
+        self.request()
+    }
+
+    /// Move a preset range up to now, for new samples while it is shown.
+    /// Waits for a load in flight, and for the newest bucket to fill: until
+    /// then a reload would draw the same points.
+    pub fn follow_now(&mut self, cx: &mut Cx) -> Option<TelemetryRequest> {
+        if self.dataflows.is_empty() || self.preset == RangePreset::Custom || self.pending.is_some() {
+            return None;
+        }
+        if let Some(shown) = &self.shown {
+            if unix_now() < shown.window.end + shown.bucket_secs() {
+                return None;
+            }
+        }
+        self.set_preset(cx, self.preset);
+        self.request()
+    }
+
+    /// Show loaded series, if they are still the ones wanted
+    pub fn set_data(&mut self, cx: &mut Cx, result: Result<TelemetryData, String>) {
+        let data = match result {
+            Ok(data) => data,
+            Err(e) => {
+                self.pending = None;
+                self.set_status(cx, &format!("Error loading telemetry: {}", e));
+                return;
+            }
+        };
+        if self.pending.as_ref() != Some(&data.request) {
+            return;
+        }
+        self.pending = None;
+        self.shown = Some(data.request.clone());
+
+        if data.nodes != self.nodes {
+            self.nodes = data.nodes.clone();
+            let mut labels = vec!["All nodes".to_string()];
+            labels.extend(self.nodes.clone());
+            let dropdown = self.view.drop_down(id!(node_dropdown));
+            dropdown.set_labels(cx, labels);
+            let index = self
+                .node
+                .as_ref()
+                .and_then(|node| self.nodes.iter().position(|n| n == node))
+                .map_or(0, |i| i + 1);
+            dropdown.set_selected_item(cx, index);
+        }
+
+        let request = &data.request;
+        let bucket_secs = request.bucket_secs();
+        self.view
+            .line_chart(id!(cpu_chart))
+            .set_series(cx, request.window, bucket_secs, data.cpu.clone(), format_cpu);
+        self.view
+            .line_chart(id!(memory_chart))
+            .set_series(cx, request.window, bucket_secs, data.memory.clone(), format_memory);
+
+        let labels: Vec<&str> = data.cpu.iter().map(|series| series.label.as_str()).collect();
+        let range = match self.preset {
+            RangePreset::Custom => String::new(),
+            preset => format!("Last {}: ", preset.label()),
+        };
+        let status = if data.is_empty() {
+            format!("{}{}, no samples recorded in this range", range, labels.join(" vs "))
+        } else {
+            format!("{}{}, {}s per point", range, labels.join(" vs "), bucket_secs)
+        };
+        self.set_status(cx, &status);
+    }
+
+    fn set_dataflows(&mut self, cx: &mut Cx, dataflows: Vec<(String, String)>) {
+        let compare_uuid = self.compare.and_then(|i| self.dataflows.get(i)).map(|(uuid, _)| uuid.clone());
+        self.dataflows = dataflows;
+        self.selected = 0;
+        self.compare = compare_uuid.and_then(|uuid| self.dataflows.iter().position(|(u, _)| *u == uuid));
+
+        let labels: Vec<String> = self.dataflows.iter().map(|(_, label)| label.clone()).collect();
+        let dropdown = self.view.drop_down(id!(dataflow_dropdown));
+        dropdown.set_labels(cx, labels.clone());
+        dropdown.set_selected_item(cx, 0);
+
+        let mut compare_labels = vec!["Compare with...".to_string()];
+        compare_labels.extend(labels);
+        let dropdown = self.view.drop_down(id!(compare_dropdown));
+        dropdown.set_labels(cx, compare_labels);
+        dropdown.set_selected_item(cx, self.compare.map_or(0, |i| i + 1));
+    }
+
+    fn selected_uuid(&self) -> Option<&str> {
+        self.dataflows.get(self.selected).map(|(uuid, _)| uuid.as_str())
+    }
+
+    /// Show the whole dataflow, or one node of it
+    fn set_node(&mut self, cx: &mut Cx, node: Option<String>) {
+        if node.is_none() && self.node.is_some() {
+            self.view.drop_down(id!(node_dropdown)).set_selected_item(cx, 0);
+        }
+        self.node = node;
+    }
+
+    fn set_preset(&mut self, cx: &mut Cx, preset: RangePreset) {
+        let Some(secs) = preset.secs() else {
+            return;
+        };
+        let window = TimeWindow::last(secs, unix_now());
+        self.preset = preset;
+        self.home = Some((preset, window));
+        self.set_window(cx, window);
+    }
+
+    /// Move both charts to `window` and show it in the range inputs
+    fn set_window(&mut self, cx: &mut Cx, window: TimeWindow) {
+        self.window = Some(window);
+        self.view.line_chart(id!(cpu_chart)).set_window(cx, window);
+        self.view.line_chart(id!(memory_chart)).set_window(cx, window);
+        let input_time = |timestamp| format_utc(timestamp).trim_end_matches(" UTC").to_string();
+        self.view.text_input(id!(since_input)).set_text(cx, &input_time(window.start));
+        let until = if self.preset == RangePreset::Custom {
+            input_time(window.end)
+        } else {
+            String::new()
+        };
+        self.view.text_input(id!(until_input)).set_text(cx, &until);
+    }
+
+    /// What to load for the current dataflows, node and window
+    fn request(&mut self) -> Option<TelemetryRequest> {
+        let window = self.window?;
+        let mut dataflows = vec![self.dataflows.get(self.selected)?.clone()];
+        if let Some(other) = self.compare.filter(|i| *i != self.selected) {
+            dataflows.extend(self.dataflows.get(other).cloned());
+        }
+        let request = TelemetryRequest {
+            dataflows,
+            node: self.node.clone(),
+            window,
+            points: CHART_POINTS,
+        };
+        self.pending = Some(request.clone());
+        Some(request)
+    }
+
+    /// Ask the app to load the current series
+    fn load(&mut self, cx: &mut Cx, scope: &mut Scope) {
+        if let Some(request) = self.request() {
+            self.set_status(cx, "Loading...");
+            cx.widget_action(self.widget_uid(), &scope.path, TelemetryAction::Load(request));
+        }
+    }
+
+    fn set_status(&mut self, cx: &mut Cx, text: &str) {
+        self.view.label(id!(status_label)).set_text(cx, text);
+    }
+}
+
+impl TelemetryDashboardRef {
+    /// Show the dashboard, returns what to load for it
+    pub fn open(
+        &self,
+        cx: &mut Cx,
+        dataflows: Vec<(String, String)>,
+        selected: Option<&str>,
+    ) -> Option<TelemetryRequest> {
+        self.borrow_mut()?.open(cx, dataflows, selected)
+    }
+
+    /// Move a preset range up to now, returns what to load if it moved
+    pub fn follow_now(&self, cx: &mut Cx) -> Option<TelemetryRequest> {
+        self.borrow_mut()?.follow_now(cx)
+    }
+
+    pub fn set_data(&self, cx: &mut Cx, result: Result<TelemetryData, String>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_data(cx, result);
+        }
+    }
+
+    /// Check if the dashboard asked for data or was closed
+    pub fn action(&self, actions: &Actions) -> Option<TelemetryAction> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .map(|action| action.cast())
+            .find(|action| *action != TelemetryAction::None)
+    }
+}
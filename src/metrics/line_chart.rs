@@ -0,0 +1,347 @@
+use makepad_widgets::*;
+
+use super::telemetry::{time_label, ChartSeries, TimeWindow};
+use crate::dataflow::dataflow_table::hex_color;
+use crate::dataflow::model::unix_now;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    // Lines of one metric over a time window. The wheel zooms around the
+    // pointer, dragging pans, hovering reads the values under the pointer.
+    pub LineChart = {{LineChart}} {
+        width: Fill, height: 180
+        draw_bg: { color: #f8fafc }
+        draw_line: { color: #3b82f6 }
+        draw_grid: { color: #e2e8f0 }
+        draw_text: {
+            color: #64748b
+            text_style: { font_size: 8.0 }
+        }
+    }
+}
+
+/// Line colors: the dataflow, then the one compared with it
+const SERIES_COLORS: [u32; 2] = [0x3b82f6, 0xf97316];
+
+/// Room for the value labels on the left, the legend above and the times below
+const PAD_LEFT: f64 = 56.0;
+const PAD_RIGHT: f64 = 8.0;
+const PAD_TOP: f64 = 18.0;
+const PAD_BOTTOM: f64 = 16.0;
+
+const LINE_WIDTH: f64 = 1.5;
+
+/// How much one wheel step zooms
+const ZOOM_STEP: f64 = 1.25;
+
+/// Points further apart than this many buckets are not joined, so stopped
+/// dataflows show as gaps
+const MAX_JOIN_BUCKETS: u64 = 3;
+
+/// What zooming and panning the chart asks for
+#[derive(Clone, Debug, PartialEq, DefaultNone)]
+pub enum LineChartAction {
+    None,
+    /// The window was zoomed, or panned by dragging
+    Window(TimeWindow),
+}
+
+/// Time series chart of the telemetry dashboard
+#[derive(Live, LiveHook, Widget)]
+pub struct LineChart {
+    #[walk]
+    walk: Walk,
+    #[redraw]
+    #[live]
+    draw_bg: DrawColor,
+    #[live]
+    draw_line: DrawColor,
+    #[live]
+    draw_grid: DrawColor,
+    #[live]
+    draw_text: DrawText,
+    #[rust]
+    series: Vec<ChartSeries>,
+    #[rust]
+    window: Option<TimeWindow>,
+    /// Seconds averaged into one point
+    #[rust]
+    bucket_secs: u64,
+    /// Formats values on the axis and in the hover readout
+    #[rust]
+    format: Option<fn(f64) -> String>,
+    /// Where the lines were last drawn
+    #[rust]
+    plot: Rect,
+    #[rust]
+    hover_x: Option<f64>,
+    /// Pointer position and window when a drag started
+    #[rust]
+    drag: Option<(f64, TimeWindow)>,
+}
+
+impl Widget for LineChart {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        let uid = self.widget_uid();
+        let Some(window) = self.window else {
+            return;
+        };
+        match event.hits(cx, self.draw_bg.area()) {
+            Hit::FingerHoverIn(e) | Hit::FingerHoverOver(e) => {
+                self.hover_x = Some(e.abs.x);
+                self.redraw(cx);
+            }
+            Hit::FingerHoverOut(_) => {
+                self.hover_x = None;
+                self.redraw(cx);
+            }
+            Hit::FingerScroll(e) => {
+                let factor = if e.scroll.y > 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+                let zoomed = window.zoom(factor, self.fraction_at(e.abs.x));
+                if zoomed != window {
+                    self.window = Some(zoomed);
+                    cx.widget_action(uid, &scope.path, LineChartAction::Window(zoomed));
+                    self.redraw(cx);
+                }
+            }
+            Hit::FingerDown(e) => {
+                self.drag = Some((e.abs.x, window));
+            }
+            Hit::FingerMove(e) => {
+                // Slide what is loaded along while dragging, load on release
+                if let Some((from, start)) = self.drag {
+                    let width = self.plot.size.x.max(1.0);
+                    self.window = Some(start.pan((from - e.abs.x) / width, unix_now()));
+                    self.hover_x = None;
+                    self.redraw(cx);
+                }
+            }
+            Hit::FingerUp(_) => {
+                if let Some((_, start)) = self.drag.take() {
+                    if window != start {
+                        cx.widget_action(uid, &scope.path, LineChartAction::Window(window));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle(walk);
+        self.draw_bg.draw_abs(cx, rect);
+        let plot = Rect {
+            pos: dvec2(rect.pos.x + PAD_LEFT, rect.pos.y + PAD_TOP),
+            size: dvec2(
+                (rect.size.x - PAD_LEFT - PAD_RIGHT).max(1.0),
+                (rect.size.y - PAD_TOP - PAD_BOTTOM).max(1.0),
+            ),
+        };
+        self.plot = plot;
+        let Some(window) = self.window else {
+            return DrawStep::done();
+        };
+        let format = self.format.unwrap_or(|value| format!("{:.2}", value));
+
+        // Grid lines at a quarter of the top value each, labelled on the left
+        let max = self.max_value();
+        for step in 0..=4 {
+            let y = plot.pos.y + plot.size.y * step as f64 / 4.0;
+            self.draw_grid.draw_abs(
+                cx,
+                Rect {
+                    pos: dvec2(plot.pos.x, y),
+                    size: dvec2(plot.size.x, 1.0),
+                },
+            );
+            if step % 2 == 0 {
+                let value = max * (4 - step) as f64 / 4.0;
+                self.draw_text.draw_abs(cx, dvec2(rect.pos.x + 4.0, y - 6.0), &format(value));
+            }
+        }
+
+        // Times at both ends and in the middle
+        let secs = window.secs();
+        let times_y = plot.pos.y + plot.size.y + 3.0;
+        self.draw_text
+            .draw_abs(cx, dvec2(plot.pos.x, times_y), &time_label(window.start, secs));
+        self.draw_text.draw_abs(
+            cx,
+            dvec2(plot.pos.x + plot.size.x / 2.0 - 24.0, times_y),
+            &time_label(window.time_at(0.5), secs),
+        );
+        self.draw_text.draw_abs(
+            cx,
+            dvec2(plot.pos.x + plot.size.x - 56.0, times_y),
+            &time_label(window.end, secs),
+        );
+
+        if self.series.iter().all(|series| series.points.is_empty()) {
+            self.draw_text.draw_abs(
+                cx,
+                plot.pos + dvec2(plot.size.x / 2.0 - 80.0, plot.size.y / 2.0 - 6.0),
+                "No samples recorded in this range",
+            );
+            return DrawStep::done();
+        }
+
+        for index in 0..self.series.len() {
+            self.draw_line.color = hex_color(SERIES_COLORS[index % SERIES_COLORS.len()]);
+            self.draw_series(cx, index, window, max);
+        }
+
+        // The legend, or what is under the pointer
+        let mut x = plot.pos.x;
+        let hovered = self.hover_x.map(|x| window.time_at(self.fraction_at(x)));
+        if let (Some(hover_x), Some(time)) = (self.hover_x, hovered) {
+            self.draw_grid.draw_abs(
+                cx,
+                Rect {
+                    pos: dvec2(hover_x.clamp(plot.pos.x, plot.pos.x + plot.size.x), plot.pos.y),
+                    size: dvec2(1.0, plot.size.y),
+                },
+            );
+            let label = time_label(time, secs);
+            self.draw_text.draw_abs(cx, dvec2(x, rect.pos.y + 3.0), &label);
+            x += label.len() as f64 * 6.0 + 12.0;
+        }
+        for index in 0..self.series.len() {
+            let series = &self.series[index];
+            let text = match hovered {
+                Some(time) => match self.value_at(series, time) {
+                    Some(value) => format!("{} {}", series.label, format(value)),
+                    None => format!("{} -", series.label),
+                },
+                None => series.label.clone(),
+            };
+            self.draw_line.color = hex_color(SERIES_COLORS[index % SERIES_COLORS.len()]);
+            self.draw_line.draw_abs(
+                cx,
+                Rect {
+                    pos: dvec2(x, rect.pos.y + 6.0),
+                    size: dvec2(8.0, 8.0),
+                },
+            );
+            self.draw_text.draw_abs(cx, dvec2(x + 12.0, rect.pos.y + 3.0), &text);
+            x += text.len() as f64 * 6.0 + 28.0;
+        }
+        DrawStep::done()
+    }
+}
+
+impl LineChart {
+    /// Fraction of the window under `x`
+    fn fraction_at(&self, x: f64) -> f64 {
+        ((x - self.plot.pos.x) / self.plot.size.x.max(1.0)).clamp(0.0, 1.0)
+    }
+
+    /// Top of the value axis: a bit above the highest point, so lines stay off the top
+    fn max_value(&self) -> f64 {
+        let max = self
+            .series
+            .iter()
+            .flat_map(|series| series.points.iter().map(|(_, value)| *value))
+            .fold(0.0, f64::max);
+        if max > 0.0 {
+            max * 1.1
+        } else {
+            1.0
+        }
+    }
+
+    /// Average of the bucket of `series` holding `time`
+    fn value_at(&self, series: &ChartSeries, time: u64) -> Option<f64> {
+        let bucket_secs = self.bucket_secs.max(1);
+        let start = time / bucket_secs * bucket_secs;
+        series
+            .points
+            .iter()
+            .find(|(timestamp, _)| *timestamp == start)
+            .map(|(_, value)| *value)
+    }
+
+    /// One series as steps from point to point, clipped to the plot
+    fn draw_series(&mut self, cx: &mut Cx2d, index: usize, window: TimeWindow, max: f64) {
+        let plot = self.plot;
+        let join_secs = self.bucket_secs.max(1) * MAX_JOIN_BUCKETS;
+        let to_x = |timestamp: u64| plot.pos.x + window.fraction(timestamp) * plot.size.x;
+        let to_y = |value: f64| plot.pos.y + plot.size.y * (1.0 - value / max);
+        let (left, right) = (plot.pos.x, plot.pos.x + plot.size.x);
+
+        let points = &self.series[index].points;
+        for (i, &(timestamp, value)) in points.iter().enumerate() {
+            let x = to_x(timestamp);
+            let y = to_y(value);
+            // Each point holds until the next one, or for a bucket at the end of a run
+            let (next_x, next_y) = match points.get(i + 1) {
+                Some(&(next, next_value)) if next - timestamp <= join_secs => (to_x(next), Some(to_y(next_value))),
+                _ => (to_x(timestamp + self.bucket_secs.max(1)), None),
+            };
+            if next_x < left || x > right {
+                continue;
+            }
+            let from = x.max(left);
+            let to = next_x.min(right);
+            self.draw_line.draw_abs(
+                cx,
+                Rect {
+                    pos: dvec2(from, y - LINE_WIDTH / 2.0),
+                    size: dvec2((to - from).max(LINE_WIDTH), LINE_WIDTH),
+                },
+            );
+            if let Some(next_y) = next_y.filter(|_| next_x <= right) {
+                self.draw_line.draw_abs(
+                    cx,
+                    Rect {
+                        pos: dvec2(next_x - LINE_WIDTH / 2.0, y.min(next_y)),
+                        size: dvec2(LINE_WIDTH, (y - next_y).abs().max(LINE_WIDTH)),
+                    },
+                );
+            }
+        }
+    }
+}
+
+impl LineChartRef {
+    /// Show `series` over `window`, each point averaging `bucket_secs`
+    pub fn set_series(
+        &self,
+        cx: &mut Cx,
+        window: TimeWindow,
+        bucket_secs: u64,
+        series: Vec<ChartSeries>,
+        format: fn(f64) -> String,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.window = Some(window);
+            inner.bucket_secs = bucket_secs;
+            inner.series = series;
+            inner.format = Some(format);
+            inner.redraw(cx);
+        }
+    }
+
+    /// Move to `window` while its series load, keeping the current ones
+    pub fn set_window(&self, cx: &mut Cx, window: TimeWindow) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.window = Some(window);
+            inner.redraw(cx);
+        }
+    }
+
+    /// Check if the chart was zoomed or panned to a new window
+    pub fn window_changed(&self, actions: &Actions) -> Option<TimeWindow> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .find_map(|action| match action.cast() {
+                LineChartAction::Window(window) => Some(window),
+                LineChartAction::None => None,
+            })
+    }
+}
@@ -0,0 +1,36 @@
+// Samples, queries and summaries
+pub mod series;
+pub mod telemetry;
+// Widgets
+pub mod dashboard;
+pub mod line_chart;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod node_top;
+pub mod sparkline;
+// On-disk history and node processes, native only
+#[cfg(not(target_arch = "wasm32"))]
+pub mod process;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod store;
+
+pub use series::{dataflow_samples, downsample, RecentSamples, Sample, SampleQuery, Summary};
+pub use dashboard::{TelemetryAction, TelemetryDashboard, TelemetryDashboardRef, TelemetryDashboardWidgetRefExt};
+pub use line_chart::{LineChart, LineChartAction, LineChartRef, LineChartWidgetRefExt};
+#[cfg(not(target_arch = "wasm32"))]
+pub use node_top::{NodeTop, NodeTopAction, NodeTopRef, NodeTopWidgetRefExt};
+pub use sparkline::{Sparkline, SparklineRef, SparklineWidgetRefExt};
+pub use telemetry::{ChartSeries, RangePreset, TelemetryData, TelemetryRequest, TimeWindow};
+#[cfg(not(target_arch = "wasm32"))]
+pub use process::{NodeMetrics, NodeSort, ProcessSampler};
+#[cfg(not(target_arch = "wasm32"))]
+pub use store::{MetricsStore, Retention};
+
+use makepad_widgets::*;
+
+pub fn live_design(cx: &mut Cx) {
+    sparkline::live_design(cx);
+    line_chart::live_design(cx);
+    dashboard::live_design(cx);
+    #[cfg(not(target_arch = "wasm32"))]
+    node_top::live_design(cx);
+}
@@ -0,0 +1,262 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+use std::collections::HashMap;
+
+use super::process::{NodeMetrics, NodeSort};
+use crate::dataflow::model::{format_cpu, format_memory};
+use crate::logs::short_id;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    PANEL_BG = #ffffff
+    TOOLBAR_BG = #f1f5f9
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+
+    TopCell = <Label> {
+        height: Fit
+        draw_text: {
+            color: (TEXT_PRIMARY),
+            text_style: { font_size: 10.0 }
+            wrap: Ellipsis
+        }
+    }
+
+    TopHeaderCell = <Label> {
+        height: Fit
+        draw_text: {
+            color: (TEXT_SECONDARY),
+            text_style: { font_size: 10.0 }
+        }
+    }
+
+    // One node: where it runs and what it uses
+    NodeTopRow = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        spacing: 8
+        padding: { left: 12, right: 12, top: 3, bottom: 3 }
+
+        node_label = <TopCell> { width: Fill }
+        dataflow_label = <TopCell> { width: 150 }
+        cpu_label = <TopCell> { width: 70 }
+        memory_label = <TopCell> { width: 80 }
+        threads_label = <TopCell> { width: 60 }
+        fds_label = <TopCell> { width: 60 }
+        pids_label = <TopCell> { width: 110 }
+    }
+
+    // Every node process on this machine, busiest first, like `top`
+    pub NodeTop = {{NodeTop}} {
+        visible: false
+        width: Fill, height: 260
+        flow: Down
+        margin: { top: 12 }
+        show_bg: true
+        draw_bg: { color: (PANEL_BG) }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: { y: 0.5 }
+            padding: { left: 12, right: 12, top: 8, bottom: 8 }
+            show_bg: true
+            draw_bg: { color: (TOOLBAR_BG) }
+
+            <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 13.0 }
+                }
+                text: "Nodes"
+            }
+            status_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 }
+                    wrap: Ellipsis
+                }
+                text: ""
+            }
+            sort_dropdown = <DropDown> {
+                width: 130, height: 28
+                labels: ["CPU", "Memory", "Threads", "Open files"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            close_button = <Button> {
+                width: Fit, height: 28
+                padding: { left: 10, right: 10 }
+                text: "Close"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            padding: { left: 12, right: 12, top: 4, bottom: 4 }
+
+            <TopHeaderCell> { width: Fill, text: "NODE" }
+            <TopHeaderCell> { width: 150, text: "DATAFLOW" }
+            <TopHeaderCell> { width: 70, text: "CPU" }
+            <TopHeaderCell> { width: 80, text: "MEMORY" }
+            <TopHeaderCell> { width: 60, text: "THREADS" }
+            <TopHeaderCell> { width: 60, text: "FILES" }
+            <TopHeaderCell> { width: 110, text: "PIDS" }
+        }
+
+        node_list = <PortalList> {
+            width: Fill, height: Fill
+            flow: Down
+
+            NodeTopRow = <NodeTopRow> {}
+        }
+    }
+}
+
+/// Requests from the node list the app has to act on
+#[derive(Clone, Debug, PartialEq, DefaultNone)]
+pub enum NodeTopAction {
+    None,
+    /// Stop sampling, the list was closed
+    Close,
+}
+
+/// CPU, memory, threads and open files of every node process, refreshed by
+/// the poller while shown
+#[derive(Live, LiveHook, Widget)]
+pub struct NodeTop {
+    #[deref]
+    view: View,
+    #[rust]
+    metrics: Vec<NodeMetrics>,
+    /// Dataflow names by UUID
+    #[rust]
+    names: HashMap<String, String>,
+    #[rust]
+    sort: NodeSort,
+}
+
+impl Widget for NodeTop {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_rows(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for NodeTop {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        if let Some(index) = self.view.drop_down(id!(sort_dropdown)).selected(actions) {
+            self.sort = NodeSort::ALL.get(index).copied().unwrap_or_default();
+            self.sort.sort(&mut self.metrics);
+            self.redraw(cx);
+        }
+
+        if self.view.button(id!(close_button)).clicked(actions) {
+            self.view.set_visible(cx, false);
+            cx.widget_action(self.widget_uid(), &scope.path, NodeTopAction::Close);
+        }
+    }
+}
+
+impl NodeTop {
+    fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        list.set_item_range(cx, 0, self.metrics.len());
+        while let Some(item_id) = list.next_visible_item(cx) {
+            let Some(node) = self.metrics.get(item_id) else {
+                continue;
+            };
+            let item = list.item(cx, item_id, live_id!(NodeTopRow));
+            let dataflow = match self.names.get(&node.dataflow) {
+                Some(name) if !name.is_empty() => name.as_str(),
+                _ => short_id(&node.dataflow),
+            };
+            let pids: Vec<String> = node.pids.iter().map(u32::to_string).collect();
+            item.label(id!(node_label)).set_text(cx, &node.node);
+            item.label(id!(dataflow_label)).set_text(cx, dataflow);
+            item.label(id!(cpu_label)).set_text(cx, &format_cpu(node.cpu));
+            item.label(id!(memory_label)).set_text(cx, &format_memory(node.memory_gb()));
+            item.label(id!(threads_label)).set_text(cx, &node.threads.to_string());
+            item.label(id!(fds_label)).set_text(cx, &node.fds.to_string());
+            item.label(id!(pids_label)).set_text(cx, &pids.join(", "));
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+
+    fn set_status(&mut self, cx: &mut Cx, text: &str) {
+        self.view.label(id!(status_label)).set_text(cx, text);
+    }
+}
+
+impl NodeTopRef {
+    /// Show the list, empty until the first sample arrives
+    pub fn open(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.view.set_visible(cx, true);
+            inner.set_status(cx, "Sampling...");
+            inner.redraw(cx);
+        }
+    }
+
+    /// Show a new sample, `names` naming dataflows by UUID
+    pub fn set_metrics(&self, cx: &mut Cx, result: &Result<Vec<NodeMetrics>, String>, names: HashMap<String, String>) {
+        let Some(mut inner) = self.borrow_mut() else {
+            return;
+        };
+        match result {
+            Ok(metrics) => {
+                let mut metrics = metrics.clone();
+                inner.sort.sort(&mut metrics);
+                let status = if metrics.is_empty() {
+                    "No dora node processes found on this machine".to_string()
+                } else {
+                    let mut dataflows: Vec<&str> = metrics.iter().map(|m| m.dataflow.as_str()).collect();
+                    dataflows.sort();
+                    dataflows.dedup();
+                    format!(
+                        "{} nodes of {} dataflows, {} CPU and {} in total",
+                        metrics.len(),
+                        dataflows.len(),
+                        format_cpu(metrics.iter().map(|m| m.cpu).sum()),
+                        format_memory(metrics.iter().map(|m| m.memory_gb()).sum())
+                    )
+                };
+                inner.metrics = metrics;
+                inner.set_status(cx, &status);
+            }
+            Err(e) => {
+                inner.metrics.clear();
+                inner.set_status(cx, e);
+            }
+        }
+        inner.names = names;
+        inner.redraw(cx);
+    }
+
+    /// Check if the list was closed
+    pub fn action(&self, actions: &Actions) -> Option<NodeTopAction> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .map(|action| action.cast())
+            .find(|action| *action != NodeTopAction::None)
+    }
+}
@@ -0,0 +1,466 @@
+//! CPU, memory, threads and open files of each node, read from `/proc`.
+//!
+//! `dora list` only reports totals per dataflow. The daemon starts every node
+//! with its configuration in the `DORA_NODE_CONFIG` environment variable, a
+//! YAML document naming the dataflow and the node, and processes a node
+//! spawns itself inherit it. Walking `/proc` and reading each process's
+//! environment therefore finds all processes of every node. This only sees
+//! nodes of a daemon on this machine running as the same user, and needs
+//! Linux.
+
+use super::series::{Sample, CPU, MEMORY};
+use crate::dataflow::model::{format_cpu, format_memory};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Environment variable the daemon passes each node its configuration in
+pub const NODE_CONFIG_ENV: &str = "DORA_NODE_CONFIG";
+
+/// Clock ticks per second of the times in `/proc/<pid>/stat`. `USER_HZ` is
+/// 100 on every architecture Linux supports.
+const CLOCK_TICKS: f64 = 100.0;
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+// Readings of the previous sample, for CPU usage between samples
+static SAMPLER: Mutex<Option<ProcessSampler>> = Mutex::new(None);
+
+/// Dataflow and node of a process, from the node configuration in its environment
+pub fn node_marker(environ: &[u8]) -> Option<(String, String)> {
+    #[derive(Deserialize)]
+    struct NodeConfig {
+        dataflow_id: String,
+        node_id: String,
+    }
+
+    let prefix = format!("{}=", NODE_CONFIG_ENV);
+    let value = environ
+        .split(|b| *b == 0)
+        .find_map(|entry| entry.strip_prefix(prefix.as_bytes()))?;
+    let config: NodeConfig = serde_yaml::from_slice(value).ok()?;
+    Some((config.dataflow_id, config.node_id))
+}
+
+/// What `/proc/<pid>/stat` tells about a process
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProcStat {
+    /// User and system time, in clock ticks
+    pub cpu_ticks: u64,
+    pub threads: u32,
+    /// Start time after boot, in clock ticks
+    pub start_ticks: u64,
+}
+
+/// Parse `/proc/<pid>/stat`. The command name in parentheses may contain
+/// spaces and parentheses itself, so fields are counted from the last `)`.
+pub fn parse_stat(text: &str) -> Option<ProcStat> {
+    let fields: Vec<&str> = text[text.rfind(')')? + 1..].split_whitespace().collect();
+    // Fields 14 (utime), 15 (stime), 20 (num_threads) and 22 (starttime),
+    // counted from field 3 (state)
+    let field = |number: usize| fields.get(number - 3)?.parse::<u64>().ok();
+    Some(ProcStat {
+        cpu_ticks: field(14)? + field(15)?,
+        threads: field(20)? as u32,
+        start_ticks: field(22)?,
+    })
+}
+
+/// Resident memory in bytes from the `VmRSS` line of `/proc/<pid>/status`
+pub fn parse_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// One process of a node at one moment
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessReading {
+    pub pid: u32,
+    pub dataflow: String,
+    pub node: String,
+    pub stat: ProcStat,
+    pub rss_bytes: u64,
+    pub fds: u32,
+}
+
+/// Usage of all processes of one node
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeMetrics {
+    pub dataflow: String,
+    pub node: String,
+    pub pids: Vec<u32>,
+    /// Percent of one core, so busy multi-threaded nodes go above 100
+    pub cpu: f64,
+    pub rss_bytes: u64,
+    pub threads: u32,
+    pub fds: u32,
+}
+
+impl NodeMetrics {
+    /// Resident memory in GB, the unit of dataflow memory
+    pub fn memory_gb(&self) -> f64 {
+        self.rss_bytes as f64 / BYTES_PER_GB
+    }
+}
+
+/// Column the node list is sorted by, highest first
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NodeSort {
+    #[default]
+    Cpu,
+    Memory,
+    Threads,
+    Fds,
+}
+
+impl NodeSort {
+    pub const ALL: [NodeSort; 4] = [NodeSort::Cpu, NodeSort::Memory, NodeSort::Threads, NodeSort::Fds];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NodeSort::Cpu => "CPU",
+            NodeSort::Memory => "Memory",
+            NodeSort::Threads => "Threads",
+            NodeSort::Fds => "Open files",
+        }
+    }
+
+    /// `cpu`, `memory`, `threads` or `fds`
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.to_lowercase().as_str() {
+            "cpu" => Some(NodeSort::Cpu),
+            "memory" | "rss" => Some(NodeSort::Memory),
+            "threads" => Some(NodeSort::Threads),
+            "fds" | "files" => Some(NodeSort::Fds),
+            _ => None,
+        }
+    }
+
+    /// Sort highest first, ties by dataflow and node
+    pub fn sort(&self, metrics: &mut [NodeMetrics]) {
+        let key = |m: &NodeMetrics| match self {
+            NodeSort::Cpu => m.cpu,
+            NodeSort::Memory => m.rss_bytes as f64,
+            NodeSort::Threads => m.threads as f64,
+            NodeSort::Fds => m.fds as f64,
+        };
+        metrics.sort_by(|a, b| {
+            key(b)
+                .total_cmp(&key(a))
+                .then_with(|| (&a.dataflow, &a.node).cmp(&(&b.dataflow, &b.node)))
+        });
+    }
+}
+
+/// Turns readings into per-node metrics, remembering each process's CPU
+/// time to report usage between samples
+#[derive(Debug, Default)]
+pub struct ProcessSampler {
+    /// Start and CPU ticks of each process, and seconds since boot, when last read
+    previous: HashMap<u32, (u64, u64, f64)>,
+}
+
+impl ProcessSampler {
+    /// Read all node processes now
+    pub fn sample(&mut self) -> Result<Vec<NodeMetrics>, String> {
+        let readings = read_processes()?;
+        Ok(self.update(readings, read_uptime()?))
+    }
+
+    /// Metrics of the nodes in `readings`, taken `uptime` seconds after boot.
+    /// CPU usage covers the time since the previous reading of each process,
+    /// or since it started when it is new.
+    pub fn update(&mut self, readings: Vec<ProcessReading>, uptime: f64) -> Vec<NodeMetrics> {
+        let mut previous = HashMap::new();
+        let mut nodes: Vec<NodeMetrics> = Vec::new();
+        for reading in readings {
+            let stat = reading.stat;
+            let (since_ticks, since) = match self.previous.get(&reading.pid) {
+                // A reused PID has another start time
+                Some(&(start, ticks, at)) if start == stat.start_ticks && at < uptime => (ticks, at),
+                _ => (0, stat.start_ticks as f64 / CLOCK_TICKS),
+            };
+            let elapsed = uptime - since;
+            let cpu = if elapsed > 0.0 {
+                stat.cpu_ticks.saturating_sub(since_ticks) as f64 / CLOCK_TICKS / elapsed * 100.0
+            } else {
+                0.0
+            };
+            previous.insert(reading.pid, (stat.start_ticks, stat.cpu_ticks, uptime));
+
+            let index = match nodes
+                .iter()
+                .position(|n| n.dataflow == reading.dataflow && n.node == reading.node)
+            {
+                Some(index) => index,
+                None => {
+                    nodes.push(NodeMetrics {
+                        dataflow: reading.dataflow,
+                        node: reading.node,
+                        pids: Vec::new(),
+                        cpu: 0.0,
+                        rss_bytes: 0,
+                        threads: 0,
+                        fds: 0,
+                    });
+                    nodes.len() - 1
+                }
+            };
+            let node = &mut nodes[index];
+            node.pids.push(reading.pid);
+            node.cpu += cpu;
+            node.rss_bytes += reading.rss_bytes;
+            node.threads += stat.threads;
+            node.fds += reading.fds;
+        }
+        // Exited processes are forgotten
+        self.previous = previous;
+        NodeSort::Cpu.sort(&mut nodes);
+        nodes
+    }
+}
+
+/// Processes of dora nodes, skipping processes that exit or can't be read meanwhile
+#[cfg(target_os = "linux")]
+pub fn read_processes() -> Result<Vec<ProcessReading>, String> {
+    use std::fs;
+
+    let entries = fs::read_dir("/proc").map_err(|e| format!("Cannot read /proc: {}", e))?;
+    let mut readings = Vec::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        let path = entry.path();
+        // Other users' environments are not readable, those are no nodes of ours
+        let Some((dataflow, node)) = fs::read(path.join("environ")).ok().and_then(|env| node_marker(&env)) else {
+            continue;
+        };
+        let Some(stat) = fs::read_to_string(path.join("stat")).ok().and_then(|s| parse_stat(&s)) else {
+            continue;
+        };
+        let rss_bytes = fs::read_to_string(path.join("status"))
+            .ok()
+            .and_then(|s| parse_rss(&s))
+            .unwrap_or(0);
+        let fds = fs::read_dir(path.join("fd")).map(|fds| fds.count() as u32).unwrap_or(0);
+        readings.push(ProcessReading {
+            pid,
+            dataflow,
+            node,
+            stat,
+            rss_bytes,
+            fds,
+        });
+    }
+    Ok(readings)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_processes() -> Result<Vec<ProcessReading>, String> {
+    Err("Per-node metrics read /proc, which only Linux has".to_string())
+}
+
+/// Seconds since boot, the clock of process start times
+#[cfg(target_os = "linux")]
+fn read_uptime() -> Result<f64, String> {
+    let text = std::fs::read_to_string("/proc/uptime").map_err(|e| format!("Cannot read /proc/uptime: {}", e))?;
+    text.split_whitespace()
+        .next()
+        .and_then(|secs| secs.parse().ok())
+        .ok_or_else(|| format!("Unexpected /proc/uptime: {}", text.trim()))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_uptime() -> Result<f64, String> {
+    Err("Per-node metrics read /proc, which only Linux has".to_string())
+}
+
+/// CPU and memory samples of each node, recorded next to the dataflows'
+pub fn node_samples(metrics: &[NodeMetrics], timestamp: u64) -> Vec<Sample> {
+    metrics
+        .iter()
+        .flat_map(|m| {
+            [(CPU, m.cpu), (MEMORY, m.memory_gb())].map(|(metric, value)| Sample {
+                timestamp,
+                dataflow: m.dataflow.clone(),
+                node: Some(m.node.clone()),
+                metric: metric.to_string(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Table of node metrics, `names` giving dataflow names by UUID
+pub fn render_node_metrics(metrics: &[NodeMetrics], names: &HashMap<String, String>, sort: NodeSort) -> String {
+    if metrics.is_empty() {
+        return "No dora node processes found on this machine. Per-node metrics only cover \
+                dataflows run by a daemon on this machine."
+            .to_string();
+    }
+    let dataflows = {
+        let mut ids: Vec<&str> = metrics.iter().map(|m| m.dataflow.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        ids.len()
+    };
+    let mut out = format!(
+        "{} nodes of {} dataflows, by {}:\n{:<20} {:<20} {:>7} {:>9} {:>8} {:>5}  PIDS",
+        metrics.len(),
+        dataflows,
+        sort.label(),
+        "NODE",
+        "DATAFLOW",
+        "CPU",
+        "MEMORY",
+        "THREADS",
+        "FDS"
+    );
+    for m in metrics {
+        let dataflow = names
+            .get(&m.dataflow)
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .unwrap_or_else(|| m.dataflow.split('-').next().unwrap_or(&m.dataflow).to_string());
+        let pids: Vec<String> = m.pids.iter().map(u32::to_string).collect();
+        out.push_str(&format!(
+            "\n{:<20} {:<20} {:>7} {:>9} {:>8} {:>5}  {}",
+            m.node,
+            dataflow,
+            format_cpu(m.cpu),
+            format_memory(m.memory_gb()),
+            m.threads,
+            m.fds,
+            pids.join(",")
+        ));
+    }
+    out
+}
+
+/// Sample all node processes, CPU usage covering the time since the
+/// previous call
+pub fn sample_nodes() -> Result<Vec<NodeMetrics>, String> {
+    SAMPLER.lock().unwrap().get_or_insert_with(ProcessSampler::default).sample()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What the daemon puts in a node's environment, shortened
+    const NODE_CONFIG: &str = "dataflow_id: 0193c4ea-5c2f-7d2b-a8f1-6f3e8e1c2b4a
+node_id: camera
+run_config:
+  inputs:
+    tick: dora/timer/millis/20
+  outputs:
+  - image
+daemon_communication: !Tcp
+  socket_addr: 127.0.0.1:53290
+dynamic: false
+";
+
+    fn reading(pid: u32, node: &str, cpu_ticks: u64, start_ticks: u64) -> ProcessReading {
+        ProcessReading {
+            pid,
+            dataflow: "a".to_string(),
+            node: node.to_string(),
+            stat: ProcStat {
+                cpu_ticks,
+                threads: 4,
+                start_ticks,
+            },
+            rss_bytes: 1024 * 1024,
+            fds: 10,
+        }
+    }
+
+    #[test]
+    fn test_node_marker() {
+        let environ = format!("PATH=/usr/bin\0{}={}\0HOME=/root\0", NODE_CONFIG_ENV, NODE_CONFIG);
+        assert_eq!(
+            node_marker(environ.as_bytes()),
+            Some((
+                "0193c4ea-5c2f-7d2b-a8f1-6f3e8e1c2b4a".to_string(),
+                "camera".to_string()
+            ))
+        );
+        assert_eq!(node_marker(b"PATH=/usr/bin\0HOME=/root\0"), None);
+        assert_eq!(node_marker(b"DORA_NODE_CONFIG=not: [yaml\0"), None);
+    }
+
+    #[test]
+    fn test_parse_stat_and_status() {
+        let stat = "4242 (python3 (my node)) S 1 4242 4242 0 -1 4194304 5000 0 0 0 \
+                    250 50 0 0 20 0 7 0 123456 1000000 2000 18446744073709551615";
+        assert_eq!(
+            parse_stat(stat),
+            Some(ProcStat {
+                cpu_ticks: 300,
+                threads: 7,
+                start_ticks: 123456
+            })
+        );
+        assert_eq!(parse_stat("4242 (truncated) S 1"), None);
+
+        let status = "Name:\tpython3\nVmPeak:\t  200000 kB\nVmRSS:\t   51200 kB\nThreads:\t7\n";
+        assert_eq!(parse_rss(status), Some(51200 * 1024));
+        assert_eq!(parse_rss("Name:\tkthreadd\n"), None);
+    }
+
+    #[test]
+    fn test_sampler_cpu_between_samples() {
+        let mut sampler = ProcessSampler::default();
+        // Started 10 s after boot, 5 s of CPU in the 10 s since: 50%
+        let first = sampler.update(vec![reading(1, "camera", 500, 1000)], 20.0);
+        assert_eq!(first.len(), 1);
+        assert!((first[0].cpu - 50.0).abs() < 1e-9);
+
+        // 2 s of CPU in the next 2 s: 100%. A second process of the same node adds up.
+        let second = sampler.update(
+            vec![reading(1, "camera", 700, 1000), reading(2, "detector", 100, 1500), reading(3, "camera", 0, 2100)],
+            22.0,
+        );
+        assert_eq!(second[0].node, "camera");
+        assert_eq!(second[0].pids, vec![1, 3]);
+        assert!((second[0].cpu - 100.0).abs() < 1e-9);
+        assert_eq!(second[0].threads, 8);
+        assert_eq!(second[0].rss_bytes, 2 * 1024 * 1024);
+        assert_eq!(second[1].node, "detector");
+
+        // PID 1 reused by a process started later counts from its own start
+        let reused = sampler.update(vec![reading(1, "camera", 100, 2300)], 24.0);
+        assert!((reused[0].cpu - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sort_and_render() {
+        let mut metrics = ProcessSampler::default().update(
+            vec![reading(1, "camera", 100, 1000), reading(2, "detector", 900, 1000)],
+            20.0,
+        );
+        assert_eq!(metrics[0].node, "detector");
+        metrics[0].fds = 3;
+        NodeSort::Fds.sort(&mut metrics);
+        assert_eq!(metrics[0].node, "camera");
+        assert_eq!(NodeSort::parse("RSS"), Some(NodeSort::Memory));
+        assert_eq!(NodeSort::parse("disk"), None);
+
+        let names = HashMap::from([("a".to_string(), "vision".to_string())]);
+        let text = render_node_metrics(&metrics, &names, NodeSort::Fds);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "2 nodes of 1 dataflows, by Open files:");
+        assert!(lines[2].starts_with("camera"));
+        assert!(lines[2].contains("vision"));
+        assert!(lines[2].contains("10.0%"));
+        assert!(lines[2].ends_with("  1"));
+        assert!(render_node_metrics(&[], &names, NodeSort::Cpu).starts_with("No dora node processes"));
+
+        let samples = node_samples(&metrics, 100);
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples[0].node.as_deref(), Some("camera"));
+        assert_eq!(samples[1].metric, MEMORY);
+    }
+}
@@ -0,0 +1,256 @@
+//! Metric samples and how to summarize them.
+
+use crate::dataflow::{DataflowInfo, DataflowStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// CPU usage in percent
+pub const CPU: &str = "cpu";
+/// Memory usage in GB
+pub const MEMORY: &str = "memory";
+
+/// Samples kept per series for sparklines
+pub const RECENT_SAMPLES: usize = 60;
+
+/// One value of one metric at one time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    /// Unix time in seconds
+    #[serde(rename = "t")]
+    pub timestamp: u64,
+    #[serde(rename = "d")]
+    pub dataflow: String,
+    /// Node the value belongs to, `None` for the whole dataflow
+    #[serde(rename = "n", default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(rename = "m")]
+    pub metric: String,
+    #[serde(rename = "v")]
+    pub value: f64,
+}
+
+/// CPU and memory samples of the running dataflows at `timestamp`
+pub fn dataflow_samples(dataflows: &[DataflowInfo], timestamp: u64) -> Vec<Sample> {
+    dataflows
+        .iter()
+        .filter(|df| df.status == DataflowStatus::Running)
+        .flat_map(|df| {
+            [(CPU, df.cpu), (MEMORY, df.memory)].map(|(metric, value)| Sample {
+                timestamp,
+                dataflow: df.uuid.clone(),
+                node: None,
+                metric: metric.to_string(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Which samples to read. Fields left `None` match everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SampleQuery {
+    pub dataflow: Option<String>,
+    pub node: Option<String>,
+    pub metric: Option<String>,
+    /// First second included
+    pub since: Option<u64>,
+    /// Last second included
+    pub until: Option<u64>,
+}
+
+impl SampleQuery {
+    pub fn matches(&self, sample: &Sample) -> bool {
+        self.dataflow.as_ref().is_none_or(|d| *d == sample.dataflow)
+            && self.node.as_ref().is_none_or(|n| sample.node.as_ref() == Some(n))
+            && self.metric.as_ref().is_none_or(|m| *m == sample.metric)
+            && self.since.is_none_or(|since| sample.timestamp >= since)
+            && self.until.is_none_or(|until| sample.timestamp <= until)
+    }
+}
+
+/// Count, minimum, maximum and average of some values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+}
+
+impl Summary {
+    /// Summary of `values`, `None` if there are none
+    pub fn of(values: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let mut summary: Option<Summary> = None;
+        for value in values {
+            match &mut summary {
+                Some(s) => s.add(value),
+                None => {
+                    summary = Some(Summary {
+                        count: 1,
+                        min: value,
+                        max: value,
+                        sum: value,
+                    })
+                }
+            }
+        }
+        summary
+    }
+
+    pub fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+    }
+
+    pub fn avg(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+/// Dataflow, node and metric of a series
+type SeriesKey = (String, Option<String>, String);
+
+/// The newest [`RECENT_SAMPLES`] values of each series, for sparklines
+#[derive(Debug, Clone, Default)]
+pub struct RecentSamples {
+    series: HashMap<SeriesKey, VecDeque<(u64, f64)>>,
+}
+
+impl RecentSamples {
+    pub fn push(&mut self, sample: &Sample) {
+        let key = (sample.dataflow.clone(), sample.node.clone(), sample.metric.clone());
+        let values = self.series.entry(key).or_default();
+        values.push_back((sample.timestamp, sample.value));
+        if values.len() > RECENT_SAMPLES {
+            values.pop_front();
+        }
+    }
+
+    /// Values of a series, oldest first
+    pub fn values(&self, dataflow: &str, node: Option<&str>, metric: &str) -> Vec<f64> {
+        let key = (dataflow.to_string(), node.map(str::to_string), metric.to_string());
+        self.series
+            .get(&key)
+            .map(|values| values.iter().map(|(_, value)| *value).collect())
+            .unwrap_or_default()
+    }
+
+    /// The series of whole dataflows, without those of their nodes
+    pub fn dataflow_series(&self) -> Self {
+        Self {
+            series: self
+                .series
+                .iter()
+                .filter(|((_, node, _), _)| node.is_none())
+                .map(|(key, values)| (key.clone(), values.clone()))
+                .collect(),
+        }
+    }
+
+    /// Drop values older than `since`, and series left without any, e.g. of
+    /// stopped dataflows
+    pub fn forget_before(&mut self, since: u64) {
+        self.series.retain(|_, values| {
+            while values.front().is_some_and(|(timestamp, _)| *timestamp < since) {
+                values.pop_front();
+            }
+            !values.is_empty()
+        });
+    }
+}
+
+/// Summaries of `samples` in buckets of `bucket_secs` aligned to multiples of
+/// it, with the start of each bucket. Empty buckets are left out.
+pub fn downsample(samples: &[Sample], bucket_secs: u64) -> Vec<(u64, Summary)> {
+    let bucket_secs = bucket_secs.max(1);
+    let mut buckets: Vec<(u64, Summary)> = Vec::new();
+    let mut sorted: Vec<&Sample> = samples.iter().collect();
+    sorted.sort_by_key(|sample| sample.timestamp);
+    for sample in sorted {
+        let start = sample.timestamp / bucket_secs * bucket_secs;
+        match buckets.last_mut() {
+            Some((last, summary)) if *last == start => summary.add(sample.value),
+            _ => buckets.push((start, Summary::of([sample.value]).unwrap())),
+        }
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: u64, value: f64) -> Sample {
+        Sample {
+            timestamp,
+            dataflow: "a".to_string(),
+            node: None,
+            metric: CPU.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_dataflow_samples_skip_stopped() {
+        let dataflows = vec![
+            DataflowInfo {
+                uuid: "a".to_string(),
+                status: DataflowStatus::Running,
+                cpu: 12.5,
+                memory: 0.25,
+                ..Default::default()
+            },
+            DataflowInfo {
+                uuid: "b".to_string(),
+                status: DataflowStatus::Finished,
+                ..Default::default()
+            },
+        ];
+        let samples = dataflow_samples(&dataflows, 100);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0], sample(100, 12.5));
+        assert_eq!(samples[1].metric, MEMORY);
+        assert_eq!(samples[1].value, 0.25);
+    }
+
+    #[test]
+    fn test_recent_samples() {
+        let mut recent = RecentSamples::default();
+        for i in 0..100 {
+            recent.push(&sample(i, i as f64));
+        }
+        let values = recent.values("a", None, CPU);
+        assert_eq!(values.len(), RECENT_SAMPLES);
+        assert_eq!(values[0], 40.0);
+        assert!(recent.values("a", Some("camera"), CPU).is_empty());
+
+        recent.push(&Sample {
+            node: Some("camera".to_string()),
+            ..sample(99, 7.0)
+        });
+        let dataflows = recent.dataflow_series();
+        assert_eq!(dataflows.values("a", None, CPU).len(), RECENT_SAMPLES);
+        assert!(dataflows.values("a", Some("camera"), CPU).is_empty());
+
+        recent.forget_before(98);
+        assert_eq!(recent.values("a", None, CPU), vec![98.0, 99.0]);
+        recent.forget_before(100);
+        assert!(recent.values("a", None, CPU).is_empty());
+    }
+
+    #[test]
+    fn test_downsample() {
+        let samples = vec![sample(125, 4.0), sample(61, 1.0), sample(64, 3.0), sample(119, 2.0)];
+        let buckets = downsample(&samples, 60);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].0, 60);
+        assert_eq!(buckets[0].1.count, 3);
+        assert_eq!(buckets[0].1.min, 1.0);
+        assert_eq!(buckets[0].1.max, 3.0);
+        assert_eq!(buckets[0].1.avg(), 2.0);
+        assert_eq!(buckets[1], (120, Summary::of([4.0]).unwrap()));
+        assert!(Summary::of([]).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use makepad_widgets::*;
+
+use super::series::{Summary, RECENT_SAMPLES};
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    // Recent values as bars, newest on the right. Hovering shows min, max and average.
+    pub Sparkline = {{Sparkline}} {
+        width: 64, height: 22
+        draw_bg: { color: #f1f5f9 }
+        draw_bar: {
+            color: #3b82f6
+            // Fade towards the baseline so the tops of the bars read as a line
+            fn pixel(self) -> vec4 {
+                let alpha = self.color.a * mix(1.0, 0.3, self.pos.y);
+                return vec4(self.color.rgb * alpha, alpha);
+            }
+        }
+        draw_tip: { color: #1e293b }
+        draw_text: {
+            color: #ffffff
+            text_style: { font_size: 8.0 }
+        }
+    }
+}
+
+/// Width of the hover tooltip, it extends to the left over the value
+const TIP_WIDTH: f64 = 150.0;
+
+/// Sparkline of a metric's recent values, with min/max/avg on hover
+#[derive(Live, LiveHook, Widget)]
+pub struct Sparkline {
+    #[walk]
+    walk: Walk,
+    #[redraw]
+    #[live]
+    draw_bg: DrawColor,
+    #[live]
+    draw_bar: DrawColor,
+    #[live]
+    draw_tip: DrawColor,
+    #[live]
+    draw_text: DrawText,
+    #[rust]
+    values: Vec<f64>,
+    /// Formats values in the tooltip, e.g. as a percentage
+    #[rust]
+    format: Option<fn(f64) -> String>,
+    #[rust]
+    hovered: bool,
+}
+
+impl Widget for Sparkline {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, _scope: &mut Scope) {
+        match event.hits(cx, self.draw_bg.area()) {
+            Hit::FingerHoverIn(_) => {
+                self.hovered = true;
+                self.redraw(cx);
+            }
+            Hit::FingerHoverOut(_) => {
+                self.hovered = false;
+                self.redraw(cx);
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle(walk);
+        self.draw_bg.draw_abs(cx, rect);
+
+        if self.hovered {
+            let tip = Rect {
+                pos: dvec2(rect.pos.x + rect.size.x - TIP_WIDTH, rect.pos.y),
+                size: dvec2(TIP_WIDTH, rect.size.y),
+            };
+            self.draw_tip.draw_abs(cx, tip);
+            let text = self.tip_text();
+            self.draw_text.draw_abs(cx, tip.pos + dvec2(6.0, rect.size.y / 2.0 - 6.0), &text);
+            return DrawStep::done();
+        }
+
+        let max = self.values.iter().cloned().fold(0.0, f64::max);
+        let slot = rect.size.x / RECENT_SAMPLES as f64;
+        // Right-aligned, so the newest value is always at the right edge
+        let first_x = rect.pos.x + rect.size.x - self.values.len() as f64 * slot;
+        for (index, value) in self.values.iter().enumerate() {
+            let height = if max > 0.0 {
+                (rect.size.y * value / max).max(1.0)
+            } else {
+                1.0
+            };
+            self.draw_bar.draw_abs(
+                cx,
+                Rect {
+                    pos: dvec2(first_x + index as f64 * slot, rect.pos.y + rect.size.y - height),
+                    size: dvec2(slot.max(1.0), height),
+                },
+            );
+        }
+        DrawStep::done()
+    }
+}
+
+impl Sparkline {
+    /// "min 2.0% max 40.3% avg 12.1%"
+    fn tip_text(&self) -> String {
+        let format = self.format.unwrap_or(|value| format!("{:.2}", value));
+        match Summary::of(self.values.iter().cloned()) {
+            Some(summary) => format!(
+                "min {}  max {}  avg {}",
+                format(summary.min),
+                format(summary.max),
+                format(summary.avg())
+            ),
+            None => "No history yet".to_string(),
+        }
+    }
+}
+
+impl SparklineRef {
+    /// Show `values`, oldest first, formatting them with `format` on hover
+    pub fn set_values(&self, cx: &mut Cx, values: Vec<f64>, format: fn(f64) -> String) {
+        if let Some(mut inner) = self.borrow_mut() {
+            if inner.values != values {
+                inner.values = values;
+                inner.redraw(cx);
+            }
+            inner.format = Some(format);
+        }
+    }
+}
@@ -0,0 +1,404 @@
+//! On-disk history of metric samples.
+//!
+//! Samples are appended as JSON lines to hourly segment files named after
+//! the Unix time the hour starts at, e.g. `metrics/1768989600.jsonl`. Appends
+//! are cheap, a query only opens the segments its time range touches, and
+//! retention deletes whole segments: those older than the maximum age, then
+//! the oldest ones while the store is over its size limit. A line cut short by
+//! a crash is skipped when read.
+
+use super::series::{RecentSamples, Sample, SampleQuery};
+use super::telemetry::{TelemetryData, TelemetryRequest};
+use crate::dataflow::model::unix_now;
+use crate::settings::{self, studio_dir};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Store of the Studio directory, opened on first access
+static STORE: Mutex<Option<MetricsStore>> = Mutex::new(None);
+
+/// Seconds covered by one segment file
+pub const SEGMENT_SECS: u64 = 3600;
+
+/// Time between retention checks while recording
+const PRUNE_INTERVAL_SECS: u64 = 600;
+
+/// How far back recent samples are loaded and kept, for sparklines
+const RECENT_SECS: u64 = 15 * 60;
+
+/// How long and how much history is kept
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Retention {
+    pub max_age_secs: u64,
+    pub max_bytes: u64,
+}
+
+impl Retention {
+    /// Retention set in the settings
+    pub fn from_settings() -> Self {
+        let settings = settings::get();
+        Self {
+            max_age_secs: settings.metrics_retention_days * 86400,
+            max_bytes: settings.metrics_max_mb * 1024 * 1024,
+        }
+    }
+}
+
+/// Metric samples in hourly segment files under one directory
+#[derive(Debug)]
+pub struct MetricsStore {
+    dir: PathBuf,
+    retention: Retention,
+    last_prune: u64,
+    /// Newest values of each series, kept in memory
+    recent: RecentSamples,
+}
+
+impl MetricsStore {
+    /// Open the store in `dir`, creating it, and apply the retention
+    pub fn open(dir: &Path, retention: Retention) -> Result<Self, String> {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let mut store = Self {
+            dir: dir.to_path_buf(),
+            retention,
+            last_prune: 0,
+            recent: RecentSamples::default(),
+        };
+        let now = unix_now();
+        store.prune(now)?;
+        let query = SampleQuery {
+            since: Some(now.saturating_sub(RECENT_SECS)),
+            ..Default::default()
+        };
+        for sample in store.query(&query)? {
+            store.recent.push(&sample);
+        }
+        Ok(store)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn set_retention(&mut self, retention: Retention) {
+        self.retention = retention;
+    }
+
+    /// Append samples to the segments of their hours
+    pub fn insert(&mut self, samples: &[Sample]) -> Result<(), String> {
+        let mut sorted: Vec<&Sample> = samples.iter().collect();
+        sorted.sort_by_key(|sample| sample.timestamp);
+        for chunk in sorted.chunk_by(|a, b| segment_start(a.timestamp) == segment_start(b.timestamp)) {
+            let path = self.segment_path(segment_start(chunk[0].timestamp));
+            let mut text = String::new();
+            for sample in chunk {
+                let line = serde_json::to_string(sample).map_err(|e| format!("Failed to serialize sample: {}", e))?;
+                text.push_str(&line);
+                text.push('\n');
+            }
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut file| file.write_all(text.as_bytes()))
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            for sample in chunk {
+                self.recent.push(sample);
+            }
+        }
+
+        let now = unix_now();
+        self.recent.forget_before(now.saturating_sub(RECENT_SECS));
+        if now >= self.last_prune + PRUNE_INTERVAL_SECS {
+            self.prune(now)?;
+        }
+        Ok(())
+    }
+
+    /// Samples matching `query`, oldest first
+    pub fn query(&self, query: &SampleQuery) -> Result<Vec<Sample>, String> {
+        read_segments(&self.segments()?, query)
+    }
+
+    /// Newest values of a series, oldest first
+    pub fn recent(&self, dataflow: &str, node: Option<&str>, metric: &str) -> Vec<f64> {
+        self.recent.values(dataflow, node, metric)
+    }
+
+    /// Newest values of every dataflow's series
+    pub fn recent_samples(&self) -> RecentSamples {
+        self.recent.dataflow_series()
+    }
+
+    /// Series for the telemetry dashboard
+    pub fn load_telemetry(&self, request: TelemetryRequest) -> Result<TelemetryData, String> {
+        telemetry_from_segments(&self.segments()?, request)
+    }
+
+    /// Delete segments beyond the retention at `now`, returning how many
+    pub fn prune(&mut self, now: u64) -> Result<usize, String> {
+        self.last_prune = now;
+        let oldest_kept = segment_start(now.saturating_sub(self.retention.max_age_secs));
+        let mut segments = self.segments()?;
+        let mut removed = 0;
+
+        let expired = segments.iter().take_while(|(start, _)| *start < oldest_kept).count();
+        for (_, path) in segments.drain(..expired) {
+            remove_segment(&path)?;
+            removed += 1;
+        }
+
+        let mut sizes: Vec<u64> = segments
+            .iter()
+            .map(|(_, path)| fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+            .collect();
+        let mut total: u64 = sizes.iter().sum();
+        // The newest segment stays, it is being written to
+        while total > self.retention.max_bytes && segments.len() > 1 {
+            let (_, path) = segments.remove(0);
+            total -= sizes.remove(0);
+            remove_segment(&path)?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    /// Bytes used by all segments
+    pub fn size_bytes(&self) -> Result<u64, String> {
+        Ok(self
+            .segments()?
+            .iter()
+            .map(|(_, path)| fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+            .sum())
+    }
+
+    /// Segment files with the start of their hour, oldest first
+    pub fn segments(&self) -> Result<Vec<(u64, PathBuf)>, String> {
+        let entries = fs::read_dir(&self.dir).map_err(|e| format!("Failed to read {}: {}", self.dir.display(), e))?;
+        let mut segments: Vec<(u64, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+            .filter_map(|path| {
+                let start = path.file_stem()?.to_str()?.parse().ok()?;
+                Some((start, path))
+            })
+            .collect();
+        segments.sort();
+        Ok(segments)
+    }
+
+    fn segment_path(&self, start: u64) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", start))
+    }
+}
+
+/// Start of the hour segment holding `timestamp`
+pub fn segment_start(timestamp: u64) -> u64 {
+    timestamp / SEGMENT_SECS * SEGMENT_SECS
+}
+
+/// Samples of `segments` matching `query`, oldest first. Only reads the
+/// files, so it runs without the store locked.
+fn read_segments(segments: &[(u64, PathBuf)], query: &SampleQuery) -> Result<Vec<Sample>, String> {
+    let mut samples = Vec::new();
+    for (start, path) in segments {
+        let end = start + SEGMENT_SECS - 1;
+        if query.since.is_some_and(|since| end < since) || query.until.is_some_and(|until| *start > until) {
+            continue;
+        }
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            // Pruned since it was listed
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        samples.extend(
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str::<Sample>(line).ok())
+                .filter(|sample| query.matches(sample)),
+        );
+    }
+    samples.sort_by_key(|sample| sample.timestamp);
+    Ok(samples)
+}
+
+/// Series for the telemetry dashboard, read from `segments`
+fn telemetry_from_segments(segments: &[(u64, PathBuf)], request: TelemetryRequest) -> Result<TelemetryData, String> {
+    let mut samples = Vec::new();
+    for (uuid, _) in &request.dataflows {
+        samples.extend(read_segments(
+            segments,
+            &SampleQuery {
+                dataflow: Some(uuid.clone()),
+                since: Some(request.window.start),
+                until: Some(request.window.end),
+                ..Default::default()
+            },
+        )?);
+    }
+    Ok(TelemetryData::build(request, &samples))
+}
+
+fn remove_segment(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove {}: {}", path.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Directory of the metrics history
+pub fn metrics_dir() -> PathBuf {
+    studio_dir().join("metrics")
+}
+
+fn with_store<T>(f: impl FnOnce(&mut MetricsStore) -> Result<T, String>) -> Result<T, String> {
+    let mut store = STORE.lock().unwrap();
+    if store.is_none() {
+        *store = Some(MetricsStore::open(&metrics_dir(), Retention::from_settings())?);
+    }
+    f(store.as_mut().unwrap())
+}
+
+/// Record samples in the history, logging failures
+pub fn record(samples: &[Sample]) {
+    if samples.is_empty() {
+        return;
+    }
+    if let Err(e) = with_store(|store| store.insert(samples)) {
+        eprintln!("[Metrics] Failed to record samples: {}", e);
+    }
+}
+
+/// Samples of the history matching `query`. The store is locked only to
+/// list the segments, so recording carries on while they are read.
+pub fn query(query: &SampleQuery) -> Result<Vec<Sample>, String> {
+    read_segments(&with_store(|store| store.segments())?, query)
+}
+
+/// Newest values of every dataflow's series, empty when the store can't be opened
+pub fn recent_samples() -> RecentSamples {
+    with_store(|store| Ok(store.recent_samples())).unwrap_or_default()
+}
+
+/// Series for the telemetry dashboard. Like [`query`], the segments are
+/// read with the store unlocked.
+pub fn load_telemetry(request: TelemetryRequest) -> Result<TelemetryData, String> {
+    telemetry_from_segments(&with_store(|store| store.segments())?, request)
+}
+
+/// Series loaded for the telemetry dashboard
+#[derive(Debug)]
+pub struct TelemetryLoaded(pub Result<TelemetryData, String>);
+
+/// Load the series of `request` on a thread of its own, a day of history is
+/// too much to read on the UI thread
+pub fn load_telemetry_in_background(request: TelemetryRequest, done: impl FnOnce(TelemetryLoaded) + Send + 'static) {
+    std::thread::spawn(move || done(TelemetryLoaded(load_telemetry(request))));
+}
+
+/// Apply changed retention settings now
+pub fn apply_retention() -> Result<usize, String> {
+    with_store(|store| {
+        store.set_retention(Retention::from_settings());
+        store.prune(unix_now())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::series::CPU;
+
+    const KEEP_ALL: Retention = Retention {
+        max_age_secs: u64::MAX,
+        max_bytes: u64::MAX,
+    };
+
+    fn sample(timestamp: u64, value: f64) -> Sample {
+        Sample {
+            timestamp,
+            dataflow: "a".to_string(),
+            node: None,
+            metric: CPU.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_insert_splits_hours_and_skips_torn_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = MetricsStore::open(dir.path(), KEEP_ALL).unwrap();
+        store
+            .insert(&[sample(3599, 1.0), sample(3600, 2.0), sample(7300, 3.0), sample(10, 0.5)])
+            .unwrap();
+        let starts: Vec<u64> = store.segments().unwrap().into_iter().map(|(start, _)| start).collect();
+        assert_eq!(starts, vec![0, 3600, 7200]);
+
+        let mut file = OpenOptions::new().append(true).open(dir.path().join("3600.jsonl")).unwrap();
+        file.write_all(b"{\"t\":3700,\"d\":\"a\",\"m\":").unwrap();
+        let values: Vec<f64> = store
+            .query(&SampleQuery::default())
+            .unwrap()
+            .iter()
+            .map(|s| s.value)
+            .collect();
+        assert_eq!(values, vec![0.5, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_recent_values_survive_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = unix_now();
+        {
+            let mut store = MetricsStore::open(dir.path(), KEEP_ALL).unwrap();
+            store
+                .insert(&[sample(now - 2 * RECENT_SECS, 1.0), sample(now - 10, 2.0), sample(now - 5, 3.0)])
+                .unwrap();
+            assert_eq!(store.recent("a", None, CPU), vec![2.0, 3.0]);
+        }
+        let store = MetricsStore::open(dir.path(), KEEP_ALL).unwrap();
+        assert_eq!(store.recent("a", None, CPU), vec![2.0, 3.0]);
+        assert!(store.recent("b", None, CPU).is_empty());
+    }
+
+    #[test]
+    fn test_prune_by_age_and_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = 10 * SEGMENT_SECS + 5;
+        let mut store = MetricsStore::open(
+            dir.path(),
+            Retention {
+                max_age_secs: 3 * SEGMENT_SECS,
+                max_bytes: u64::MAX,
+            },
+        )
+        .unwrap();
+        let samples: Vec<Sample> = (0..=10).map(|hour| sample(hour * SEGMENT_SECS, 1.0)).collect();
+        store.insert(&samples).unwrap();
+        assert_eq!(store.segments().unwrap().len(), 11);
+
+        // Hours 7 to 10 are within three hours of now
+        assert_eq!(store.prune(now).unwrap(), 7);
+        assert_eq!(store.segments().unwrap()[0].0, 7 * SEGMENT_SECS);
+
+        let segment_bytes = store.size_bytes().unwrap() / 4;
+        store.set_retention(Retention {
+            max_age_secs: u64::MAX,
+            max_bytes: 2 * segment_bytes,
+        });
+        assert_eq!(store.prune(now).unwrap(), 2);
+        let hours: Vec<u64> = store
+            .query(&SampleQuery::default())
+            .unwrap()
+            .iter()
+            .map(|s| s.timestamp / SEGMENT_SECS)
+            .collect();
+        assert_eq!(hours, vec![9, 10]);
+    }
+}
@@ -0,0 +1,309 @@
+//! What the telemetry dashboard shows: a time window and the series in it.
+//!
+//! The window starts as one of the [`RangePreset`]s or a custom range and is
+//! then zoomed and panned. Each change asks for the recorded samples of the
+//! window again, averaged into about one point per pixel column, so a day of
+//! history costs no more to draw than five minutes.
+
+use super::series::{downsample, Sample, CPU, MEMORY};
+use crate::dataflow::model::{format_utc, parse_rfc3339};
+
+/// Shortest window zooming in goes to
+pub const MIN_WINDOW_SECS: u64 = 30;
+
+/// Ranges offered above the charts
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RangePreset {
+    #[default]
+    FiveMinutes,
+    Hour,
+    Day,
+    /// Typed in, or zoomed and panned away from a preset
+    Custom,
+}
+
+impl RangePreset {
+    /// Length of the range, `None` for custom ranges
+    pub fn secs(&self) -> Option<u64> {
+        match self {
+            RangePreset::FiveMinutes => Some(5 * 60),
+            RangePreset::Hour => Some(3600),
+            RangePreset::Day => Some(86400),
+            RangePreset::Custom => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RangePreset::FiveMinutes => "5m",
+            RangePreset::Hour => "1h",
+            RangePreset::Day => "24h",
+            RangePreset::Custom => "Custom",
+        }
+    }
+}
+
+/// A span of time in Unix seconds, both ends included
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeWindow {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl TimeWindow {
+    /// The `secs` before `now`
+    pub fn last(secs: u64, now: u64) -> Self {
+        Self {
+            start: now.saturating_sub(secs),
+            end: now,
+        }
+    }
+
+    /// A custom range from two times typed as RFC 3339 or `YYYY-MM-DD HH:MM[:SS]`
+    /// in UTC. An empty `until` means `now`.
+    pub fn parse(since: &str, until: &str, now: u64) -> Result<Self, String> {
+        let start = parse_time(since).ok_or_else(|| format!("Invalid start time: {}", since))?;
+        let end = if until.trim().is_empty() {
+            now
+        } else {
+            parse_time(until).ok_or_else(|| format!("Invalid end time: {}", until))?
+        };
+        if end <= start {
+            return Err("The end of the range must be after its start".to_string());
+        }
+        Ok(Self { start, end })
+    }
+
+    pub fn secs(&self) -> u64 {
+        self.end - self.start
+    }
+
+    /// Where `timestamp` falls, 0.0 at the start and 1.0 at the end
+    pub fn fraction(&self, timestamp: u64) -> f64 {
+        (timestamp as f64 - self.start as f64) / self.secs().max(1) as f64
+    }
+
+    /// The time at `fraction` of the window
+    pub fn time_at(&self, fraction: f64) -> u64 {
+        (self.start as f64 + fraction.clamp(0.0, 1.0) * self.secs() as f64).round() as u64
+    }
+
+    /// Zoom by `factor` (below 1.0 zooms in) keeping the time at `anchor`,
+    /// a fraction of the window, in place
+    pub fn zoom(&self, factor: f64, anchor: f64) -> Self {
+        let anchor = anchor.clamp(0.0, 1.0);
+        let secs = (self.secs() as f64 * factor).max(MIN_WINDOW_SECS as f64);
+        let pivot = self.start as f64 + anchor * self.secs() as f64;
+        let start = (pivot - anchor * secs).max(0.0);
+        Self {
+            start: start.round() as u64,
+            end: (start + secs).round() as u64,
+        }
+    }
+
+    /// Move by `fraction` of the window, positive towards later times, but
+    /// not past `now`
+    pub fn pan(&self, fraction: f64, now: u64) -> Self {
+        let shift = (fraction * self.secs() as f64).round() as i64;
+        let latest_shift = now as i64 - self.end as i64;
+        let shift = shift.min(latest_shift.max(0)).max(-(self.start as i64));
+        Self {
+            start: (self.start as i64 + shift) as u64,
+            end: (self.end as i64 + shift) as u64,
+        }
+    }
+}
+
+/// Unix seconds of `2026-01-22T10:57:00Z`, `2026-01-22 10:57:00` or `2026-01-22 10:57`
+fn parse_time(text: &str) -> Option<u64> {
+    let text = text.trim();
+    match text.len() {
+        16 => parse_rfc3339(&format!("{}:00", text)),
+        _ => parse_rfc3339(text),
+    }
+}
+
+/// Axis label of a time: `10:57:30`, or `01-22 10:57` in windows over half a day
+pub fn time_label(timestamp: u64, window_secs: u64) -> String {
+    let utc = format_utc(timestamp);
+    if window_secs > 43200 {
+        utc[5..16].to_string()
+    } else {
+        utc[11..19].to_string()
+    }
+}
+
+/// One line of a chart: a dataflow, or one of its nodes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartSeries {
+    pub label: String,
+    /// Average of each bucket with samples, by bucket start
+    pub points: Vec<(u64, f64)>,
+}
+
+/// What to load for the dashboard
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryRequest {
+    /// UUID and label of the dataflow, then of the one compared with it
+    pub dataflows: Vec<(String, String)>,
+    /// Only this node's series instead of the dataflows'
+    pub node: Option<String>,
+    pub window: TimeWindow,
+    /// About how many points each series gets
+    pub points: usize,
+}
+
+impl TelemetryRequest {
+    /// Seconds averaged into one point
+    pub fn bucket_secs(&self) -> u64 {
+        self.window.secs().div_ceil(self.points.max(1) as u64).max(1)
+    }
+}
+
+/// Series of a request, ready to draw
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryData {
+    pub request: TelemetryRequest,
+    pub cpu: Vec<ChartSeries>,
+    pub memory: Vec<ChartSeries>,
+    /// Nodes of the first dataflow with samples in the window
+    pub nodes: Vec<String>,
+}
+
+impl TelemetryData {
+    /// Build the series of `request` from samples of its dataflows in its window
+    pub fn build(request: TelemetryRequest, samples: &[Sample]) -> Self {
+        let bucket_secs = request.bucket_secs();
+        let mut nodes: Vec<String> = samples
+            .iter()
+            .filter(|s| Some(&s.dataflow) == request.dataflows.first().map(|(uuid, _)| uuid))
+            .filter_map(|s| s.node.clone())
+            .collect();
+        nodes.sort();
+        nodes.dedup();
+
+        let series = |metric: &str| -> Vec<ChartSeries> {
+            request
+                .dataflows
+                .iter()
+                .map(|(uuid, label)| {
+                    let matching: Vec<Sample> = samples
+                        .iter()
+                        .filter(|s| s.dataflow == *uuid && s.metric == metric && s.node == request.node)
+                        .cloned()
+                        .collect();
+                    let label = match &request.node {
+                        Some(node) => format!("{} / {}", label, node),
+                        None => label.clone(),
+                    };
+                    ChartSeries {
+                        label,
+                        points: downsample(&matching, bucket_secs)
+                            .into_iter()
+                            .map(|(start, summary)| (start, summary.avg()))
+                            .collect(),
+                    }
+                })
+                .collect()
+        };
+        Self {
+            cpu: series(CPU),
+            memory: series(MEMORY),
+            nodes,
+            request,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cpu.iter().chain(&self.memory).all(|series| series.points.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(dataflow: &str, node: Option<&str>, metric: &str, timestamp: u64, value: f64) -> Sample {
+        Sample {
+            timestamp,
+            dataflow: dataflow.to_string(),
+            node: node.map(str::to_string),
+            metric: metric.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_zoom_and_pan() {
+        let window = TimeWindow::last(3600, 10_000);
+        assert_eq!(window, TimeWindow { start: 6400, end: 10_000 });
+
+        // Zooming in around the middle keeps the middle in place
+        let zoomed = window.zoom(0.5, 0.5);
+        assert_eq!(zoomed, TimeWindow { start: 7300, end: 9100 });
+        // Around the right edge, the end stays
+        assert_eq!(window.zoom(0.5, 1.0).end, 10_000);
+        assert_eq!(window.zoom(0.0001, 0.5).secs(), MIN_WINDOW_SECS);
+
+        // Panning stops at now, and at the epoch
+        assert_eq!(zoomed.pan(-0.5, 10_000), TimeWindow { start: 6400, end: 8200 });
+        assert_eq!(zoomed.pan(2.0, 10_000), TimeWindow { start: 8200, end: 10_000 });
+        assert_eq!(TimeWindow { start: 100, end: 400 }.pan(-1.0, 10_000).start, 0);
+
+        assert_eq!(window.fraction(8200), 0.5);
+        assert_eq!(window.time_at(0.25), 7300);
+        assert_eq!(time_label(window.end, window.secs()), "02:46:40");
+        assert_eq!(time_label(window.end, 86400), "01-01 02:46");
+    }
+
+    #[test]
+    fn test_parse_custom_range() {
+        let window = TimeWindow::parse("2026-01-22 10:00", "2026-01-22T11:30:00Z", 0).unwrap();
+        assert_eq!(window.secs(), 5400);
+        let open = TimeWindow::parse("2026-01-22 10:00:00", "", window.end + 60).unwrap();
+        assert_eq!(open.end, window.end + 60);
+        assert_eq!(
+            TimeWindow::parse("yesterday", "", 0).unwrap_err(),
+            "Invalid start time: yesterday"
+        );
+        assert!(TimeWindow::parse("2026-01-22 11:00", "2026-01-22 10:00", 0).is_err());
+    }
+
+    #[test]
+    fn test_build_series_and_overlay() {
+        let request = TelemetryRequest {
+            dataflows: vec![("a".to_string(), "camera".to_string()), ("b".to_string(), "lidar".to_string())],
+            node: None,
+            window: TimeWindow { start: 0, end: 600 },
+            points: 10,
+        };
+        assert_eq!(request.bucket_secs(), 60);
+        let samples = vec![
+            sample("a", None, CPU, 0, 10.0),
+            sample("a", None, CPU, 30, 20.0),
+            sample("a", None, CPU, 65, 40.0),
+            sample("a", Some("detector"), CPU, 65, 30.0),
+            sample("a", None, MEMORY, 65, 0.5),
+            sample("b", None, CPU, 5, 80.0),
+        ];
+        let data = TelemetryData::build(request.clone(), &samples);
+        assert_eq!(data.cpu.len(), 2);
+        assert_eq!(data.cpu[0].label, "camera");
+        assert_eq!(data.cpu[0].points, vec![(0, 15.0), (60, 40.0)]);
+        assert_eq!(data.cpu[1].points, vec![(0, 80.0)]);
+        assert_eq!(data.memory[0].points, vec![(60, 0.5)]);
+        assert!(data.memory[1].points.is_empty());
+        assert_eq!(data.nodes, vec!["detector"]);
+
+        let node = TelemetryData::build(
+            TelemetryRequest {
+                node: Some("detector".to_string()),
+                ..request
+            },
+            &samples,
+        );
+        assert_eq!(node.cpu[0].label, "camera / detector");
+        assert_eq!(node.cpu[0].points, vec![(60, 30.0)]);
+        assert!(!node.is_empty());
+    }
+}
@@ -0,0 +1,602 @@
+//! Background worker for everything the dataflow table asks of dora.
+//!
+//! Listing dataflows, checking the connection and running start/restart/stop/
+//! destroy/logs can each take seconds when the coordinator is slow, so the UI never does
+//! them itself. It sends [`PollerRequest`]s to a worker thread, which answers
+//! with [`PollerEvent`]s through the sink given to [`start`] (the app passes
+//! `Cx::post_action`). Refresh requests that pile up while the worker is busy
+//! are merged into one, and list and health events are only sent when they
+//! differ from the previous ones. Each refresh also checks the alert rules and
+//! tells the webhooks about failed dataflows and alerts.
+
+use crate::alerts::{history, webhook, AlertEngine, AlertEvent, AlertRule, Notification};
+use crate::client::{self, version, ConnectionHealth, StartRequest};
+use crate::dataflow::model::unix_now;
+use crate::dataflow::DataflowInfo;
+use crate::launches;
+use crate::logs::{system, SystemLogs};
+use crate::metrics::{self, process, store, NodeMetrics, RecentSamples};
+use crate::settings::CoordinatorEndpoint;
+use crate::tools;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Request channel to the worker thread, set once by `start`
+static SENDER: Mutex<Option<Sender<PollerRequest>>> = Mutex::new(None);
+
+/// Time between automatic refreshes
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time between refreshes while the log watcher reports lifecycle events
+pub const FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Time between refreshes while the node list is shown
+pub const NODE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Command run over several selected dataflows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatchCommand {
+    Stop,
+    Destroy,
+    Restart,
+}
+
+impl BatchCommand {
+    /// Past tense for summaries, e.g. "Stopped"
+    pub fn done_label(&self) -> &'static str {
+        match self {
+            BatchCommand::Stop => "Stopped",
+            BatchCommand::Destroy => "Destroyed",
+            BatchCommand::Restart => "Restarted",
+        }
+    }
+}
+
+/// Work for the poller thread
+#[derive(Debug, Clone)]
+pub enum PollerRequest {
+    /// List dataflows and check the connection now
+    Refresh,
+    /// Talk to another coordinator from now on
+    SetCoordinator(CoordinatorEndpoint),
+    Start(StartRequest),
+    /// Stop a dataflow and start its recorded YAML again
+    Restart(String),
+    Stop(String),
+    Destroy(String),
+    /// Run a command on each dataflow, one after the other
+    Batch(BatchCommand, Vec<String>),
+    Logs(String),
+    /// Logs of one node of a dataflow
+    NodeLogs {
+        dataflow_id: String,
+        node: String,
+    },
+    /// Coordinator and daemon logs
+    SystemLogs,
+    /// Send node process metrics on every refresh, and refresh more often, or stop
+    WatchNodes(bool),
+}
+
+/// Results delivered back to the UI
+#[derive(Debug, Clone)]
+pub enum PollerEvent {
+    /// The dataflow list changed, with the newest values of each dataflow's
+    /// series for the sparklines
+    Dataflows {
+        dataflows: Vec<DataflowInfo>,
+        recent: RecentSamples,
+    },
+    /// New samples were recorded for an unchanged dataflow list
+    Sparklines(RecentSamples),
+    /// Listing failed, with the error to show instead of the table
+    ListFailed(String),
+    /// Coordinator/daemon reachability changed
+    Health(ConnectionHealth),
+    /// CLI/coordinator version mismatch warning, `None` when they agree
+    VersionWarning(Option<String>),
+    /// A start finished, with the new dataflow's UUID on success
+    Started {
+        request: StartRequest,
+        result: Result<String, String>,
+    },
+    /// A restart finished, with the new UUID on success
+    Restarted {
+        dataflow_id: String,
+        result: Result<String, String>,
+    },
+    /// A stop or destroy finished
+    CommandFinished {
+        command: &'static str,
+        dataflow_id: String,
+        result: Result<String, String>,
+    },
+    /// A batch finished, with the result for each dataflow in request order
+    BatchFinished {
+        command: BatchCommand,
+        results: Vec<(String, Result<String, String>)>,
+    },
+    /// Logs fetched for a dataflow, or for one of its nodes
+    Logs {
+        dataflow_id: String,
+        node: Option<String>,
+        result: Result<String, String>,
+    },
+    /// Coordinator and daemon logs read
+    SystemLogs(Result<SystemLogs, String>),
+    /// Node processes sampled, while watched
+    NodeMetrics(Result<Vec<NodeMetrics>, String>),
+    /// Alerts fired or resolved, with all alerts firing now
+    Alerts {
+        events: Vec<AlertEvent>,
+        firing: Vec<AlertEvent>,
+    },
+}
+
+/// Start the poller thread. Later calls are ignored.
+pub fn start(sink: impl Fn(PollerEvent) + Send + Sync + 'static) {
+    let mut sender = SENDER.lock().unwrap();
+    if sender.is_some() {
+        return;
+    }
+    let (tx, rx) = channel();
+    *sender = Some(tx);
+    std::thread::spawn(move || run(rx, Worker::new(sink)));
+}
+
+/// Queue a request for the poller thread
+pub fn request(request: PollerRequest) {
+    match SENDER.lock().unwrap().as_ref() {
+        Some(sender) => {
+            let _ = sender.send(request);
+        }
+        None => eprintln!("[Poller] Not started, dropping {:?}", request),
+    }
+}
+
+/// Queue a refresh of the dataflow list
+pub fn refresh() {
+    request(PollerRequest::Refresh);
+}
+
+fn run(rx: Receiver<PollerRequest>, mut worker: Worker) {
+    worker.check_versions(&crate::settings::get().selected_endpoint());
+    worker.refresh();
+
+    loop {
+        let interval = if worker.watch_nodes {
+            NODE_POLL_INTERVAL
+        } else if crate::watcher::is_active() {
+            FALLBACK_POLL_INTERVAL
+        } else {
+            POLL_INTERVAL
+        };
+        let first = match rx.recv_timeout(interval) {
+            Ok(request) => Some(request),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        worker.handle(Batch::collect(first, &rx));
+    }
+}
+
+/// Requests taken from the channel in one go
+#[derive(Debug, Default)]
+struct Batch {
+    /// Whether to refresh once the commands ran
+    refresh: bool,
+    /// Everything except refreshes, in arrival order
+    commands: Vec<PollerRequest>,
+}
+
+impl Batch {
+    /// Merge `first` (`None` on the periodic timeout) with everything already queued
+    fn collect(first: Option<PollerRequest>, rx: &Receiver<PollerRequest>) -> Self {
+        let mut batch = Batch {
+            refresh: first.is_none(),
+            commands: Vec::new(),
+        };
+        for request in first.into_iter().chain(rx.try_iter()) {
+            match request {
+                PollerRequest::Refresh => batch.refresh = true,
+                PollerRequest::Logs(_) | PollerRequest::NodeLogs { .. } | PollerRequest::SystemLogs => {
+                    batch.commands.push(request)
+                }
+                // Anything that changes dataflows is followed by a refresh
+                other => {
+                    batch.refresh = true;
+                    batch.commands.push(other);
+                }
+            }
+        }
+        batch
+    }
+}
+
+struct Worker {
+    sink: Arc<dyn Fn(PollerEvent) + Send + Sync>,
+    last_list: Option<Result<Vec<DataflowInfo>, String>>,
+    last_health: Option<ConnectionHealth>,
+    last_warning: Option<Option<String>>,
+    /// Whether the node list is shown
+    watch_nodes: bool,
+    alerts: AlertEngine,
+    /// Alerts firing after the previous refresh
+    last_firing: Vec<AlertEvent>,
+    /// History file alerts are appended to
+    alerts_path: PathBuf,
+}
+
+impl Worker {
+    fn new(sink: impl Fn(PollerEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            last_list: None,
+            last_health: None,
+            last_warning: None,
+            watch_nodes: false,
+            alerts: AlertEngine::default(),
+            last_firing: Vec::new(),
+            alerts_path: history::alerts_path(),
+        }
+    }
+
+    fn handle(&mut self, batch: Batch) {
+        for command in batch.commands {
+            match command {
+                PollerRequest::SetCoordinator(endpoint) => {
+                    client::set_coordinator(&endpoint);
+                    self.check_versions(&endpoint);
+                    // The other coordinator's dataflows did not disappear or fail
+                    self.alerts = AlertEngine::default();
+                    self.last_list = None;
+                }
+                PollerRequest::Start(request) => self.start_dataflow(request),
+                PollerRequest::Restart(id) => {
+                    let result = launches::restart(&id);
+                    (self.sink)(PollerEvent::Restarted {
+                        dataflow_id: id,
+                        result,
+                    });
+                }
+                PollerRequest::Stop(id) => self.run_command("stop", stop_dataflow, id),
+                PollerRequest::Destroy(id) => self.run_command("destroy", destroy_dataflow, id),
+                PollerRequest::Batch(command, ids) => {
+                    let results = ids
+                        .into_iter()
+                        .map(|id| {
+                            let result = match command {
+                                BatchCommand::Stop => stop_dataflow(&id),
+                                BatchCommand::Destroy => destroy_dataflow(&id),
+                                BatchCommand::Restart => launches::restart(&id),
+                            };
+                            (id, result)
+                        })
+                        .collect();
+                    (self.sink)(PollerEvent::BatchFinished { command, results });
+                }
+                // Raw logs, the viewer parses them itself
+                PollerRequest::Logs(id) => {
+                    let result = tools::dataflow_logs(&id, None);
+                    (self.sink)(PollerEvent::Logs {
+                        dataflow_id: id,
+                        node: None,
+                        result,
+                    });
+                }
+                PollerRequest::NodeLogs { dataflow_id, node } => {
+                    let result = tools::dataflow_logs(&dataflow_id, Some(&node));
+                    (self.sink)(PollerEvent::Logs {
+                        dataflow_id,
+                        node: Some(node),
+                        result,
+                    });
+                }
+                PollerRequest::SystemLogs => {
+                    let result = system::read_system_logs(system::SYSTEM_TAIL_LINES);
+                    (self.sink)(PollerEvent::SystemLogs(result));
+                }
+                PollerRequest::WatchNodes(watch) => self.watch_nodes = watch,
+                PollerRequest::Refresh => {}
+            }
+        }
+        if batch.refresh {
+            self.refresh();
+        }
+    }
+
+    fn start_dataflow(&mut self, request: StartRequest) {
+        let result = launches::start(&request).map_err(|e| e.to_string());
+        (self.sink)(PollerEvent::Started { request, result });
+    }
+
+    fn run_command(
+        &mut self,
+        command: &'static str,
+        run: fn(&str) -> Result<String, String>,
+        dataflow_id: String,
+    ) {
+        let result = run(&dataflow_id);
+        (self.sink)(PollerEvent::CommandFinished {
+            command,
+            dataflow_id,
+            result,
+        });
+    }
+
+    fn refresh(&mut self) {
+        let health = client::check_health();
+        let list = match &health {
+            // Listing would only time out again
+            ConnectionHealth::CoordinatorUnreachable(e) => {
+                Err(format!("Coordinator not reachable: {}", e))
+            }
+            _ => client::with_client(|c| c.list_dataflows()).map_err(|e| e.to_string()),
+        };
+        let mut recent = RecentSamples::default();
+        if let Ok(dataflows) = &list {
+            store::record(&metrics::dataflow_samples(dataflows, unix_now()));
+            recent = store::recent_samples();
+            let nodes = self.sample_nodes(dataflows);
+            let settings = crate::settings::get();
+            let alerts = self.check_alerts(&settings.alert_rules, dataflows, &nodes);
+            let mut notifications = match &self.last_list {
+                Some(Ok(previous)) => webhook::failures(previous, dataflows, unix_now()),
+                _ => Vec::new(),
+            };
+            notifications.extend(alerts.into_iter().map(Notification::Alert));
+            webhook::notify(&settings.webhooks, notifications);
+        }
+        self.publish_health(health);
+        self.publish_list(list, recent);
+    }
+
+    /// Record each node's usage, and send it while the node list is shown
+    fn sample_nodes(&mut self, dataflows: &[DataflowInfo]) -> Vec<NodeMetrics> {
+        if !self.watch_nodes && !dataflows.iter().any(|df| df.is_running()) {
+            return Vec::new();
+        }
+        let nodes = process::sample_nodes();
+        if let Ok(nodes) = &nodes {
+            store::record(&process::node_samples(nodes, unix_now()));
+        }
+        if self.watch_nodes {
+            (self.sink)(PollerEvent::NodeMetrics(nodes.clone()));
+        }
+        nodes.unwrap_or_default()
+    }
+
+    /// Check the alert rules against this refresh, keep what fired or
+    /// resolved in the history and send it. Returns what fired or resolved.
+    fn check_alerts(
+        &mut self,
+        rules: &[AlertRule],
+        dataflows: &[DataflowInfo],
+        nodes: &[NodeMetrics],
+    ) -> Vec<AlertEvent> {
+        let events = self.alerts.evaluate(rules, dataflows, nodes, unix_now());
+        let firing = self.alerts.firing();
+        if events.is_empty() && firing == self.last_firing {
+            return events;
+        }
+        if !events.is_empty() {
+            if let Err(e) = history::append_to(&self.alerts_path, &events) {
+                eprintln!("[Poller] {}", e);
+            }
+        }
+        self.last_firing = firing.clone();
+        (self.sink)(PollerEvent::Alerts {
+            events: events.clone(),
+            firing,
+        });
+        events
+    }
+
+    fn check_versions(&mut self, endpoint: &CoordinatorEndpoint) {
+        let warning = version::check_versions(endpoint);
+        if self.last_warning.as_ref() != Some(&warning) {
+            self.last_warning = Some(warning.clone());
+            (self.sink)(PollerEvent::VersionWarning(warning));
+        }
+    }
+
+    fn publish_health(&mut self, health: ConnectionHealth) {
+        if self.last_health.as_ref() != Some(&health) {
+            self.last_health = Some(health.clone());
+            (self.sink)(PollerEvent::Health(health));
+        }
+    }
+
+    fn publish_list(&mut self, list: Result<Vec<DataflowInfo>, String>, recent: RecentSamples) {
+        if self.last_list.as_ref() == Some(&list) {
+            // Usage was recorded all the same, keep the sparklines moving
+            if list.is_ok() {
+                (self.sink)(PollerEvent::Sparklines(recent));
+            }
+            return;
+        }
+        self.last_list = Some(list.clone());
+        (self.sink)(match list {
+            Ok(dataflows) => PollerEvent::Dataflows { dataflows, recent },
+            Err(e) => PollerEvent::ListFailed(e),
+        });
+    }
+}
+
+/// One-line summary of a batch, naming the dataflows that failed
+pub fn batch_summary(command: BatchCommand, results: &[(String, Result<String, String>)]) -> String {
+    let failures: Vec<_> = results
+        .iter()
+        .filter_map(|(id, result)| result.as_ref().err().map(|e| (id, e)))
+        .collect();
+    let mut summary = format!(
+        "{} {} of {} dataflows",
+        command.done_label(),
+        results.len() - failures.len(),
+        results.len()
+    );
+    if !failures.is_empty() {
+        summary.push_str("; failed: ");
+        let failed: Vec<_> = failures
+            .iter()
+            .map(|(id, e)| format!("{} ({})", &id[..8.min(id.len())], e))
+            .collect();
+        summary.push_str(&failed.join(", "));
+    }
+    summary
+}
+
+fn stop_dataflow(dataflow_id: &str) -> Result<String, String> {
+    client::with_client(|c| c.stop_dataflow(dataflow_id))
+        .map(|()| format!("Stopped dataflow {}", dataflow_id))
+        .map_err(|e| e.to_string())
+}
+
+fn destroy_dataflow(dataflow_id: &str) -> Result<String, String> {
+    client::with_client(|c| c.destroy_dataflow(dataflow_id))
+        .map(|()| format!("Destroyed dataflow {}", dataflow_id))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording_worker() -> (Worker, Arc<Mutex<Vec<PollerEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink_events = events.clone();
+        let worker = Worker::new(move |event| sink_events.lock().unwrap().push(event));
+        (worker, events)
+    }
+
+    #[test]
+    fn test_batch_coalesces_refreshes() {
+        let (tx, rx) = channel();
+        tx.send(PollerRequest::Refresh).unwrap();
+        tx.send(PollerRequest::Refresh).unwrap();
+        tx.send(PollerRequest::Logs("a".to_string())).unwrap();
+
+        let batch = Batch::collect(Some(PollerRequest::Refresh), &rx);
+        assert!(batch.refresh);
+        assert_eq!(batch.commands.len(), 1);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_batch_refreshes_after_commands() {
+        let (tx, rx) = channel();
+        tx.send(PollerRequest::Destroy("b".to_string())).unwrap();
+
+        let batch = Batch::collect(Some(PollerRequest::Stop("a".to_string())), &rx);
+        assert!(batch.refresh);
+        assert!(matches!(&batch.commands[..], [PollerRequest::Stop(a), PollerRequest::Destroy(b)] if a == "a" && b == "b"));
+
+        let logs_only = Batch::collect(Some(PollerRequest::Logs("a".to_string())), &rx);
+        assert!(!logs_only.refresh);
+
+        let start = Batch::collect(Some(PollerRequest::Start(StartRequest::new("flow.yml"))), &rx);
+        assert!(start.refresh);
+    }
+
+    #[test]
+    fn test_batch_summary() {
+        let results = vec![
+            ("019be55a-8468-7d4b-9e95-db7938985bc7".to_string(), Ok("Stopped".to_string())),
+            ("019be55b-0000-7d4b-9e95-db7938985bc7".to_string(), Err("not running".to_string())),
+            ("019be55c-0000-7d4b-9e95-db7938985bc7".to_string(), Ok("Stopped".to_string())),
+        ];
+        assert_eq!(
+            batch_summary(BatchCommand::Stop, &results),
+            "Stopped 2 of 3 dataflows; failed: 019be55b (not running)"
+        );
+        assert_eq!(
+            batch_summary(BatchCommand::Destroy, &results[..1]),
+            "Destroyed 1 of 1 dataflows"
+        );
+    }
+
+    #[test]
+    fn test_batch_timeout_refreshes() {
+        let (_tx, rx) = channel();
+        let batch = Batch::collect(None, &rx);
+        assert!(batch.refresh);
+        assert!(batch.commands.is_empty());
+    }
+
+    #[test]
+    fn test_list_only_published_on_change() {
+        let (mut worker, events) = recording_worker();
+        let flows = vec![DataflowInfo {
+            uuid: "a".to_string(),
+            ..Default::default()
+        }];
+
+        worker.publish_list(Ok(flows.clone()), RecentSamples::default());
+        worker.publish_list(Ok(flows.clone()), RecentSamples::default());
+        {
+            // Only the sparklines move on
+            let events = events.lock().unwrap();
+            assert_eq!(events.len(), 2);
+            assert!(matches!(events[1], PollerEvent::Sparklines(_)));
+        }
+
+        worker.publish_list(Err("down".to_string()), RecentSamples::default());
+        worker.publish_list(Err("down".to_string()), RecentSamples::default());
+        worker.publish_list(Ok(flows), RecentSamples::default());
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 4);
+        assert!(matches!(events[2], PollerEvent::ListFailed(_)));
+        assert!(matches!(events[3], PollerEvent::Dataflows { .. }));
+    }
+
+    #[test]
+    fn test_node_metrics_sent_while_watched() {
+        let (mut worker, events) = recording_worker();
+        // Nothing running and nobody watching: no need to walk /proc
+        worker.sample_nodes(&[]);
+        assert!(events.lock().unwrap().is_empty());
+
+        worker.handle(Batch {
+            refresh: false,
+            commands: vec![PollerRequest::WatchNodes(true)],
+        });
+        worker.sample_nodes(&[]);
+        assert!(matches!(events.lock().unwrap()[..], [PollerEvent::NodeMetrics(_)]));
+        assert!(Batch::collect(Some(PollerRequest::WatchNodes(false)), &channel().1).refresh);
+    }
+
+    #[test]
+    fn test_alerts_sent_and_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut worker, events) = recording_worker();
+        worker.alerts_path = dir.path().join("alerts.jsonl");
+        let rules = vec![AlertRule::parse("status becomes failed").unwrap()];
+        let failed = vec![DataflowInfo {
+            uuid: "a".to_string(),
+            name: "camera".to_string(),
+            status: crate::dataflow::DataflowStatus::Failed,
+            ..Default::default()
+        }];
+
+        worker.check_alerts(&rules, &failed, &[]);
+        worker.check_alerts(&rules, &failed, &[]);
+        worker.check_alerts(&rules, &[], &[]);
+        let events = events.lock().unwrap();
+        assert!(matches!(&events[..], [
+            PollerEvent::Alerts { events: fired, firing },
+            PollerEvent::Alerts { events: resolved, firing: now_firing },
+        ] if fired.len() == 1 && firing.len() == 1 && resolved.len() == 1 && now_firing.is_empty()));
+        assert_eq!(history::load_from(&worker.alerts_path, 10).len(), 2);
+    }
+
+    #[test]
+    fn test_health_only_published_on_change() {
+        let (mut worker, events) = recording_worker();
+        worker.publish_health(ConnectionHealth::Connected);
+        worker.publish_health(ConnectionHealth::Connected);
+        worker.publish_health(ConnectionHealth::NoDaemon);
+        assert_eq!(events.lock().unwrap().len(), 2);
+    }
+}
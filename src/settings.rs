@@ -0,0 +1,343 @@
+//! Persistent user settings.
+//!
+//! Settings are stored as JSON in `~/.dora/studio/settings.json`
+//! (`$DORA_STUDIO_HOME/settings.json` when that variable is set). A missing
+//! file falls back to defaults so a fresh install just works. A malformed
+//! field falls back to its default alone, and a file that does not parse is
+//! copied to `settings.json.bak` before it is first saved over.
+
+use crate::alerts::{AlertRule, Webhook};
+use crate::dataflow::table_view::TableView;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Cached settings, loaded on first access
+static SETTINGS: Mutex<Option<Settings>> = Mutex::new(None);
+
+/// Number of recently started dataflow files to remember
+pub const MAX_RECENT_DATAFLOWS: usize = 10;
+
+/// A named dora coordinator reachable over its control port
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoordinatorEndpoint {
+    pub name: String,
+    pub host: String,
+    /// Control port Studio and the CLI talk to
+    pub port: u16,
+    /// Port daemons connect to, used when Studio starts dora itself
+    #[serde(default = "default_daemon_port")]
+    pub daemon_port: u16,
+}
+
+fn default_daemon_port() -> u16 {
+    crate::client::protocol::DEFAULT_DAEMON_PORT
+}
+
+impl CoordinatorEndpoint {
+    /// The coordinator dora starts by default on this machine
+    pub fn local() -> Self {
+        Self {
+            name: "local".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: crate::client::protocol::DEFAULT_CONTROL_PORT,
+            daemon_port: default_daemon_port(),
+        }
+    }
+
+    /// `host:port` address of the control channel
+    pub fn addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    /// Label shown in the coordinator switcher
+    pub fn label(&self) -> String {
+        format!("{} ({})", self.name, self.addr())
+    }
+}
+
+/// All persisted settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub coordinators: Vec<CoordinatorEndpoint>,
+    pub selected_coordinator: usize,
+    /// Refresh the table on lifecycle events from coordinator/daemon logs
+    pub watch_logs: bool,
+    /// Coordinator/daemon JSON log files to watch
+    pub log_files: Vec<PathBuf>,
+    /// Dataflow YAML files started from Studio, most recent first
+    pub recent_dataflows: Vec<PathBuf>,
+    /// Sort order and filters of the dataflow table
+    pub table_view: TableView,
+    /// Days of metrics history kept
+    pub metrics_retention_days: u64,
+    /// Size the metrics history may grow to, in MB
+    pub metrics_max_mb: u64,
+    /// Alerts checked on every refresh
+    pub alert_rules: Vec<AlertRule>,
+    /// Endpoints told about failed dataflows and alerts
+    pub webhooks: Vec<Webhook>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            coordinators: vec![CoordinatorEndpoint::local()],
+            selected_coordinator: 0,
+            watch_logs: true,
+            log_files: Vec::new(),
+            recent_dataflows: Vec::new(),
+            table_view: TableView::default(),
+            metrics_retention_days: 7,
+            metrics_max_mb: 256,
+            alert_rules: Vec::new(),
+            webhooks: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Load settings from `path`, falling back to defaults for the fields
+    /// that are missing or malformed
+    pub fn load_from(path: &Path) -> Self {
+        let mut settings = match std::fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content, path),
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    eprintln!("[Settings] Failed to read {}: {}", path.display(), e);
+                }
+                Settings::default()
+            }
+        };
+        if settings.coordinators.is_empty() {
+            settings.coordinators.push(CoordinatorEndpoint::local());
+        }
+        if settings.selected_coordinator >= settings.coordinators.len() {
+            settings.selected_coordinator = 0;
+        }
+        settings
+    }
+
+    /// Parse settings one field at a time, so a malformed field only resets
+    /// itself instead of the whole file
+    fn parse(content: &str, path: &Path) -> Self {
+        let value: serde_json::Value = match serde_json::from_str(content) {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("[Settings] Failed to parse {}: {}", path.display(), e);
+                return Settings::default();
+            }
+        };
+        if let Ok(settings) = serde_json::from_value(value.clone()) {
+            return settings;
+        }
+        let serde_json::Value::Object(fields) = value else {
+            eprintln!("[Settings] Failed to parse {}: not a JSON object", path.display());
+            return Settings::default();
+        };
+        let mut merged = serde_json::to_value(Settings::default()).unwrap_or_default();
+        for (key, field) in fields {
+            let mut candidate = merged.clone();
+            candidate[key.as_str()] = field;
+            match serde_json::from_value::<Settings>(candidate.clone()) {
+                Ok(_) => merged = candidate,
+                Err(e) => eprintln!("[Settings] Ignoring `{}` in {}: {}", key, path.display(), e),
+            }
+        }
+        serde_json::from_value(merged).unwrap_or_default()
+    }
+
+    /// Write settings to `path`, creating parent directories. A file at
+    /// `path` that does not parse is copied to `<path>.bak` first.
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        if let Ok(content) = std::fs::read_to_string(path) {
+            if serde_json::from_str::<Settings>(&content).is_err() {
+                let backup = backup_path(path);
+                std::fs::write(&backup, content)
+                    .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+                eprintln!("[Settings] Kept unreadable {} as {}", path.display(), backup.display());
+            }
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// The coordinator all operations currently target
+    pub fn selected_endpoint(&self) -> CoordinatorEndpoint {
+        self.coordinators
+            .get(self.selected_coordinator)
+            .cloned()
+            .unwrap_or_else(CoordinatorEndpoint::local)
+    }
+
+    /// Move `path` to the front of the recent dataflows
+    pub fn remember_recent(&mut self, path: &Path) {
+        self.recent_dataflows.retain(|p| p != path);
+        self.recent_dataflows.insert(0, path.to_path_buf());
+        self.recent_dataflows.truncate(MAX_RECENT_DATAFLOWS);
+    }
+}
+
+/// Directory holding Studio's persistent state
+pub fn studio_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("DORA_STUDIO_HOME") {
+        return PathBuf::from(dir);
+    }
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".dora").join("studio")
+}
+
+/// `settings.json.bak` next to `settings.json`
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Path of the settings file
+pub fn settings_path() -> PathBuf {
+    studio_dir().join("settings.json")
+}
+
+/// Get a copy of the current settings
+pub fn get() -> Settings {
+    SETTINGS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| Settings::load_from(&settings_path()))
+        .clone()
+}
+
+/// Modify the settings and persist them
+pub fn update(f: impl FnOnce(&mut Settings)) -> Result<(), String> {
+    let mut settings = SETTINGS.lock().unwrap();
+    let settings = settings.get_or_insert_with(|| Settings::load_from(&settings_path()));
+    f(settings);
+    settings.save_to(&settings_path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::WebhookFormat;
+
+    #[test]
+    fn test_default_settings() {
+        let settings = Settings::default();
+        assert_eq!(settings.coordinators.len(), 1);
+        assert_eq!(settings.selected_endpoint().addr(), "127.0.0.1:6012");
+    }
+
+    #[test]
+    fn test_settings_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("settings.json");
+
+        let settings = Settings {
+            coordinators: vec![
+                CoordinatorEndpoint::local(),
+                CoordinatorEndpoint {
+                    name: "rig-2".to_string(),
+                    host: "192.168.1.208".to_string(),
+                    port: 6013,
+                    daemon_port: 53291,
+                },
+            ],
+            selected_coordinator: 1,
+            log_files: vec![PathBuf::from("/var/log/dora/coordinator.json")],
+            table_view: TableView {
+                filter: "camera".to_string(),
+                ..Default::default()
+            },
+            alert_rules: vec![AlertRule::parse("camera: memory > 2 GB for 60s").unwrap()],
+            webhooks: vec![Webhook {
+                url: "https://hooks.slack.com/services/T000/B000/XXXX".to_string(),
+                format: WebhookFormat::Slack,
+                template: "{{message}}".to_string(),
+            }],
+            ..Default::default()
+        };
+        settings.save_to(&path).unwrap();
+
+        let loaded = Settings::load_from(&path);
+        assert_eq!(loaded, settings);
+        assert_eq!(loaded.selected_endpoint().label(), "rig-2 (192.168.1.208:6013)");
+    }
+
+    #[test]
+    fn test_load_repairs_invalid_selection() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, r#"{"coordinators":[],"selected_coordinator":5}"#).unwrap();
+
+        let loaded = Settings::load_from(&path);
+        assert_eq!(loaded.coordinators, vec![CoordinatorEndpoint::local()]);
+        assert_eq!(loaded.selected_coordinator, 0);
+    }
+
+    #[test]
+    fn test_remember_recent() {
+        let mut settings = Settings::default();
+        for i in 0..MAX_RECENT_DATAFLOWS + 2 {
+            settings.remember_recent(Path::new(&format!("/flows/{}.yml", i)));
+        }
+        settings.remember_recent(Path::new("/flows/5.yml"));
+
+        assert_eq!(settings.recent_dataflows.len(), MAX_RECENT_DATAFLOWS);
+        assert_eq!(settings.recent_dataflows[0], PathBuf::from("/flows/5.yml"));
+        assert_eq!(settings.recent_dataflows[1], PathBuf::from("/flows/11.yml"));
+        assert_eq!(settings.recent_dataflows.iter().filter(|p| p.ends_with("5.yml")).count(), 1);
+    }
+
+    #[test]
+    fn test_malformed_field_keeps_the_others() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let content = r#"{
+            "coordinators": [{"name": "rig-2", "host": "192.168.1.208", "port": 6013}],
+            "watch_logs": false,
+            "metrics_retention_days": "a week",
+            "alert_rules": [{"condition": {"kind": "bogus"}}]
+        }"#;
+        std::fs::write(&path, content).unwrap();
+
+        let loaded = Settings::load_from(&path);
+        assert_eq!(loaded.coordinators[0].name, "rig-2");
+        assert!(!loaded.watch_logs);
+        assert_eq!(loaded.metrics_retention_days, 7);
+        assert!(loaded.alert_rules.is_empty());
+
+        // The original survives the first save over it
+        loaded.save_to(&path).unwrap();
+        let backup = dir.path().join("settings.json.bak");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), content);
+        assert_eq!(Settings::load_from(&path), loaded);
+    }
+
+    #[test]
+    fn test_unparsable_file_backed_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(Settings::load_from(&path), Settings::default());
+
+        Settings::default().save_to(&path).unwrap();
+        Settings::default().save_to(&path).unwrap();
+        let backup = dir.path().join("settings.json.bak");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "{ not json");
+    }
+
+    #[test]
+    fn test_load_missing_file_uses_defaults() {
+        let loaded = Settings::load_from(Path::new("/nonexistent/settings.json"));
+        assert_eq!(loaded, Settings::default());
+    }
+}
@@ -0,0 +1,432 @@
+//! Supervision of a local dora coordinator and daemon.
+//!
+//! Studio can bring up `dora coordinator` and `dora daemon` itself, the way
+//! `dora up` does, but as child processes so their output can be captured and
+//! crashes noticed. Captured lines are kept in a bounded buffer for the UI and
+//! the agent tools.
+
+use crate::client::{self, ConnectionHealth};
+use crate::settings::CoordinatorEndpoint;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Maximum number of captured output lines kept in memory
+const MAX_OUTPUT_LINES: usize = 5000;
+/// How often a crashed process is restarted before giving up
+const MAX_RESTARTS: u32 = 3;
+/// How long to wait for the coordinator and daemon to come up
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+/// Interval between liveness checks of the supervised processes
+const MONITOR_INTERVAL: Duration = Duration::from_secs(1);
+
+// Global state
+static SUPERVISOR: Mutex<Option<Supervisor>> = Mutex::new(None);
+static OUTPUT: Mutex<VecDeque<OutputLine>> = Mutex::new(VecDeque::new());
+
+/// A dora process Studio can supervise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessKind {
+    Coordinator,
+    Daemon,
+}
+
+impl ProcessKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProcessKind::Coordinator => "coordinator",
+            ProcessKind::Daemon => "daemon",
+        }
+    }
+
+    fn args(&self, endpoint: &CoordinatorEndpoint) -> std::io::Result<Vec<String>> {
+        Ok(match self {
+            ProcessKind::Coordinator => vec![
+                "coordinator".to_string(),
+                "--port".to_string(),
+                endpoint.daemon_port.to_string(),
+                "--control-port".to_string(),
+                endpoint.port.to_string(),
+            ],
+            ProcessKind::Daemon => vec![
+                "daemon".to_string(),
+                "--coordinator-addr".to_string(),
+                coordinator_ip(endpoint)?.to_string(),
+                "--coordinator-port".to_string(),
+                endpoint.daemon_port.to_string(),
+            ],
+        })
+    }
+}
+
+/// Address the daemon connects to. dora only takes an IP address, so host
+/// names like `localhost` are resolved, preferring IPv4.
+fn coordinator_ip(endpoint: &CoordinatorEndpoint) -> std::io::Result<IpAddr> {
+    if endpoint.host == "0.0.0.0" {
+        return Ok(Ipv4Addr::LOCALHOST.into());
+    }
+    let addrs: Vec<SocketAddr> = (endpoint.host.as_str(), endpoint.daemon_port).to_socket_addrs()?.collect();
+    addrs
+        .iter()
+        .find(|addr| addr.is_ipv4())
+        .or(addrs.first())
+        .map(|addr| addr.ip())
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} does not resolve to an address", endpoint.host),
+            )
+        })
+}
+
+/// One line of captured stdout/stderr
+#[derive(Debug, Clone)]
+pub struct OutputLine {
+    pub source: ProcessKind,
+    pub line: String,
+}
+
+struct Supervised {
+    kind: ProcessKind,
+    child: Child,
+    restarts: u32,
+}
+
+struct Supervisor {
+    endpoint: CoordinatorEndpoint,
+    processes: Vec<Supervised>,
+}
+
+/// Whether Studio can start dora for this endpoint (only on this machine)
+pub fn is_local(endpoint: &CoordinatorEndpoint) -> bool {
+    matches!(endpoint.host.as_str(), "127.0.0.1" | "localhost" | "::1" | "0.0.0.0")
+}
+
+/// Whether Studio currently runs a coordinator or daemon itself
+pub fn is_supervising() -> bool {
+    SUPERVISOR
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|s| !s.processes.is_empty())
+}
+
+/// Whether Studio currently runs the coordinator or daemon of `endpoint`
+pub fn supervises(endpoint: &CoordinatorEndpoint) -> bool {
+    SUPERVISOR
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|s| !s.processes.is_empty() && s.endpoint.addr() == endpoint.addr())
+}
+
+/// Start a local coordinator and daemon for `endpoint`, skipping what is already running
+pub fn up(endpoint: &CoordinatorEndpoint) -> Result<String, String> {
+    if !is_local(endpoint) {
+        return Err(format!(
+            "{} is not on this machine, start dora there instead",
+            endpoint.label()
+        ));
+    }
+
+    let mut probe = client::client_for(endpoint);
+    let mut started = Vec::new();
+    match client::health_of(probe.as_mut()) {
+        ConnectionHealth::Connected => {
+            return Ok(format!("dora is already running at {}", endpoint.addr()))
+        }
+        ConnectionHealth::NoDaemon => {}
+        ConnectionHealth::CoordinatorUnreachable(_) => {
+            spawn_supervised(ProcessKind::Coordinator, endpoint)?;
+            wait_until(|| TcpStream::connect(endpoint.addr()).is_ok()).map_err(|_| {
+                format!(
+                    "coordinator did not start listening on {}\n{}",
+                    endpoint.addr(),
+                    output_tail(20)
+                )
+            })?;
+            started.push("coordinator");
+        }
+    }
+
+    spawn_supervised(ProcessKind::Daemon, endpoint)?;
+    wait_until(|| client::health_of(probe.as_mut()) == ConnectionHealth::Connected).map_err(|_| {
+        format!(
+            "daemon did not connect to {}\n{}",
+            endpoint.addr(),
+            output_tail(20)
+        )
+    })?;
+    started.push("daemon");
+
+    Ok(format!("Started local dora {} at {}", started.join(" and "), endpoint.addr()))
+}
+
+/// Destroy the coordinator (which stops all daemons and dataflows) and reap supervised processes.
+///
+/// Targets the supervised coordinator if there is one, the selected one
+/// otherwise, but only if that runs on this machine.
+pub fn down() -> Result<String, String> {
+    let selected = crate::settings::get().selected_endpoint();
+    let (endpoint, supervisor) = {
+        let mut supervisor = SUPERVISOR.lock().unwrap();
+        let endpoint = down_target(supervisor.as_ref().map(|s| &s.endpoint), selected)?;
+        (endpoint, supervisor.take())
+    };
+    let destroyed = client::client_for(&endpoint).destroy();
+
+    let mut killed = 0;
+    if let Some(mut supervisor) = supervisor {
+        for process in &mut supervisor.processes {
+            // Give the processes a moment to exit after the destroy command
+            let deadline = Instant::now() + Duration::from_secs(3);
+            while Instant::now() < deadline {
+                if let Ok(Some(_)) = process.child.try_wait() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            if let Ok(None) = process.child.try_wait() {
+                let _ = process.child.kill();
+                let _ = process.child.wait();
+                killed += 1;
+            }
+        }
+    }
+
+    match destroyed {
+        Ok(()) if killed == 0 => Ok("Destroyed dora coordinator and daemons".to_string()),
+        Ok(()) => Ok(format!(
+            "Destroyed dora coordinator and daemons ({} process(es) had to be killed)",
+            killed
+        )),
+        Err(e) if killed > 0 => Ok(format!("Killed {} supervised process(es) ({})", killed, e)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// The coordinator `down` destroys: the supervised one, or the selected one
+/// if it is on this machine. Remote coordinators are left alone.
+fn down_target(
+    supervised: Option<&CoordinatorEndpoint>,
+    selected: CoordinatorEndpoint,
+) -> Result<CoordinatorEndpoint, String> {
+    match supervised {
+        Some(endpoint) => Ok(endpoint.clone()),
+        None if is_local(&selected) => Ok(selected),
+        None => Err(format!(
+            "{} is not managed by Studio, stop dora on its own machine instead",
+            selected.label()
+        )),
+    }
+}
+
+/// Human readable summary of the connection and supervised processes
+pub fn status() -> String {
+    let endpoint = crate::settings::get().selected_endpoint();
+    let health = match client::check_health() {
+        ConnectionHealth::Connected => "coordinator and daemon connected".to_string(),
+        ConnectionHealth::NoDaemon => "coordinator running, no daemon connected".to_string(),
+        ConnectionHealth::CoordinatorUnreachable(e) => format!("coordinator unreachable ({})", e),
+    };
+
+    let mut lines = vec![format!("{}: {}", endpoint.label(), health)];
+    if let Some(supervisor) = SUPERVISOR.lock().unwrap().as_ref() {
+        for process in &supervisor.processes {
+            lines.push(format!(
+                "supervised {} (pid {}, {} restart(s))",
+                process.kind.name(),
+                process.child.id(),
+                process.restarts
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// The most recent captured output lines, oldest first
+pub fn recent_output(limit: usize) -> Vec<OutputLine> {
+    let output = OUTPUT.lock().unwrap();
+    output.iter().skip(output.len().saturating_sub(limit)).cloned().collect()
+}
+
+fn output_tail(limit: usize) -> String {
+    recent_output(limit)
+        .iter()
+        .map(|l| format!("[{}] {}", l.source.name(), l.line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn push_output(source: ProcessKind, line: String) {
+    let mut output = OUTPUT.lock().unwrap();
+    if output.len() == MAX_OUTPUT_LINES {
+        output.pop_front();
+    }
+    output.push_back(OutputLine { source, line });
+}
+
+fn spawn_supervised(kind: ProcessKind, endpoint: &CoordinatorEndpoint) -> Result<(), String> {
+    let child = spawn(kind, endpoint).map_err(|e| format!("Failed to start dora {}: {}", kind.name(), e))?;
+
+    let mut supervisor = SUPERVISOR.lock().unwrap();
+    let start_monitor = supervisor.is_none();
+    let supervisor = supervisor.get_or_insert_with(|| Supervisor {
+        endpoint: endpoint.clone(),
+        processes: Vec::new(),
+    });
+    supervisor.processes.push(Supervised {
+        kind,
+        child,
+        restarts: 0,
+    });
+
+    if start_monitor {
+        std::thread::spawn(monitor);
+    }
+    Ok(())
+}
+
+fn spawn(kind: ProcessKind, endpoint: &CoordinatorEndpoint) -> std::io::Result<Child> {
+    let mut child = Command::new("dora")
+        .args(kind.args(endpoint)?)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(stdout) = child.stdout.take() {
+        capture(kind, stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        capture(kind, stderr);
+    }
+    push_output(kind, format!("started with pid {}", child.id()));
+    Ok(child)
+}
+
+fn capture(kind: ProcessKind, stream: impl Read + Send + 'static) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if crate::watcher::is_watching() {
+                crate::watcher::observe_line(&line);
+            }
+            push_output(kind, line);
+        }
+    });
+}
+
+/// Restart supervised processes that exit unexpectedly, until `down` is called
+fn monitor() {
+    loop {
+        std::thread::sleep(MONITOR_INTERVAL);
+
+        let mut supervisor = SUPERVISOR.lock().unwrap();
+        let Some(supervisor) = supervisor.as_mut() else {
+            return;
+        };
+
+        let endpoint = supervisor.endpoint.clone();
+        supervisor.processes.retain_mut(|process| {
+            let Ok(Some(status)) = process.child.try_wait() else {
+                return true;
+            };
+            push_output(process.kind, format!("exited with {}", status));
+
+            if process.restarts >= MAX_RESTARTS {
+                push_output(process.kind, "giving up after repeated crashes".to_string());
+                return false;
+            }
+            match spawn(process.kind, &endpoint) {
+                Ok(child) => {
+                    process.child = child;
+                    process.restarts += 1;
+                    true
+                }
+                Err(e) => {
+                    push_output(process.kind, format!("restart failed: {}", e));
+                    false
+                }
+            }
+        });
+    }
+}
+
+fn wait_until(mut ready: impl FnMut() -> bool) -> Result<(), ()> {
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    while Instant::now() < deadline {
+        if ready() {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    Err(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(host: &str) -> CoordinatorEndpoint {
+        CoordinatorEndpoint {
+            name: "test".to_string(),
+            host: host.to_string(),
+            port: 6012,
+            daemon_port: 53290,
+        }
+    }
+
+    #[test]
+    fn test_is_local() {
+        assert!(is_local(&endpoint("127.0.0.1")));
+        assert!(is_local(&endpoint("localhost")));
+        assert!(!is_local(&endpoint("192.168.1.208")));
+    }
+
+    #[test]
+    fn test_up_refuses_remote_endpoint() {
+        let result = up(&endpoint("192.168.1.208"));
+        assert!(result.unwrap_err().contains("not on this machine"));
+    }
+
+    #[test]
+    fn test_down_leaves_remote_coordinators_alone() {
+        let remote = endpoint("192.168.1.208");
+        assert!(down_target(None, remote.clone()).unwrap_err().contains("not managed by Studio"));
+        assert_eq!(down_target(None, endpoint("localhost")).unwrap(), endpoint("localhost"));
+
+        let supervised = endpoint("127.0.0.1");
+        assert_eq!(down_target(Some(&supervised), remote).unwrap(), supervised);
+    }
+
+    #[test]
+    fn test_process_args() {
+        let ep = CoordinatorEndpoint {
+            port: 6013,
+            daemon_port: 53291,
+            ..endpoint("localhost")
+        };
+        assert_eq!(
+            ProcessKind::Coordinator.args(&ep).unwrap(),
+            vec!["coordinator", "--port", "53291", "--control-port", "6013"]
+        );
+        assert_eq!(
+            ProcessKind::Daemon.args(&ep).unwrap(),
+            vec!["daemon", "--coordinator-addr", "127.0.0.1", "--coordinator-port", "53291"]
+        );
+        assert_eq!(coordinator_ip(&endpoint("0.0.0.0")).unwrap().to_string(), "127.0.0.1");
+    }
+
+    #[test]
+    fn test_output_buffer_is_bounded() {
+        for i in 0..MAX_OUTPUT_LINES + 10 {
+            push_output(ProcessKind::Daemon, format!("line {}", i));
+        }
+        let output = recent_output(usize::MAX);
+        assert_eq!(output.len(), MAX_OUTPUT_LINES);
+        assert_eq!(output.last().unwrap().line, format!("line {}", MAX_OUTPUT_LINES + 9));
+    }
+}
@@ -1,4 +1,13 @@
+use crate::client::{version, with_client, CliClient, ClientError, DoraClient, StartRequest};
+use crate::dataflow::model::format_utc;
+use crate::logs::export::{self, ExportFormat, TimeRange};
+use crate::logs::system::{self, SystemLogs};
+use crate::logs::{merge_by_timestamp, patterns, short_id, LevelHistogram, LogFilter, LogLevel, LogLine, LogSearch};
+use crate::metrics::process::{self, NodeMetrics, NodeSort};
+use crate::{launches, settings, supervisor};
 use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
 use std::process::Command;
 
 /// Tool definition for Claude API
@@ -38,6 +47,15 @@ pub fn get_dora_tools() -> Vec<ToolDefinition> {
                     "dataflow_path": {
                         "type": "string",
                         "description": "Path to the dataflow YAML file to start"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Optional name for the dataflow"
+                    },
+                    "env": {
+                        "type": "object",
+                        "description": "Optional environment variables set on every node, overriding the YAML",
+                        "additionalProperties": { "type": "string" }
                     }
                 },
                 "required": ["dataflow_path"]
@@ -57,6 +75,20 @@ pub fn get_dora_tools() -> Vec<ToolDefinition> {
                 "required": ["dataflow_id"]
             }),
         },
+        ToolDefinition {
+            name: "restart_dataflow".to_string(),
+            description: "Restart a dataflow that was started from Studio: stops it if it is still running and starts the same YAML again, with the same name and environment overrides. Returns the new UUID.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dataflow_id": {
+                        "type": "string",
+                        "description": "UUID or name of the dataflow to restart"
+                    }
+                },
+                "required": ["dataflow_id"]
+            }),
+        },
         ToolDefinition {
             name: "dora_destroy".to_string(),
             description: "Destroy (forcefully stop) a dataflow and clean up all resources.".to_string(),
@@ -73,7 +105,7 @@ pub fn get_dora_tools() -> Vec<ToolDefinition> {
         },
         ToolDefinition {
             name: "dora_logs".to_string(),
-            description: "Get logs from a running dataflow. Optionally filter by node name.".to_string(),
+            description: "Get logs from a running dataflow, or from several merged by time. Optionally filter by node name, minimum level or text.".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -81,12 +113,216 @@ pub fn get_dora_tools() -> Vec<ToolDefinition> {
                         "type": "string",
                         "description": "UUID or name of the dataflow"
                     },
+                    "dataflow_ids": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Optional: several dataflows whose logs are interleaved by timestamp, instead of dataflow_id"
+                    },
                     "node": {
                         "type": "string",
                         "description": "Optional: filter logs by node name"
+                    },
+                    "level": {
+                        "type": "string",
+                        "enum": ["trace", "debug", "info", "warn", "error"],
+                        "description": "Optional: only lines at least this severe"
+                    },
+                    "contains": {
+                        "type": "string",
+                        "description": "Optional: only lines whose message, node, target or fields contain this text (case-insensitive)"
+                    },
+                    "regex": {
+                        "type": "boolean",
+                        "description": "Optional: treat contains as a regular expression"
                     }
                 },
-                "required": ["dataflow_id"]
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "export_logs".to_string(),
+            description: "Save the logs of one or more dataflows to a file as JSONL, CSV or plain text, with the same filters as dora_logs plus a time range. Refuses to replace an existing file unless overwrite is set.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Absolute path of the file to write, ~/ is expanded. Its folder must exist."
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["jsonl", "csv", "text"],
+                        "description": "Optional: file format, taken from the path's extension when omitted (default jsonl)"
+                    },
+                    "dataflow_id": {
+                        "type": "string",
+                        "description": "UUID or name of the dataflow"
+                    },
+                    "dataflow_ids": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Optional: several dataflows whose logs are interleaved by timestamp, instead of dataflow_id"
+                    },
+                    "node": {
+                        "type": "string",
+                        "description": "Optional: only logs of this node"
+                    },
+                    "level": {
+                        "type": "string",
+                        "enum": ["trace", "debug", "info", "warn", "error"],
+                        "description": "Optional: only lines at least this severe"
+                    },
+                    "contains": {
+                        "type": "string",
+                        "description": "Optional: only lines whose message, node, target or fields contain this text (case-insensitive)"
+                    },
+                    "regex": {
+                        "type": "boolean",
+                        "description": "Optional: treat contains as a regular expression"
+                    },
+                    "since": {
+                        "type": "string",
+                        "description": "Optional: only lines at or after this RFC 3339 UTC time, e.g. 2026-01-20T18:25:00Z"
+                    },
+                    "until": {
+                        "type": "string",
+                        "description": "Optional: only lines at or before this RFC 3339 UTC time; a prefix like 2026-01-20T18:30 covers that whole minute"
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Optional: replace the file if it exists"
+                    }
+                },
+                "required": ["path"]
+            }),
+        },
+        ToolDefinition {
+            name: "read_system_logs".to_string(),
+            description: "Read the dora coordinator and daemon logs: spawn failures, zenoh endpoints, build steps and connection problems that do not show up in node logs. Reads the output of a coordinator and daemon Studio started and the log files set in the settings.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "source": {
+                        "type": "string",
+                        "description": "Optional: only this source, e.g. coordinator, daemon or a log file name"
+                    },
+                    "level": {
+                        "type": "string",
+                        "enum": ["trace", "debug", "info", "warn", "error"],
+                        "description": "Optional: only lines at least this severe"
+                    },
+                    "contains": {
+                        "type": "string",
+                        "description": "Optional: only lines whose message, target or fields contain this text (case-insensitive)"
+                    },
+                    "regex": {
+                        "type": "boolean",
+                        "description": "Optional: treat contains as a regular expression"
+                    },
+                    "lines": {
+                        "type": "integer",
+                        "description": "Optional: at most this many of the newest matching lines (default 200)"
+                    }
+                },
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "analyze_logs".to_string(),
+            description: "Summarize logs instead of reading them line by line: counts per level, when errors peaked, and the most frequent message patterns with their variable parts shown as <*>. Works on the logs of one or more dataflows, or on the coordinator and daemon logs with system set.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dataflow_id": {
+                        "type": "string",
+                        "description": "UUID or name of the dataflow"
+                    },
+                    "dataflow_ids": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Optional: several dataflows analyzed together, instead of dataflow_id"
+                    },
+                    "system": {
+                        "type": "boolean",
+                        "description": "Optional: analyze the coordinator and daemon logs instead of a dataflow's"
+                    },
+                    "node": {
+                        "type": "string",
+                        "description": "Optional: only logs of this node, or of this source with system"
+                    },
+                    "level": {
+                        "type": "string",
+                        "enum": ["trace", "debug", "info", "warn", "error"],
+                        "description": "Optional: only lines at least this severe"
+                    },
+                    "contains": {
+                        "type": "string",
+                        "description": "Optional: only lines whose message, node, target or fields contain this text (case-insensitive)"
+                    },
+                    "regex": {
+                        "type": "boolean",
+                        "description": "Optional: treat contains as a regular expression"
+                    },
+                    "top": {
+                        "type": "integer",
+                        "description": "Optional: how many patterns to list (default 20)"
+                    }
+                },
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "get_node_metrics".to_string(),
+            description: "Current CPU, memory, thread and open file counts of each dora node, busiest first. Finds the processes of every node on this machine, so it answers which node uses the most CPU or memory when dora list only has totals per dataflow. CPU is percent of one core since the previous sample.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dataflow_id": {
+                        "type": "string",
+                        "description": "Optional: only nodes of this dataflow, by UUID, UUID prefix or name"
+                    },
+                    "node": {
+                        "type": "string",
+                        "description": "Optional: only this node"
+                    },
+                    "sort_by": {
+                        "type": "string",
+                        "enum": ["cpu", "memory", "threads", "fds"],
+                        "description": "Optional: what to sort by, highest first (default cpu)"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Optional: at most this many nodes (default 20)"
+                    }
+                },
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "dora_up".to_string(),
+            description: "Bring up dora: start a local coordinator and daemon supervised by Studio. Use when dataflow commands fail because no coordinator or daemon is running.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "dora_down".to_string(),
+            description: "Tear down dora: destroy the local coordinator, which stops all daemons and dataflows. Remote coordinators are not touched.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "dora_status".to_string(),
+            description: "Check whether the coordinator and daemon are reachable, and show recent output of the processes Studio supervises.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {},
+                "required": []
             }),
         },
         ToolDefinition {
@@ -163,7 +399,15 @@ pub fn execute_tool(name: &str, tool_use_id: &str, args: &serde_json::Value) ->
         "dora_start" => execute_dora_start(args),
         "dora_stop" => execute_dora_stop(args),
         "dora_destroy" => execute_dora_destroy(args),
+        "restart_dataflow" => execute_restart_dataflow(args),
         "dora_logs" => execute_dora_logs(args),
+        "export_logs" => execute_export_logs(args),
+        "read_system_logs" => execute_read_system_logs(args),
+        "analyze_logs" => execute_analyze_logs(args),
+        "get_node_metrics" => execute_get_node_metrics(args),
+        "dora_up" => supervisor::up(&settings::get().selected_endpoint()),
+        "dora_down" => supervisor::down(),
+        "dora_status" => Ok(execute_dora_status()),
         "shell_command" => execute_shell_command(args),
         "read_file" => execute_read_file(args),
         "write_file" => execute_write_file(args),
@@ -186,7 +430,8 @@ pub fn execute_tool(name: &str, tool_use_id: &str, args: &serde_json::Value) ->
 }
 
 fn execute_dora_list() -> Result<String, String> {
-    run_command("dora", &["list", "--format", "json"])
+    let dataflows = with_client(|client| client.list_dataflows()).map_err(|e| e.to_string())?;
+    serde_json::to_string(&dataflows).map_err(|e| format!("Failed to serialize dataflows: {}", e))
 }
 
 fn execute_dora_start(args: &serde_json::Value) -> Result<String, String> {
@@ -195,7 +440,20 @@ fn execute_dora_start(args: &serde_json::Value) -> Result<String, String> {
         .and_then(|v| v.as_str())
         .ok_or("Missing dataflow_path argument")?;
 
-    run_command("dora", &["start", "--detach", path])
+    let mut request = StartRequest::new(path);
+    request.name = args.get("name").and_then(|v| v.as_str()).map(String::from);
+    if let Some(env) = args.get("env").and_then(|v| v.as_object()) {
+        for (key, value) in env {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            request.env.insert(key.clone(), value);
+        }
+    }
+
+    let uuid = launches::start(&request).map_err(|e| e.to_string())?;
+    Ok(format!("Started dataflow {}", uuid))
 }
 
 fn execute_dora_stop(args: &serde_json::Value) -> Result<String, String> {
@@ -204,7 +462,8 @@ fn execute_dora_stop(args: &serde_json::Value) -> Result<String, String> {
         .and_then(|v| v.as_str())
         .ok_or("Missing dataflow_id argument")?;
 
-    run_command("dora", &["stop", id])
+    with_client(|client| client.stop_dataflow(id)).map_err(|e| e.to_string())?;
+    Ok(format!("Stopped dataflow {}", id))
 }
 
 fn execute_dora_destroy(args: &serde_json::Value) -> Result<String, String> {
@@ -213,23 +472,343 @@ fn execute_dora_destroy(args: &serde_json::Value) -> Result<String, String> {
         .and_then(|v| v.as_str())
         .ok_or("Missing dataflow_id argument")?;
 
-    run_command("dora", &["destroy", id])
+    with_client(|client| client.destroy_dataflow(id)).map_err(|e| e.to_string())?;
+    Ok(format!("Destroyed dataflow {}", id))
 }
 
-fn execute_dora_logs(args: &serde_json::Value) -> Result<String, String> {
+fn execute_restart_dataflow(args: &serde_json::Value) -> Result<String, String> {
     let id = args
         .get("dataflow_id")
         .and_then(|v| v.as_str())
         .ok_or("Missing dataflow_id argument")?;
 
-    let mut cmd_args = vec!["logs", id];
+    let uuid = launches::restart(id)?;
+    Ok(format!("Restarted dataflow {} as {}", id, uuid))
+}
+
+fn execute_dora_logs(args: &serde_json::Value) -> Result<String, String> {
+    let query = LogQuery::from_args(args)?;
+    let sources = fetch_logs(&query.ids, query.node.as_deref())?;
+    Ok(render_logs(&sources, query.node.as_deref(), query.min_level, &query.search))
+}
+
+fn execute_export_logs(args: &serde_json::Value) -> Result<String, String> {
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing path argument")?;
+    let format = match args.get("format").and_then(|v| v.as_str()) {
+        Some(format) => ExportFormat::parse(format).ok_or_else(|| format!("Unknown export format: {}", format))?,
+        None => ExportFormat::from_path(Path::new(path)).unwrap_or_default(),
+    };
+    let overwrite = args.get("overwrite").and_then(|v| v.as_bool()).unwrap_or(false);
+    let range = TimeRange {
+        since: args.get("since").and_then(|v| v.as_str()).map(str::to_string),
+        until: args.get("until").and_then(|v| v.as_str()).map(str::to_string),
+    };
+    let query = LogQuery::from_args(args)?;
 
-    if let Some(node) = args.get("node").and_then(|v| v.as_str()) {
-        cmd_args.push("--node");
-        cmd_args.push(node);
+    // Check the path before fetching logs that could not be saved
+    export::export_path(path, overwrite)?;
+    let sources = fetch_logs(&query.ids, query.node.as_deref())?;
+    let lines = range.apply(filter_logs(&sources, query.node.as_deref(), query.min_level, &query.search));
+    let summary = export::write_export(path, &lines, format, overwrite)?;
+    Ok(summary.to_string())
+}
+
+fn execute_read_system_logs(args: &serde_json::Value) -> Result<String, String> {
+    let (min_level, search) = level_and_search(args)?;
+    let filter = LogFilter {
+        node: args.get("source").and_then(|v| v.as_str()).map(str::to_string),
+        min_level,
+    };
+    let limit = args.get("lines").and_then(|v| v.as_u64()).unwrap_or(200) as usize;
+
+    let logs = system::read_system_logs(system::SYSTEM_TAIL_LINES)?;
+    Ok(render_system_logs(&logs, &filter, &search, limit))
+}
+
+fn execute_analyze_logs(args: &serde_json::Value) -> Result<String, String> {
+    let top = args.get("top").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+    let lines = if args.get("system").and_then(|v| v.as_bool()).unwrap_or(false) {
+        let (min_level, search) = level_and_search(args)?;
+        let filter = LogFilter {
+            node: args.get("node").and_then(|v| v.as_str()).map(str::to_string),
+            min_level,
+        };
+        system::read_system_logs(system::SYSTEM_TAIL_LINES)?
+            .lines
+            .into_iter()
+            .filter(|line| filter.matches(line) && (search.is_empty() || search.is_match(line)))
+            .collect()
+    } else {
+        let query = LogQuery::from_args(args)?;
+        let sources = fetch_logs(&query.ids, query.node.as_deref())?;
+        filter_logs(&sources, query.node.as_deref(), query.min_level, &query.search)
+    };
+    Ok(render_analysis(&lines, top))
+}
+
+fn execute_get_node_metrics(args: &serde_json::Value) -> Result<String, String> {
+    let sort = match args.get("sort_by").and_then(|v| v.as_str()) {
+        Some(sort) => NodeSort::parse(sort).ok_or_else(|| format!("Unknown sort: {}", sort))?,
+        None => NodeSort::Cpu,
+    };
+    let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+    // Names are only for display and filtering, the processes are found without the coordinator
+    let names: HashMap<String, String> = with_client(|client| client.list_dataflows())
+        .map(|dataflows| dataflows.into_iter().map(|df| (df.uuid, df.name)).collect())
+        .unwrap_or_default();
+
+    let metrics = process::sample_nodes()?;
+    let found = metrics.len();
+    let metrics = filter_node_metrics(
+        metrics,
+        args.get("dataflow_id").and_then(|v| v.as_str()),
+        args.get("node").and_then(|v| v.as_str()),
+        &names,
+    );
+    if metrics.is_empty() && found > 0 {
+        return Ok(format!("No matching node processes, {} other nodes run on this machine", found));
     }
+    Ok(render_top_nodes(metrics, &names, sort, limit))
+}
 
-    run_command("dora", &cmd_args)
+/// Nodes of a dataflow given by UUID, UUID prefix or name, and of a node
+fn filter_node_metrics(
+    metrics: Vec<NodeMetrics>,
+    dataflow: Option<&str>,
+    node: Option<&str>,
+    names: &HashMap<String, String>,
+) -> Vec<NodeMetrics> {
+    metrics
+        .into_iter()
+        .filter(|m| {
+            dataflow.is_none_or(|d| {
+                m.dataflow.starts_with(d) || names.get(&m.dataflow).is_some_and(|name| name == d)
+            })
+        })
+        .filter(|m| node.is_none_or(|n| m.node == n))
+        .collect()
+}
+
+/// The `limit` top nodes by `sort`, saying how many were left out
+fn render_top_nodes(
+    mut metrics: Vec<NodeMetrics>,
+    names: &HashMap<String, String>,
+    sort: NodeSort,
+    limit: usize,
+) -> String {
+    sort.sort(&mut metrics);
+    let hidden = metrics.len().saturating_sub(limit);
+    metrics.truncate(limit);
+    let mut out = process::render_node_metrics(&metrics, names, sort);
+    if hidden > 0 {
+        out.push_str(&format!("\n... {} more", hidden));
+    }
+    out
+}
+
+/// Level counts, time span, error peak and the `top` most frequent patterns
+fn render_analysis(lines: &[LogLine], top: usize) -> String {
+    if lines.is_empty() {
+        return "No log lines match.".to_string();
+    }
+    let histogram = LevelHistogram::build(lines, 60);
+    let mut counts = [0; 5];
+    for line in lines {
+        if let Some(index) = LogLevel::ALL.iter().position(|level| *level == line.level) {
+            counts[index] += 1;
+        }
+    }
+    let levels: Vec<_> = LogLevel::ALL
+        .iter()
+        .zip(counts)
+        .rev()
+        .filter(|(_, count)| *count > 0)
+        .map(|(level, count)| format!("{} {}", count, level))
+        .collect();
+    let mut out = format!("{} lines: {}", lines.len(), levels.join(", "));
+
+    if !histogram.is_empty() {
+        let end = histogram.bucket_start(histogram.buckets.len());
+        out.push_str(&format!("\nFrom {} to {}", format_utc(histogram.start), format_utc(end)));
+        let peak = (0..histogram.buckets.len())
+            .map(|index| (index, histogram.count(index, LogLevel::Error)))
+            .filter(|(_, errors)| *errors > 0)
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)));
+        if let Some((index, errors)) = peak {
+            out.push_str(&format!(
+                "\nMost errors: {} in the {}s from {}",
+                errors,
+                histogram.bucket_secs,
+                format_utc(histogram.bucket_start(index))
+            ));
+        }
+    }
+
+    let patterns = patterns::mine(lines);
+    out.push_str(&format!("\n\n{} patterns, most frequent first:", patterns.len()));
+    for pattern in patterns.iter().take(top) {
+        out.push_str(&format!("\n{}x {} ", pattern.count, pattern.level));
+        if !pattern.nodes.is_empty() {
+            out.push_str(&format!("[{}] ", pattern.nodes.join(", ")));
+        }
+        out.push_str(&pattern.template);
+        match (&pattern.first_seen, &pattern.last_seen) {
+            (Some(first), Some(last)) if first != last => out.push_str(&format!(" ({} to {})", first, last)),
+            (Some(first), _) => out.push_str(&format!(" ({})", first)),
+            _ => {}
+        }
+    }
+    if patterns.len() > top {
+        out.push_str(&format!("\n... {} more", patterns.len() - top));
+    }
+    out
+}
+
+/// The newest `limit` system lines that pass the filters, then what could not be read
+fn render_system_logs(logs: &SystemLogs, filter: &LogFilter, search: &LogSearch, limit: usize) -> String {
+    if let Some(source) = &filter.node {
+        if !logs.sources.contains(source) {
+            return format!("Unknown source {}. Sources: {}", source, logs.sources.join(", "));
+        }
+    }
+    let lines: Vec<_> = logs
+        .lines
+        .iter()
+        .filter(|line| filter.matches(line) && (search.is_empty() || search.is_match(line)))
+        .map(LogLine::display)
+        .collect();
+    let mut out = if lines.is_empty() {
+        "No log lines match.".to_string()
+    } else {
+        lines[lines.len().saturating_sub(limit)..].join("\n")
+    };
+    for error in &logs.errors {
+        out.push_str(&format!("\nCould not read {}", error));
+    }
+    out
+}
+
+/// Dataflows and filters shared by `dora_logs` and `export_logs`
+struct LogQuery {
+    ids: Vec<String>,
+    node: Option<String>,
+    min_level: Option<LogLevel>,
+    search: LogSearch,
+}
+
+impl LogQuery {
+    fn from_args(args: &serde_json::Value) -> Result<Self, String> {
+        let ids: Vec<String> = match args.get("dataflow_ids").and_then(|v| v.as_array()) {
+            Some(ids) => ids.iter().filter_map(|v| v.as_str()).map(str::to_string).collect(),
+            None => args
+                .get("dataflow_id")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .into_iter()
+                .collect(),
+        };
+        if ids.is_empty() {
+            return Err("Missing dataflow_id argument".to_string());
+        }
+        let (min_level, search) = level_and_search(args)?;
+        Ok(Self {
+            ids,
+            node: args.get("node").and_then(|v| v.as_str()).map(str::to_string),
+            min_level,
+            search,
+        })
+    }
+}
+
+/// The `level`, `contains` and `regex` arguments
+fn level_and_search(args: &serde_json::Value) -> Result<(Option<LogLevel>, LogSearch), String> {
+    let min_level = match args.get("level").and_then(|v| v.as_str()) {
+        Some(level) => Some(LogLevel::parse(level).ok_or_else(|| format!("Unknown log level: {}", level))?),
+        None => None,
+    };
+    let regex = args.get("regex").and_then(|v| v.as_bool()).unwrap_or(false);
+    let search = LogSearch::new(args.get("contains").and_then(|v| v.as_str()).unwrap_or(""), regex)?;
+    Ok((min_level, search))
+}
+
+/// Raw logs of a dataflow as dora writes them, for one node or all of them
+pub fn dataflow_logs(id: &str, node: Option<&str>) -> Result<String, String> {
+    // The coordinator can't serve whole-dataflow logs, the CLI can
+    match with_client(|client| client.dataflow_logs(id, node)) {
+        Err(ClientError::Unsupported(_)) => {
+            CliClient::with_coordinator(settings::get().selected_endpoint())
+                .dataflow_logs(id, node)
+        }
+        result => result,
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Raw logs of each dataflow, for one node or all of them
+fn fetch_logs(ids: &[String], node: Option<&str>) -> Result<Vec<(String, String)>, String> {
+    let mut sources = Vec::new();
+    for id in ids {
+        let logs = dataflow_logs(id, node).map_err(|e| format!("{}: {}", id, e))?;
+        sources.push((id.clone(), logs));
+    }
+    Ok(sources)
+}
+
+/// Parse the logs of one or more dataflows, merge them by time and keep the
+/// lines that pass the level filter and search. Every line gets its dataflow.
+fn filter_logs(sources: &[(String, String)], node: Option<&str>, min_level: Option<LogLevel>, search: &LogSearch) -> Vec<LogLine> {
+    let filter = LogFilter {
+        node: None,
+        min_level,
+    };
+    let parsed: Vec<_> = sources
+        .iter()
+        .map(|(id, logs)| (id.clone(), LogLine::parse_all(logs, node, &[])))
+        .collect();
+    merge_by_timestamp(&parsed)
+        .into_iter()
+        .filter(|line| filter.matches(line) && (search.is_empty() || search.is_match(line)))
+        .collect()
+}
+
+/// Filtered logs as short text lines. Several dataflows are merged by time,
+/// each line prefixed with the first block of its dataflow's id.
+fn render_logs(sources: &[(String, String)], node: Option<&str>, min_level: Option<LogLevel>, search: &LogSearch) -> String {
+    let merged = sources.len() > 1;
+    let lines: Vec<_> = filter_logs(sources, node, min_level, search)
+        .into_iter()
+        .map(|line| match (&line.dataflow_id, merged) {
+            (Some(id), true) => format!("{{{}}} {}", short_id(id), line.display()),
+            _ => line.display(),
+        })
+        .collect();
+    if lines.is_empty() {
+        "No log lines match.".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+fn execute_dora_status() -> String {
+    let mut status = supervisor::status();
+    match version::cli_version() {
+        Ok(v) => status.push_str(&format!("\ndora CLI version: {}", v)),
+        Err(e) => status.push_str(&format!("\ndora CLI version unknown: {}", e)),
+    }
+    if let Some(warning) = version::check_versions(&settings::get().selected_endpoint()) {
+        status.push_str(&format!("\nWarning: {}", warning));
+    }
+    let output = supervisor::recent_output(20);
+    if !output.is_empty() {
+        status.push_str("\n\nRecent output:");
+        for line in output {
+            status.push_str(&format!("\n[{}] {}", line.source.name(), line.line));
+        }
+    }
+    status
 }
 
 fn execute_shell_command(args: &serde_json::Value) -> Result<String, String> {
@@ -323,28 +902,6 @@ fn execute_list_directory(args: &serde_json::Value) -> Result<String, String> {
     Ok(result.join("\n"))
 }
 
-fn run_command(program: &str, args: &[&str]) -> Result<String, String> {
-    let output = Command::new(program)
-        .args(args)
-        .output()
-        .map_err(|e| format!("Failed to execute {}: {}", program, e))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
-    if output.status.success() {
-        Ok(format!("{}{}", stdout, stderr))
-    } else {
-        Err(format!(
-            "{} failed with exit code {:?}\nstdout: {}\nstderr: {}",
-            program,
-            output.status.code(),
-            stdout,
-            stderr
-        ))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -359,6 +916,9 @@ mod tests {
         assert!(tool_names.contains(&"dora_list"));
         assert!(tool_names.contains(&"dora_start"));
         assert!(tool_names.contains(&"dora_stop"));
+        assert!(tool_names.contains(&"restart_dataflow"));
+        assert!(tool_names.contains(&"dora_up"));
+        assert!(tool_names.contains(&"dora_status"));
         assert!(tool_names.contains(&"shell_command"));
         assert!(tool_names.contains(&"read_file"));
     }
@@ -401,6 +961,157 @@ mod tests {
         assert!(result.content.contains("Missing path"));
     }
 
+    fn render(logs: &str, node: Option<&str>, min_level: Option<LogLevel>, contains: Option<&str>) -> String {
+        let search = LogSearch::new(contains.unwrap_or(""), false).unwrap();
+        render_logs(&[("a".to_string(), logs.to_string())], node, min_level, &search)
+    }
+
+    #[test]
+    fn test_render_logs_filters_structured_lines() {
+        let logs = concat!(
+            r#"{"timestamp":"2026-01-20T18:25:28.879264Z","level":"INFO","fields":{"message":"Connected to dora-coordinator"},"target":"dora_daemon::coordinator"}"#,
+            "\n",
+            r#"{"timestamp":"2026-01-20T18:25:28.879364Z","level":"WARN","fields":{"message":"Daemon listen port already in use."},"target":"dora_daemon::local_listener"}"#,
+            "\nplain ERROR line\n",
+        );
+        assert_eq!(render(logs, None, None, None).lines().count(), 3);
+        assert_eq!(
+            render(logs, None, Some(LogLevel::Warn), Some("port")),
+            "2026-01-20T18:25:28.879364Z WARN dora_daemon::local_listener: Daemon listen port already in use."
+        );
+        assert_eq!(render(logs, Some("camera"), Some(LogLevel::Error), None), "[camera] plain ERROR line");
+        assert_eq!(render(logs, None, None, Some("zenoh")), "No log lines match.");
+    }
+
+    #[test]
+    fn test_render_logs_merges_dataflows_and_searches_regex() {
+        let a = concat!(
+            r#"{"timestamp":"2026-01-20T18:25:28Z","level":"INFO","fields":{"message":"frame 1"},"target":"camera"}"#,
+            "\n",
+            r#"{"timestamp":"2026-01-20T18:25:30Z","level":"INFO","fields":{"message":"frame 2"},"target":"camera"}"#,
+        );
+        let b = r#"{"timestamp":"2026-01-20T18:25:29Z","level":"ERROR","fields":{"message":"no frame"},"target":"detector"}"#;
+        let sources = [
+            ("0a1b2c3d-0000-0000-0000-000000000000".to_string(), a.to_string()),
+            ("9f8e7d6c-0000-0000-0000-000000000000".to_string(), b.to_string()),
+        ];
+        let merged = render_logs(&sources, None, None, &LogSearch::Empty);
+        let prefixes: Vec<_> = merged.lines().map(|l| &l[..10]).collect();
+        assert_eq!(prefixes, vec!["{0a1b2c3d}", "{9f8e7d6c}", "{0a1b2c3d}"]);
+
+        let search = LogSearch::new(r"^frame \d$", true).unwrap();
+        assert_eq!(render_logs(&sources, None, None, &search).lines().count(), 2);
+    }
+
+    #[test]
+    fn test_render_system_logs() {
+        let daemon = [
+            r#"{"timestamp":"2026-01-22T10:53:43Z","level":"INFO","fields":{"message":"Connected to dora-coordinator"},"target":"dora_daemon::coordinator"}"#,
+            r#"{"timestamp":"2026-01-22T10:53:45Z","level":"ERROR","fields":{"message":"failed to spawn node"},"target":"dora_daemon::spawn"}"#,
+        ];
+        let logs = SystemLogs {
+            lines: system::parse_source("daemon", daemon.into_iter()),
+            sources: vec!["daemon".to_string()],
+            errors: vec!["coordinator.json: No such file or directory".to_string()],
+        };
+        let errors = LogFilter {
+            node: None,
+            min_level: Some(LogLevel::Error),
+        };
+        assert_eq!(
+            render_system_logs(&logs, &errors, &LogSearch::Empty, 10),
+            "2026-01-22T10:53:45Z ERROR dora_daemon::spawn: [daemon] failed to spawn node\nCould not read coordinator.json: No such file or directory"
+        );
+        let all = LogFilter::default();
+        let newest = render_system_logs(&logs, &all, &LogSearch::Empty, 1);
+        assert!(newest.starts_with("2026-01-22T10:53:45Z"));
+        let search = LogSearch::new("CONNECTED", false).unwrap();
+        assert!(render_system_logs(&logs, &all, &search, 10).contains("Connected to dora-coordinator"));
+        let coordinator = LogFilter {
+            node: Some("coordinator".to_string()),
+            min_level: None,
+        };
+        assert_eq!(
+            render_system_logs(&logs, &coordinator, &LogSearch::Empty, 10),
+            "Unknown source coordinator. Sources: daemon"
+        );
+    }
+
+    #[test]
+    fn test_render_analysis() {
+        let mut lines = Vec::new();
+        for i in 0..30 {
+            lines.push(LogLine {
+                node: Some("camera".to_string()),
+                timestamp: Some(format!("2026-01-22T10:57:{:02}Z", i)),
+                text: format!("frame {} sent", i),
+                ..Default::default()
+            });
+        }
+        for i in 0..3 {
+            lines.push(LogLine {
+                node: Some("detector".to_string()),
+                level: LogLevel::Error,
+                timestamp: Some(format!("2026-01-22T10:58:0{}Z", i)),
+                text: format!("inference failed: CUDA error {}", i),
+                ..Default::default()
+            });
+        }
+        let analysis = render_analysis(&lines, 1);
+        let mut out = analysis.lines();
+        assert_eq!(out.next(), Some("33 lines: 3 ERROR, 30 INFO"));
+        assert_eq!(out.next(), Some("From 2026-01-22 10:57:00 UTC to 2026-01-22 10:58:04 UTC"));
+        assert_eq!(out.next(), Some("Most errors: 2 in the 2s from 2026-01-22 10:58:00 UTC"));
+        assert_eq!(out.nth(1), Some("2 patterns, most frequent first:"));
+        assert_eq!(
+            out.next(),
+            Some("30x INFO [camera] frame <*> sent (2026-01-22T10:57:00Z to 2026-01-22T10:57:29Z)")
+        );
+        assert_eq!(out.next(), Some("... 1 more"));
+        assert_eq!(render_analysis(&[], 20), "No log lines match.");
+    }
+
+    #[test]
+    fn test_render_top_nodes() {
+        let node = |dataflow: &str, node: &str, cpu: f64| NodeMetrics {
+            dataflow: dataflow.to_string(),
+            node: node.to_string(),
+            pids: vec![100],
+            cpu,
+            rss_bytes: 64 * 1024 * 1024,
+            threads: 4,
+            fds: 12,
+        };
+        let metrics = vec![
+            node("019be55a-8468-7d4b-9e95-db7938985bc7", "camera", 12.0),
+            node("019be55a-8468-7d4b-9e95-db7938985bc7", "detector", 180.5),
+            node("019be55b-0000-7d4b-9e95-db7938985bc7", "planner", 40.0),
+        ];
+        let names = HashMap::from([("019be55a-8468-7d4b-9e95-db7938985bc7".to_string(), "vision".to_string())]);
+
+        let vision = filter_node_metrics(metrics.clone(), Some("vision"), None, &names);
+        assert_eq!(vision.len(), 2);
+        assert_eq!(filter_node_metrics(metrics.clone(), Some("019be55b"), None, &names).len(), 1);
+        assert_eq!(filter_node_metrics(metrics.clone(), None, Some("camera"), &names).len(), 1);
+
+        let text = render_top_nodes(metrics, &names, NodeSort::Cpu, 2);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "2 nodes of 2 dataflows, by CPU:");
+        assert!(lines[2].starts_with("detector") && lines[2].contains("vision") && lines[2].contains("180.5%"));
+        assert!(lines[3].starts_with("planner") && lines[3].contains("019be55b"));
+        assert_eq!(lines[4], "... 1 more");
+    }
+
+    #[test]
+    fn test_export_logs_checks_arguments_first() {
+        let args = serde_json::json!({ "dataflow_id": "abc", "path": "logs.csv" });
+        assert!(execute_export_logs(&args).unwrap_err().contains("must be absolute"));
+        let args = serde_json::json!({ "dataflow_id": "abc", "path": "/tmp/logs.xml", "format": "xml" });
+        assert_eq!(execute_export_logs(&args).unwrap_err(), "Unknown export format: xml");
+        let args = serde_json::json!({ "path": "/tmp/logs.csv" });
+        assert_eq!(execute_export_logs(&args).unwrap_err(), "Missing dataflow_id argument");
+    }
+
     #[test]
     fn test_tool_result_structure() {
         let result = ToolResult {
@@ -0,0 +1,368 @@
+//! Dataflow lifecycle events from coordinator and daemon logs.
+//!
+//! The coordinator logs a JSON tracing line whenever it spawns, stops or
+//! loses a dataflow (`successfully spawned dataflow `<uuid>``). The watcher
+//! tails the log files configured in the settings, and sees the output of a
+//! coordinator started by the [`supervisor`](crate::supervisor), and asks
+//! the poller for an immediate refresh on each such event. While a local
+//! coordinator is selected and one of these sources is producing lines, the
+//! poller falls back to a much longer interval.
+
+use crate::dataflow::descriptor;
+use crate::dataflow::model::{looks_like_uuid, unix_now};
+use crate::logs::LogLine;
+use crate::{poller, settings, supervisor};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Log files currently tailed
+static TAILS: Mutex<Vec<Tail>> = Mutex::new(Vec::new());
+
+// `watch_logs` of the settings as of the last `start`, read for every
+// captured output line
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+// Descriptors from `spawn_dataflow` spans by dataflow UUID, newest last
+static DESCRIPTORS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Number of spawn descriptors kept
+const MAX_DESCRIPTORS: usize = 50;
+
+/// How often tailed files are checked for new lines
+const TAIL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How recently a tailed file must have grown to count as a live source
+const TAIL_QUIET_SECS: u64 = 300;
+
+/// A log file being tailed
+struct Tail {
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+    /// Unix time of the last line read, 0 before the first
+    last_line: Arc<AtomicU64>,
+}
+
+impl Tail {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            stop: Arc::new(AtomicBool::new(false)),
+            last_line: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Whether the file exists and lines were appended to it lately
+    fn is_producing(&self, now: u64) -> bool {
+        let last_line = self.last_line.load(Ordering::Relaxed);
+        last_line > 0 && now.saturating_sub(last_line) <= TAIL_QUIET_SECS && self.path.exists()
+    }
+}
+
+/// A dataflow lifecycle change seen in the logs
+#[derive(Debug, Clone, PartialEq)]
+pub enum LifecycleEvent {
+    /// The coordinator accepted a start request
+    Spawning(String),
+    /// All nodes were spawned
+    Spawned(String),
+    /// Spawning or running the dataflow failed
+    Failed(String),
+    /// A stop was sent to the daemons
+    Stopping(String),
+    /// The dataflow finished on a machine
+    Finished(String),
+    /// The coordinator was destroyed or stopped
+    CoordinatorStopped,
+}
+
+impl LifecycleEvent {
+    /// UUID of the affected dataflow
+    pub fn dataflow_id(&self) -> Option<&str> {
+        match self {
+            LifecycleEvent::Spawning(id)
+            | LifecycleEvent::Spawned(id)
+            | LifecycleEvent::Failed(id)
+            | LifecycleEvent::Stopping(id)
+            | LifecycleEvent::Finished(id) => Some(id),
+            LifecycleEvent::CoordinatorStopped => None,
+        }
+    }
+}
+
+/// Recognize a lifecycle event in a log line, JSON tracing or plain text
+pub fn classify(line: &str) -> Option<LifecycleEvent> {
+    let message = log_message(line)?;
+    let lower = message.to_lowercase();
+
+    if lower == "stopped" || lower.contains("received destroy command") {
+        return Some(LifecycleEvent::CoordinatorStopped);
+    }
+    if !lower.contains("dataflow") {
+        return None;
+    }
+    let uuid = find_uuid(&message)?;
+
+    let event = if lower.contains("error while spawning") || lower.contains("failed") {
+        LifecycleEvent::Failed(uuid)
+    } else if lower.contains("triggered dataflow spawn") {
+        LifecycleEvent::Spawning(uuid)
+    } else if lower.contains("spawned dataflow") {
+        LifecycleEvent::Spawned(uuid)
+    } else if lower.contains("stop dataflow") {
+        LifecycleEvent::Stopping(uuid)
+    } else if lower.contains("removed machine id") || lower.contains("finished") {
+        LifecycleEvent::Finished(uuid)
+    } else {
+        return None;
+    };
+    Some(event)
+}
+
+/// The message of a JSON tracing line, or the whole line for plain text
+fn log_message(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    Some(LogLine::parse(line, &[]).text)
+}
+
+/// The dataflow UUID in a message, preferring the one right after "dataflow"
+/// (other UUIDs, like machine ids, can come first)
+fn find_uuid(message: &str) -> Option<String> {
+    let first_uuid = |text: &str| {
+        text.split(|c: char| !(c.is_ascii_hexdigit() || c == '-'))
+            .find(|token| looks_like_uuid(token))
+            .map(str::to_string)
+    };
+    let after_dataflow = message
+        .to_ascii_lowercase()
+        .rfind("dataflow")
+        .and_then(|i| first_uuid(&message[i..]));
+    after_dataflow.or_else(|| first_uuid(message))
+}
+
+/// Feed one log line; refreshes the dataflow table if it is a lifecycle event
+pub fn observe_line(line: &str) {
+    if let Some(event) = classify(line) {
+        if let (LifecycleEvent::Spawning(uuid), Some(descriptor)) = (&event, descriptor::spawn_descriptor(line)) {
+            remember_descriptor(uuid, descriptor);
+        }
+        poller::refresh();
+    }
+}
+
+fn remember_descriptor(uuid: &str, descriptor: String) {
+    let mut descriptors = DESCRIPTORS.lock().unwrap();
+    descriptors.retain(|(id, _)| id != uuid);
+    descriptors.push((uuid.to_string(), descriptor));
+    if descriptors.len() > MAX_DESCRIPTORS {
+        descriptors.remove(0);
+    }
+}
+
+/// The descriptor the coordinator logged when spawning `uuid`, in `Debug` format
+pub fn spawn_descriptor(uuid: &str) -> Option<String> {
+    DESCRIPTORS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(id, _)| id == uuid)
+        .map(|(_, descriptor)| descriptor.clone())
+}
+
+/// (Re)start tailing the log files from the settings
+pub fn start() {
+    let settings = settings::get();
+    WATCHING.store(settings.watch_logs, Ordering::Relaxed);
+    let mut tails = TAILS.lock().unwrap();
+    for tail in tails.drain(..) {
+        tail.stop.store(true, Ordering::Relaxed);
+    }
+    if !settings.watch_logs {
+        return;
+    }
+
+    for path in settings.log_files {
+        let tail = Tail::new(path.clone());
+        let (stop, last_line) = (tail.stop.clone(), tail.last_line.clone());
+        tails.push(tail);
+        std::thread::spawn(move || {
+            eprintln!("[Watcher] Tailing {}", path.display());
+            tail_file(&path, &stop, |line| {
+                last_line.store(unix_now(), Ordering::Relaxed);
+                observe_line(line);
+            });
+        });
+    }
+}
+
+/// Whether supervised output should be fed to [`observe_line`]
+pub fn is_watching() -> bool {
+    WATCHING.load(Ordering::Relaxed)
+}
+
+/// Whether lifecycle events of the selected coordinator are being watched,
+/// so polling can slow down. Only a coordinator on this machine writes the
+/// tailed files or is supervised, and only files that are still being
+/// written to say anything about it.
+pub fn is_active() -> bool {
+    let endpoint = settings::get().selected_endpoint();
+    if !supervisor::is_local(&endpoint) {
+        return false;
+    }
+    let now = unix_now();
+    TAILS.lock().unwrap().iter().any(|tail| tail.is_producing(now))
+        || (is_watching() && supervisor::supervises(&endpoint))
+}
+
+/// Call `on_line` for every line appended to `path` until `stop` is set.
+///
+/// Starts at the current end of the file. Waits for the file if it does not
+/// exist yet and starts over when it is truncated or replaced.
+fn tail_file(path: &Path, stop: &AtomicBool, mut on_line: impl FnMut(&str)) {
+    let mut pos = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let mut partial = String::new();
+
+    while !stop.load(Ordering::Relaxed) {
+        if let Ok(mut file) = File::open(path) {
+            let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+            if len < pos {
+                pos = 0;
+                partial.clear();
+            }
+            if len > pos && file.seek(SeekFrom::Start(pos)).is_ok() {
+                let mut chunk = Vec::new();
+                if let Ok(read) = file.take(len - pos).read_to_end(&mut chunk) {
+                    pos += read as u64;
+                    partial.push_str(&String::from_utf8_lossy(&chunk));
+                    while let Some(end) = partial.find('\n') {
+                        on_line(partial[..end].trim_end_matches('\r'));
+                        partial.drain(..=end);
+                    }
+                }
+            }
+        }
+        std::thread::sleep(TAIL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const UUID: &str = "019be55a-8468-7d4b-9e95-db7938985bc7";
+
+    fn coordinator_line(message: &str) -> String {
+        serde_json::json!({
+            "timestamp": "2026-01-22T10:57:48.488377Z",
+            "level": "INFO",
+            "fields": { "message": message },
+            "target": "dora_coordinator",
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_classify_coordinator_events() {
+        let cases = [
+            (
+                format!("successfully triggered dataflow spawn `{}`", UUID),
+                LifecycleEvent::Spawning(UUID.to_string()),
+            ),
+            (
+                format!("successfully spawned dataflow `{}`", UUID),
+                LifecycleEvent::Spawned(UUID.to_string()),
+            ),
+            (
+                format!("error while spawning dataflow `{}`", UUID),
+                LifecycleEvent::Failed(UUID.to_string()),
+            ),
+            (
+                format!("successfully send stop dataflow `{}` to all daemons", UUID),
+                LifecycleEvent::Stopping(UUID.to_string()),
+            ),
+            (
+                format!("removed machine id: 3d1027c9-8cb7-4769-8512-e37def2de8e0 from dataflow: {}", UUID),
+                LifecycleEvent::Finished(UUID.to_string()),
+            ),
+            ("Received destroy command".to_string(), LifecycleEvent::CoordinatorStopped),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(classify(&coordinator_line(&message)), Some(expected), "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_classify_ignores_unrelated_lines() {
+        assert_eq!(classify(&coordinator_line("Zenoh can be reached at: tcp/192.168.1.208:60559")), None);
+        assert_eq!(classify(&coordinator_line("Daemon `3d1027c9-8cb7-4769-8512-e37def2de8e0` exited")), None);
+        assert_eq!(classify(""), None);
+    }
+
+    #[test]
+    fn test_classify_plain_text() {
+        let line = format!("2026-01-22T10:57:48Z INFO dora_coordinator: successfully spawned dataflow `{}`", UUID);
+        assert_eq!(classify(&line), Some(LifecycleEvent::Spawned(UUID.to_string())));
+        assert_eq!(classify(&line).unwrap().dataflow_id(), Some(UUID));
+    }
+
+    #[test]
+    fn test_spawn_descriptor_is_remembered() {
+        let log = include_str!("../out/dora-coordinator.txt");
+        let line = log.lines().find(|l| l.contains("triggered dataflow spawn")).unwrap();
+        let uuid = classify(line).unwrap().dataflow_id().unwrap().to_string();
+
+        observe_line(line);
+        let descriptor = spawn_descriptor(&uuid).unwrap();
+        assert!(descriptor.starts_with("Descriptor { nodes: ["));
+    }
+
+    #[test]
+    fn test_tail_producing_only_while_file_grows() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let tail = Tail::new(file.path().to_path_buf());
+        assert!(!tail.is_producing(1000));
+
+        tail.last_line.store(1000, Ordering::Relaxed);
+        assert!(tail.is_producing(1000 + TAIL_QUIET_SECS));
+        assert!(!tail.is_producing(1001 + TAIL_QUIET_SECS));
+
+        let path = file.path().to_path_buf();
+        drop(file);
+        assert!(!Tail::new(path).is_producing(1000));
+    }
+
+    #[test]
+    fn test_tail_file_reads_appended_lines() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "old line").unwrap();
+        let path = file.path().to_path_buf();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let handle = {
+            let (stop, lines) = (stop.clone(), lines.clone());
+            std::thread::spawn(move || {
+                tail_file(&path, &stop, |line| lines.lock().unwrap().push(line.to_string()))
+            })
+        };
+
+        std::thread::sleep(TAIL_INTERVAL);
+        write!(file, "first\nsec").unwrap();
+        file.flush().unwrap();
+        std::thread::sleep(TAIL_INTERVAL * 2);
+        writeln!(file, "ond").unwrap();
+        file.flush().unwrap();
+        std::thread::sleep(TAIL_INTERVAL * 3);
+
+        stop.store(true, Ordering::Relaxed);
+        handle.join().unwrap();
+        assert_eq!(*lines.lock().unwrap(), vec!["first".to_string(), "second".to_string()]);
+    }
+}
@@ -101,10 +101,64 @@ mod log_viewer {
 mod telemetry_dashboard {
     use super::*;
 
-    #[tokio::test]
-    async fn test_chart_updates_on_time_range() {
-        // TODO: Test time range changes update charts
-        todo!("Implement test_chart_updates_on_time_range")
+    #[test]
+    fn test_chart_updates_on_time_range() {
+        use dora_studio::metrics::series::CPU;
+        use dora_studio::metrics::{
+            MetricsStore, RangePreset, Retention, Sample, TelemetryRequest, TimeWindow,
+        };
+
+        // A day of one sample a minute, valued by its minute of the day
+        let now = 1_768_989_600;
+        let samples: Vec<Sample> = (0..1440)
+            .map(|minute| Sample {
+                timestamp: now - 86400 + minute * 60,
+                dataflow: "a".to_string(),
+                node: None,
+                metric: CPU.to_string(),
+                value: minute as f64,
+            })
+            .collect();
+        let dir = tempfile::TempDir::new().unwrap();
+        let retention = Retention {
+            max_age_secs: u64::MAX,
+            max_bytes: u64::MAX,
+        };
+        let mut store = MetricsStore::open(dir.path(), retention).unwrap();
+        store.insert(&samples).unwrap();
+        let reload = |window: TimeWindow| {
+            store
+                .load_telemetry(TelemetryRequest {
+                    dataflows: vec![("a".to_string(), "camera".to_string())],
+                    node: None,
+                    window,
+                    points: 24,
+                })
+                .unwrap()
+        };
+
+        // The day preset averages each hour
+        let day = reload(TimeWindow::last(RangePreset::Day.secs().unwrap(), now));
+        assert_eq!(day.request.bucket_secs(), 3600);
+        assert_eq!(day.cpu[0].points.len(), 24);
+        assert_eq!(day.cpu[0].points[0], (now - 86400, 29.5));
+
+        // The hour preset reloads only the last hour, in finer buckets
+        let hour_window = TimeWindow::last(RangePreset::Hour.secs().unwrap(), now);
+        let hour = reload(hour_window);
+        assert_eq!(hour.request.bucket_secs(), 150);
+        assert_eq!(hour.cpu[0].points.len(), 24);
+        assert_eq!(hour.cpu[0].points[0], (now - 3600, 1381.0));
+
+        // Zooming into the first half of the hour drops the second half
+        let zoomed = hour_window.zoom(0.5, 0.0);
+        assert_eq!((zoomed.start, zoomed.end), (now - 3600, now - 1800));
+        let first_half = reload(zoomed);
+        assert_eq!(first_half.request.bucket_secs(), 75);
+        let points = &first_half.cpu[0].points;
+        assert_eq!(points[0], (now - 3600, 1380.5));
+        assert!(points.iter().all(|(t, _)| (zoomed.start..=zoomed.end).contains(t)));
+        assert_eq!(points.last().unwrap().1, 1410.0);
     }
 
     #[tokio::test]
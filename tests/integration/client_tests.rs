@@ -3,6 +3,28 @@
 //! Tests client interaction with mock coordinator.
 
 use super::*;
+use dora_studio::client::{health_of, ClientError, ConnectionHealth, CoordinatorClient, DoraClient};
+use dora_studio::logs::follow::{FollowSource, LogFollower};
+use std::io::Write;
+use std::time::Duration;
+
+/// Wait until the mock has registered `count` log subscribers
+fn wait_for_subscribers(mock: &MockCoordinator, count: usize) {
+    for _ in 0..100 {
+        if mock.log_subscriber_count() >= count {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    panic!("log subscription was never registered");
+}
+
+/// Write a dataflow YAML to a temporary file
+fn yaml_file(content: &str) -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(content.as_bytes()).unwrap();
+    file
+}
 
 // ============================================================================
 // Connection Tests
@@ -10,29 +32,120 @@ use super::*;
 
 #[tokio::test]
 async fn test_client_with_mock_coordinator() {
-    // TODO: Implement when MockCoordinator is ready
-    // let mock = MockCoordinator::start().await;
-    // let client = DoraClient::connect(&mock.addr()).await.unwrap();
-    // assert!(client.is_connected());
-    // mock.shutdown().await;
-    todo!("Implement test_client_with_mock_coordinator")
+    let mock = MockCoordinator::start().await;
+    let client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    assert!(client.is_connected());
+    mock.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_client_connect_failure() {
+    // Bind and drop a listener to get a port nobody listens on
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let result = CoordinatorClient::connect(&addr.to_string());
+    assert!(matches!(result, Err(ClientError::Io(_))));
+}
+
+/// A coordinator calling `serve` after each request it reads, and the count of them
+fn raw_coordinator(
+    serve: impl Fn(&mut std::net::TcpStream) + Send + 'static,
+) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+    use dora_studio::client::protocol::read_frame;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let requests = std::sync::Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            while read_frame(&mut stream).is_ok() {
+                counter.fetch_add(1, Ordering::SeqCst);
+                serve(&mut stream);
+            }
+        }
+    });
+    (addr, requests)
+}
+
+#[tokio::test]
+async fn test_client_does_not_resend_after_lost_reply() {
+    use std::net::Shutdown;
+    use std::sync::atomic::Ordering;
+
+    // The coordinator takes the request and drops the connection unanswered
+    let (addr, requests) = raw_coordinator(|stream| {
+        let _ = stream.shutdown(Shutdown::Both);
+    });
+    let mut client = CoordinatorClient::connect(&addr).unwrap();
+    let result = client.destroy_dataflow("019be55a-8468-7d4b-9e95-db7938985bc7");
+    assert!(matches!(result, Err(ClientError::Io(_))));
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_client_reconnects_after_coordinator_closed_connection() {
+    use dora_studio::client::protocol::write_frame;
+    use std::net::Shutdown;
+    use std::sync::atomic::Ordering;
+
+    // Answers one request per connection, then closes it
+    let (addr, requests) = raw_coordinator(|stream| {
+        write_frame(stream, br#"{"DataflowList":[]}"#).unwrap();
+        let _ = stream.shutdown(Shutdown::Both);
+    });
+    let mut client = CoordinatorClient::connect(&addr).unwrap();
+    assert!(client.list_dataflows().unwrap().is_empty());
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(client.list_dataflows().unwrap().is_empty());
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
 }
 
 #[tokio::test]
 async fn test_client_handles_malformed_response() {
-    // TODO: Test error handling for invalid JSON responses
-    // let mock = MockCoordinator::start().await;
-    // mock.set_response(b"invalid json{{{");
-    // let mut client = DoraClient::connect(&mock.addr()).await.unwrap();
-    // let result = client.list_dataflows().await;
-    // assert!(result.is_err());
-    todo!("Implement test_client_handles_malformed_response")
+    let mock = MockCoordinator::start().await;
+    mock.set_response(b"invalid json{{{");
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    let result = client.list_dataflows();
+    assert!(matches!(result, Err(ClientError::ParseError(_))));
 }
 
 #[tokio::test]
 async fn test_client_reconnects_on_disconnect() {
-    // TODO: Test automatic reconnection
-    todo!("Implement test_client_reconnects_on_disconnect")
+    let mock = MockCoordinator::start().await;
+    mock.add_running_dataflow(Uuid::new_v4());
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    assert_eq!(client.list_dataflows().unwrap().len(), 1);
+
+    mock.disconnect_clients();
+
+    // The stale connection is replaced transparently
+    assert_eq!(client.list_dataflows().unwrap().len(), 1);
+    assert!(client.is_connected());
+}
+
+#[tokio::test]
+async fn test_health_reports_missing_daemon() {
+    let mock = MockCoordinator::start().await;
+    let mut client = CoordinatorClient::new(mock.addr());
+    assert_eq!(health_of(&mut client), ConnectionHealth::Connected);
+
+    mock.set_daemon_connected(false);
+    assert_eq!(health_of(&mut client), ConnectionHealth::NoDaemon);
+
+    mock.shutdown().await;
+    let mut client = CoordinatorClient::new(mock.addr());
+    assert!(matches!(
+        health_of(&mut client),
+        ConnectionHealth::CoordinatorUnreachable(_)
+    ));
 }
 
 // ============================================================================
@@ -41,43 +154,111 @@ async fn test_client_reconnects_on_disconnect() {
 
 #[tokio::test]
 async fn test_list_dataflows_empty() {
-    // TODO: Test listing when no dataflows exist
-    // let mock = MockCoordinator::start().await;
-    // mock.set_dataflows(vec![]);
-    // let mut client = DoraClient::connect(&mock.addr()).await.unwrap();
-    // let flows = client.list_dataflows().await.unwrap();
-    // assert!(flows.is_empty());
-    todo!("Implement test_list_dataflows_empty")
+    let mock = MockCoordinator::start().await;
+    mock.set_dataflows(vec![]);
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    let flows = client.list_dataflows().unwrap();
+    assert!(flows.is_empty());
 }
 
 #[tokio::test]
 async fn test_list_dataflows_multiple() {
-    // TODO: Test listing multiple dataflows
-    todo!("Implement test_list_dataflows_multiple")
+    let mock = MockCoordinator::start().await;
+    mock.set_dataflows(vec![
+        mock_dataflow("flow-1"),
+        mock_dataflow_with_status("flow-2", DataflowStatus::Finished),
+        mock_dataflow_with_status("flow-3", DataflowStatus::Failed("OOM".to_string())),
+    ]);
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    let flows = client.list_dataflows().unwrap();
+
+    assert_eq!(flows.len(), 3);
+    assert_eq!(flows[0].name, "flow-1");
+    assert!(flows[0].is_running());
+    assert_eq!(flows[1].status.label(), "Finished");
+    assert_eq!(flows[2].status.label(), "Failed");
 }
 
 #[tokio::test]
 async fn test_start_dataflow_success() {
-    // TODO: Test successful dataflow start
-    todo!("Implement test_start_dataflow_success")
+    let mock = MockCoordinator::start().await;
+    let expected_uuid = Uuid::new_v4();
+    mock.expect_start_returns(expected_uuid);
+
+    let yaml = yaml_file(connected_dataflow_yaml());
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    let uuid = client.start_dataflow(yaml.path(), Some("pipeline")).unwrap();
+    assert_eq!(uuid, expected_uuid.to_string());
+
+    // The descriptor is sent parsed, not as a path
+    let requests = mock.received_requests();
+    let start = &requests.last().unwrap()["Start"];
+    assert_eq!(start["name"], "pipeline");
+    assert_eq!(start["dataflow"]["nodes"][1]["id"], "detector");
 }
 
 #[tokio::test]
 async fn test_start_dataflow_file_not_found() {
-    // TODO: Test error when YAML file doesn't exist
-    todo!("Implement test_start_dataflow_file_not_found")
+    let mock = MockCoordinator::start().await;
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    let result = client.start_dataflow(std::path::Path::new("nonexistent.yaml"), None);
+
+    assert!(matches!(result, Err(ClientError::Io(_))));
+    assert!(mock.received_requests().is_empty());
+}
+
+#[tokio::test]
+async fn test_start_dataflow_rejected_by_coordinator() {
+    let mock = MockCoordinator::start().await;
+    mock.expect_start_fails("node `camera` has no path");
+
+    let yaml = yaml_file(simple_dataflow_yaml());
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    let result = client.start_dataflow(yaml.path(), None);
+
+    match result {
+        Err(ClientError::CoordinatorError(msg)) => assert!(msg.contains("no path")),
+        other => panic!("expected coordinator error, got {:?}", other),
+    }
 }
 
 #[tokio::test]
 async fn test_stop_dataflow_success() {
-    // TODO: Test successful dataflow stop
-    todo!("Implement test_stop_dataflow_success")
+    let mock = MockCoordinator::start().await;
+    let uuid = Uuid::new_v4();
+    mock.add_running_dataflow(uuid);
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    client.stop_dataflow(&uuid.to_string()).unwrap();
+
+    let flows = client.list_dataflows().unwrap();
+    assert_eq!(flows[0].status.label(), "Finished");
 }
 
 #[tokio::test]
 async fn test_stop_dataflow_not_running() {
-    // TODO: Test error when stopping non-running dataflow
-    todo!("Implement test_stop_dataflow_not_running")
+    let mock = MockCoordinator::start().await;
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    let result = client.stop_dataflow(&Uuid::new_v4().to_string());
+    assert!(matches!(result, Err(ClientError::CoordinatorError(_))));
+}
+
+#[tokio::test]
+async fn test_destroy_dataflow_skips_grace_period() {
+    let mock = MockCoordinator::start().await;
+    let uuid = Uuid::new_v4();
+    mock.add_running_dataflow(uuid);
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    client.destroy_dataflow(&uuid.to_string()).unwrap();
+
+    let requests = mock.received_requests();
+    let stop = &requests.last().unwrap()["Stop"];
+    assert_eq!(stop["grace_duration"]["secs"], 0);
 }
 
 // ============================================================================
@@ -86,18 +267,73 @@ async fn test_stop_dataflow_not_running() {
 
 #[tokio::test]
 async fn test_log_subscription_receives_logs() {
-    // TODO: Test real-time log streaming
-    todo!("Implement test_log_subscription_receives_logs")
+    let mock = MockCoordinator::start().await;
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    let mut stream = client.subscribe_logs(&Uuid::new_v4().to_string()).unwrap();
+    wait_for_subscribers(&mock, 1);
+
+    mock.emit_log(log_entry_node("test-node", "Test message"));
+
+    let log = stream.next().unwrap();
+    assert_eq!(log.message, "Test message");
+    assert_eq!(log.node_id.as_deref(), Some("test-node"));
+    assert_eq!(log.level, "INFO");
 }
 
 #[tokio::test]
 async fn test_log_subscription_handles_disconnect() {
-    // TODO: Test graceful handling of disconnect during streaming
-    todo!("Implement test_log_subscription_handles_disconnect")
+    let mock = MockCoordinator::start().await;
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    let mut stream = client.subscribe_logs(&Uuid::new_v4().to_string()).unwrap();
+    wait_for_subscribers(&mock, 1);
+
+    mock.shutdown().await;
+
+    // Stream should end gracefully
+    assert!(stream.next().is_none());
 }
 
 #[tokio::test]
 async fn test_log_subscription_backpressure() {
-    // TODO: Test handling of high-volume log streams
-    todo!("Implement test_log_subscription_backpressure")
+    let mock = MockCoordinator::start().await;
+
+    let mut client = CoordinatorClient::connect(&mock.addr()).unwrap();
+    let stream = client.subscribe_logs(&Uuid::new_v4().to_string()).unwrap();
+    wait_for_subscribers(&mock, 1);
+
+    // A UI that never drains: only the newest lines are kept
+    let follower = LogFollower::spawn(100, || {}, move || FollowSource::from_subscription(stream));
+    for log in generate_sample_logs(1000) {
+        mock.emit_log(log);
+    }
+    for _ in 0..200 {
+        if follower.received() == 1000 {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(follower.received(), 1000);
+
+    let batch = follower.drain();
+    assert_eq!(batch.lines.len(), 100);
+    assert_eq!(batch.dropped, 900);
+    assert_eq!(batch.lines[0].text, "Sample log message 900");
+    assert_eq!(batch.lines[99].text, "Sample log message 999");
+    assert!(!batch.finished);
+
+    // Draining resets the counter; stopping ends the stream
+    mock.emit_log(log_entry_node("test-node", "after drain"));
+    follower.stop();
+    for _ in 0..200 {
+        if follower.is_finished() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let batch = follower.drain();
+    assert!(batch.finished);
+    assert_eq!(batch.dropped, 0);
+    assert!(batch.lines.len() <= 1);
 }
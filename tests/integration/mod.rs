@@ -5,6 +5,7 @@
 mod client_tests;
 mod storage_tests;
 mod app_tests;
+mod webhook_tests;
 
 // Re-export test utilities
 pub use super::fixtures::*;
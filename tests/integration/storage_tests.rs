@@ -1,79 +1,245 @@
-//! Integration tests for Storage (DataFusion + Parquet)
+//! Integration tests for the metrics store
 //!
-//! Tests storage persistence and querying.
+//! Tests storage persistence, retention and querying.
 
-use super::*;
+use dora_studio::dataflow::model::unix_now;
+use dora_studio::dataflow::{DataflowInfo, DataflowStatus};
+use dora_studio::metrics::series::{CPU, MEMORY};
+use dora_studio::metrics::{
+    dataflow_samples, downsample, MetricsStore, Retention, Sample, SampleQuery, TelemetryRequest, TimeWindow,
+};
+use std::time::Instant;
 use tempfile::TempDir;
 
+const KEEP_ALL: Retention = Retention {
+    max_age_secs: u64::MAX,
+    max_bytes: u64::MAX,
+};
+
+/// Start of an hour, so series from it fill whole segments
+const NOW: u64 = 1_768_989_600;
+
+fn sample(dataflow: &str, node: Option<&str>, metric: &str, timestamp: u64, value: f64) -> Sample {
+    Sample {
+        timestamp,
+        dataflow: dataflow.to_string(),
+        node: node.map(str::to_string),
+        metric: metric.to_string(),
+        value,
+    }
+}
+
+/// A CPU sample of dataflow `a` every 5 seconds for `count` samples from `NOW`
+fn cpu_series(count: u64) -> Vec<Sample> {
+    (0..count).map(|i| sample("a", None, CPU, NOW + i * 5, i as f64)).collect()
+}
+
 // ============================================================================
 // Initialization Tests
 // ============================================================================
 
-#[tokio::test]
-async fn test_storage_creates_directories() {
-    // TODO: Verify storage creates required subdirectories
-    // let temp_dir = TempDir::new().unwrap();
-    // let storage = Storage::new(temp_dir.path()).await.unwrap();
-    // assert!(temp_dir.path().join("metrics").exists());
-    // assert!(temp_dir.path().join("logs").exists());
-    // assert!(temp_dir.path().join("spans").exists());
-    todo!("Implement test_storage_creates_directories")
+#[test]
+fn test_storage_creates_directories() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("studio").join("metrics");
+    let store = MetricsStore::open(&dir, KEEP_ALL).unwrap();
+    assert!(dir.is_dir());
+    assert_eq!(store.dir(), dir);
+    assert!(store.segments().unwrap().is_empty());
 }
 
 // ============================================================================
 // Persistence Tests
 // ============================================================================
 
-#[tokio::test]
-async fn test_storage_persistence_across_restarts() {
-    // TODO: Test data survives storage restart
-    // let temp_dir = TempDir::new().unwrap();
-    // {
-    //     let storage = Storage::new(temp_dir.path()).await.unwrap();
-    //     storage.insert_metrics(&[test_metric()]).await.unwrap();
-    // }
-    // {
-    //     let storage = Storage::new(temp_dir.path()).await.unwrap();
-    //     let result = storage.query("SELECT COUNT(*) FROM metrics").await.unwrap();
-    //     assert!(result.num_rows() > 0);
-    // }
-    todo!("Implement test_storage_persistence_across_restarts")
+#[test]
+fn test_storage_persistence_across_restarts() {
+    let temp_dir = TempDir::new().unwrap();
+    let dataflows = vec![DataflowInfo {
+        uuid: "a".to_string(),
+        status: DataflowStatus::Running,
+        cpu: 42.0,
+        memory: 0.5,
+        ..Default::default()
+    }];
+    {
+        let mut store = MetricsStore::open(temp_dir.path(), KEEP_ALL).unwrap();
+        store.insert(&dataflow_samples(&dataflows, NOW)).unwrap();
+    }
+    {
+        let mut store = MetricsStore::open(temp_dir.path(), KEEP_ALL).unwrap();
+        store.insert(&dataflow_samples(&dataflows, NOW + 5)).unwrap();
+    }
+    let store = MetricsStore::open(temp_dir.path(), KEEP_ALL).unwrap();
+    let samples = store.query(&SampleQuery::default()).unwrap();
+    assert_eq!(samples.len(), 4);
+    assert_eq!(samples[0].timestamp, NOW);
+    assert_eq!(samples[3].timestamp, NOW + 5);
+}
+
+#[test]
+fn test_storage_retention_on_open() {
+    let temp_dir = TempDir::new().unwrap();
+    let now = unix_now();
+    {
+        let mut store = MetricsStore::open(temp_dir.path(), KEEP_ALL).unwrap();
+        store
+            .insert(&[
+                sample("a", None, CPU, now - 30 * 86400, 1.0),
+                sample("a", None, CPU, now - 86400, 2.0),
+                sample("a", None, CPU, now, 3.0),
+            ])
+            .unwrap();
+    }
+    let week = Retention {
+        max_age_secs: 7 * 86400,
+        max_bytes: u64::MAX,
+    };
+    let store = MetricsStore::open(temp_dir.path(), week).unwrap();
+    let values: Vec<f64> = store.query(&SampleQuery::default()).unwrap().iter().map(|s| s.value).collect();
+    assert_eq!(values, vec![2.0, 3.0]);
+    assert_eq!(store.segments().unwrap().len(), 2);
 }
 
 // ============================================================================
 // Query Tests
 // ============================================================================
 
-#[tokio::test]
-async fn test_storage_time_range_query() {
-    // TODO: Test filtering by time range
-    todo!("Implement test_storage_time_range_query")
+#[test]
+fn test_storage_time_range_query() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut store = MetricsStore::open(temp_dir.path(), KEEP_ALL).unwrap();
+    // Two hours of samples, in two segment files
+    store.insert(&cpu_series(1440)).unwrap();
+    assert_eq!(store.segments().unwrap().len(), 2);
+
+    let query = SampleQuery {
+        since: Some(NOW + 3595),
+        until: Some(NOW + 3610),
+        ..Default::default()
+    };
+    let samples = store.query(&query).unwrap();
+    let timestamps: Vec<u64> = samples.iter().map(|s| s.timestamp - NOW).collect();
+    assert_eq!(timestamps, vec![3595, 3600, 3605, 3610]);
+
+    let before = SampleQuery {
+        until: Some(NOW - 1),
+        ..Default::default()
+    };
+    assert!(store.query(&before).unwrap().is_empty());
 }
 
-#[tokio::test]
-async fn test_storage_aggregation_query() {
-    // TODO: Test SQL aggregations (AVG, SUM, COUNT)
-    todo!("Implement test_storage_aggregation_query")
+#[test]
+fn test_storage_aggregation_query() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut store = MetricsStore::open(temp_dir.path(), KEEP_ALL).unwrap();
+    // Values 0..24 over two minutes
+    store.insert(&cpu_series(24)).unwrap();
+
+    let samples = store.query(&SampleQuery::default()).unwrap();
+    let minutes = downsample(&samples, 60);
+    assert_eq!(minutes.len(), 2);
+    let (start, first) = minutes[0];
+    assert_eq!(start, NOW);
+    assert_eq!(first.count, 12);
+    assert_eq!(first.min, 0.0);
+    assert_eq!(first.max, 11.0);
+    assert_eq!(first.sum, 66.0);
+    assert_eq!(first.avg(), 5.5);
+    assert_eq!(minutes[1].1.avg(), 17.5);
 }
 
-#[tokio::test]
-async fn test_storage_join_query() {
-    // TODO: Test joining metrics with logs
-    todo!("Implement test_storage_join_query")
+#[test]
+fn test_storage_filter_query() {
+    // Series of several dataflows, nodes and metrics share the files
+    let temp_dir = TempDir::new().unwrap();
+    let mut store = MetricsStore::open(temp_dir.path(), KEEP_ALL).unwrap();
+    store
+        .insert(&[
+            sample("a", None, CPU, NOW, 10.0),
+            sample("a", None, MEMORY, NOW, 0.5),
+            sample("a", Some("camera"), CPU, NOW, 4.0),
+            sample("a", Some("detector"), CPU, NOW, 6.0),
+            sample("b", None, CPU, NOW, 80.0),
+        ])
+        .unwrap();
+
+    let dataflow_cpu = SampleQuery {
+        dataflow: Some("a".to_string()),
+        metric: Some(CPU.to_string()),
+        ..Default::default()
+    };
+    assert_eq!(store.query(&dataflow_cpu).unwrap().len(), 3);
+
+    let camera = SampleQuery {
+        node: Some("camera".to_string()),
+        ..dataflow_cpu.clone()
+    };
+    let samples = store.query(&camera).unwrap();
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].value, 4.0);
+
+    let memory = SampleQuery {
+        metric: Some(MEMORY.to_string()),
+        ..Default::default()
+    };
+    assert_eq!(store.query(&memory).unwrap()[0].value, 0.5);
+}
+
+#[test]
+fn test_storage_telemetry_comparison() {
+    // A day of 5 second samples of two dataflows, charted over the last hour
+    let temp_dir = TempDir::new().unwrap();
+    let mut store = MetricsStore::open(temp_dir.path(), KEEP_ALL).unwrap();
+    let mut samples = cpu_series(17_280);
+    samples.extend((0..17_280).map(|i| sample("b", None, CPU, NOW + i * 5, 50.0)));
+    store.insert(&samples).unwrap();
+
+    let end = NOW + 24 * 3600;
+    let request = TelemetryRequest {
+        dataflows: vec![("a".to_string(), "camera".to_string()), ("b".to_string(), "lidar".to_string())],
+        node: None,
+        window: TimeWindow::last(3600, end),
+        points: 60,
+    };
+    let data = store.load_telemetry(request).unwrap();
+    assert_eq!(data.cpu.len(), 2);
+    assert_eq!(data.cpu[1].label, "lidar");
+    // One point a minute, the last one partial
+    assert_eq!(data.cpu[0].points.len(), 60);
+    assert!(data.cpu[1].points.iter().all(|(_, value)| *value == 50.0));
+    assert!(data.cpu[0].points.iter().all(|(t, _)| (end - 3600..end).contains(t)));
+    assert!(data.memory.iter().all(|series| series.points.is_empty()));
 }
 
 // ============================================================================
 // Performance Tests
 // ============================================================================
 
-#[tokio::test]
-async fn test_storage_bulk_insert_performance() {
-    // TODO: Test inserting 100K records
-    todo!("Implement test_storage_bulk_insert_performance")
+#[test]
+fn test_storage_bulk_insert_performance() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut store = MetricsStore::open(temp_dir.path(), KEEP_ALL).unwrap();
+    let samples: Vec<Sample> = (0..100_000).map(|i| sample("a", None, CPU, NOW + i, 1.0)).collect();
+
+    let started = Instant::now();
+    store.insert(&samples).unwrap();
+    assert!(started.elapsed().as_secs() < 10, "insert took {:?}", started.elapsed());
+    assert_eq!(store.query(&SampleQuery::default()).unwrap().len(), 100_000);
 }
 
-#[tokio::test]
-async fn test_storage_query_performance() {
-    // TODO: Test query performance on large dataset
-    todo!("Implement test_storage_query_performance")
+#[test]
+fn test_storage_query_performance() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut store = MetricsStore::open(temp_dir.path(), KEEP_ALL).unwrap();
+    // A day of 5 second samples
+    store.insert(&cpu_series(17_280)).unwrap();
+
+    let started = Instant::now();
+    let last_hour = SampleQuery {
+        since: Some(NOW + 23 * 3600),
+        ..Default::default()
+    };
+    assert_eq!(store.query(&last_hour).unwrap().len(), 720);
+    assert!(started.elapsed().as_millis() < 1000, "query took {:?}", started.elapsed());
 }
@@ -0,0 +1,169 @@
+//! Integration tests for outbound webhooks
+//!
+//! Tests delivery, payloads and retries against a local HTTP stand-in.
+
+use dora_studio::alerts::webhook::{deliver, test_notification, Notification, Retry, Webhook, WebhookFormat};
+use dora_studio::alerts::{AlertEvent, AlertState};
+use std::time::Duration;
+use wiremock::matchers::{body_json, body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Retries without waiting, so failing tests stay fast
+const FAST_RETRY: Retry = Retry {
+    attempts: 3,
+    backoff: Duration::from_millis(10),
+};
+
+fn webhook(server: &MockServer, format: WebhookFormat, template: &str) -> Webhook {
+    Webhook {
+        url: format!("{}/hook", server.uri()),
+        format,
+        template: template.to_string(),
+    }
+}
+
+fn failed_alert() -> Notification {
+    Notification::Alert(AlertEvent {
+        timestamp: 1_768_989_600,
+        state: AlertState::Firing,
+        rule: "status becomes Failed".to_string(),
+        dataflow: "019be55a-8468-7d4b-9e95-db7938985bc7".to_string(),
+        dataflow_name: "camera".to_string(),
+        message: "camera: status Failed".to_string(),
+    })
+}
+
+// ============================================================================
+// Delivery Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_webhook_posts_generic_json() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .and(body_partial_json(serde_json::json!({
+            "event": "alert_firing",
+            "state": "FIRING",
+            "dataflow": "camera",
+            "dataflow_id": "019be55a-8468-7d4b-9e95-db7938985bc7",
+            "rule": "status becomes Failed",
+            "timestamp": 1_768_989_600,
+            "text": "[FIRING] camera: status Failed",
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let hook = webhook(&server, WebhookFormat::Json, "");
+    let attempts = deliver(&reqwest::Client::new(), &hook, &failed_alert(), FAST_RETRY).await;
+    assert_eq!(attempts, Ok(1));
+}
+
+#[tokio::test]
+async fn test_webhook_posts_slack_payload() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .and(body_json(serde_json::json!({ "text": ":red_circle: camera needs a look (FIRING)" })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let hook = webhook(&server, WebhookFormat::Slack, ":red_circle: {{dataflow}} needs a look ({{state}})");
+    let attempts = deliver(&reqwest::Client::new(), &hook, &failed_alert(), FAST_RETRY).await;
+    assert_eq!(attempts, Ok(1));
+}
+
+#[tokio::test]
+async fn test_webhook_test_notification() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({ "rule": "test" })))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let hook = webhook(&server, WebhookFormat::Json, "");
+    let result = deliver(&reqwest::Client::new(), &hook, &test_notification(), FAST_RETRY).await;
+    assert!(result.is_ok());
+}
+
+// ============================================================================
+// Retry Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_webhook_retries_server_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let hook = webhook(&server, WebhookFormat::Json, "");
+    let attempts = deliver(&reqwest::Client::new(), &hook, &failed_alert(), FAST_RETRY).await;
+    assert_eq!(attempts, Ok(3));
+}
+
+#[tokio::test]
+async fn test_webhook_gives_up_after_attempts() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("down"))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let hook = webhook(&server, WebhookFormat::Json, "");
+    let error = deliver(&reqwest::Client::new(), &hook, &failed_alert(), FAST_RETRY)
+        .await
+        .unwrap_err();
+    assert!(error.contains("500"), "{}", error);
+    assert!(error.ends_with("(gave up after 3 attempts)"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_webhook_does_not_retry_client_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("no such hook"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let hook = webhook(&server, WebhookFormat::Slack, "");
+    let error = deliver(&reqwest::Client::new(), &hook, &failed_alert(), FAST_RETRY)
+        .await
+        .unwrap_err();
+    assert!(error.contains("404") && error.contains("no such hook"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_webhook_unreachable() {
+    // Bind and drop a listener to get a port nobody listens on
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let hook = Webhook {
+        url: format!("http://{}/hook", addr),
+        format: WebhookFormat::Json,
+        template: String::new(),
+    };
+    let error = deliver(&reqwest::Client::new(), &hook, &failed_alert(), FAST_RETRY)
+        .await
+        .unwrap_err();
+    assert!(error.starts_with("Cannot reach"), "{}", error);
+    assert!(error.ends_with("(gave up after 3 attempts)"), "{}", error);
+}
@@ -3,6 +3,8 @@
 //! Provides mock versions of external dependencies for isolated testing.
 
 use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
 use std::sync::{Arc, Mutex};
 
 // ============================================================================
@@ -137,15 +139,54 @@ impl MockStorage {
 // ============================================================================
 
 /// Mock TCP server that simulates Dora Coordinator
+///
+/// Speaks the coordinator control protocol (little-endian `u64` length prefix
+/// followed by a JSON request) on a background thread.
 pub struct MockCoordinator {
     addr: std::net::SocketAddr,
-    // TODO: Add internal state and control methods
+    state: Arc<Mutex<MockCoordinatorState>>,
+}
+
+#[derive(Default)]
+struct MockCoordinatorState {
+    dataflows: Vec<DataflowEntry>,
+    start_result: Option<Result<Uuid, String>>,
+    next_response: Option<Vec<u8>>,
+    requests: Vec<serde_json::Value>,
+    connections: Vec<TcpStream>,
+    log_subscribers: Vec<TcpStream>,
+    daemon_connected: bool,
+    shutdown: bool,
 }
 
 impl MockCoordinator {
     pub async fn start() -> Self {
-        // TODO: Start mock TCP server
-        todo!("Implement MockCoordinator::start")
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock coordinator");
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(MockCoordinatorState {
+            daemon_connected: true,
+            ..Default::default()
+        }));
+
+        let accept_state = state.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let mut state = accept_state.lock().unwrap();
+                if state.shutdown {
+                    break;
+                }
+                if let Ok(clone) = stream.try_clone() {
+                    state.connections.push(clone);
+                }
+                drop(state);
+
+                let conn_state = accept_state.clone();
+                std::thread::spawn(move || serve_connection(stream, conn_state));
+            }
+        });
+
+        Self { addr, state }
     }
 
     pub fn addr(&self) -> String {
@@ -153,19 +194,176 @@ impl MockCoordinator {
     }
 
     pub async fn shutdown(&self) {
-        // TODO: Shutdown mock server
-        todo!("Implement MockCoordinator::shutdown")
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        state.shutdown = true;
+        for stream in state.connections.drain(..).chain(state.log_subscribers.drain(..)) {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        drop(guard);
+        // Wake up the accept loop so it notices the shutdown flag
+        let _ = TcpStream::connect(self.addr);
     }
 
-    pub fn set_response(&self, _response: &[u8]) {
-        // TODO: Set response for next request
-        todo!("Implement MockCoordinator::set_response")
+    /// Reply to the next request with these raw bytes instead of a real reply
+    pub fn set_response(&self, response: &[u8]) {
+        self.state.lock().unwrap().next_response = Some(response.to_vec());
+    }
+
+    pub fn set_dataflows(&self, flows: Vec<DataflowEntry>) {
+        self.state.lock().unwrap().dataflows = flows;
     }
 
-    pub fn emit_log(&self, _log: LogMessage) {
-        // TODO: Emit log to subscribers
-        todo!("Implement MockCoordinator::emit_log")
+    pub fn add_running_dataflow(&self, uuid: Uuid) {
+        self.state.lock().unwrap().dataflows.push(DataflowEntry {
+            uuid,
+            name: None,
+            status: DataflowStatus::Running,
+            node_count: 1,
+        });
+    }
+
+    pub fn set_daemon_connected(&self, connected: bool) {
+        self.state.lock().unwrap().daemon_connected = connected;
+    }
+
+    pub fn expect_start_returns(&self, uuid: Uuid) {
+        self.state.lock().unwrap().start_result = Some(Ok(uuid));
+    }
+
+    pub fn expect_start_fails(&self, error: &str) {
+        self.state.lock().unwrap().start_result = Some(Err(error.to_string()));
+    }
+
+    /// Requests received so far, decoded as JSON
+    pub fn received_requests(&self) -> Vec<serde_json::Value> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Number of connections currently subscribed to logs
+    pub fn log_subscriber_count(&self) -> usize {
+        self.state.lock().unwrap().log_subscribers.len()
+    }
+
+    /// Close every open control connection, as a coordinator restart would
+    pub fn disconnect_clients(&self) {
+        let mut state = self.state.lock().unwrap();
+        for stream in state.connections.drain(..) {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
     }
+
+    pub fn emit_log(&self, log: LogMessage) {
+        let level = match log.level {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        };
+        let body = serde_json::to_vec(&serde_json::json!({
+            "node_id": log.node_id,
+            "level": level,
+            "message": log.message,
+        }))
+        .unwrap();
+
+        let mut state = self.state.lock().unwrap();
+        state
+            .log_subscribers
+            .retain_mut(|stream| write_frame(stream, &body).is_ok());
+    }
+}
+
+fn serve_connection(mut stream: TcpStream, state: Arc<Mutex<MockCoordinatorState>>) {
+    while let Ok(raw) = read_frame(&mut stream) {
+        let request: serde_json::Value = match serde_json::from_slice(&raw) {
+            Ok(request) => request,
+            Err(_) => break,
+        };
+
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.clone());
+
+        if request.get("LogSubscribe").is_some() {
+            // The connection becomes a one-way log stream
+            if let Ok(clone) = stream.try_clone() {
+                state.log_subscribers.push(clone);
+            }
+            return;
+        }
+
+        let reply = match state.next_response.take() {
+            Some(raw) => raw,
+            None => serde_json::to_vec(&mock_reply(&mut state, &request)).unwrap(),
+        };
+        drop(state);
+
+        if write_frame(&mut stream, &reply).is_err() {
+            break;
+        }
+    }
+}
+
+fn mock_reply(state: &mut MockCoordinatorState, request: &serde_json::Value) -> serde_json::Value {
+    if request == "List" {
+        let entries: Vec<_> = state
+            .dataflows
+            .iter()
+            .map(|df| {
+                let status = match df.status {
+                    DataflowStatus::Running => "Running",
+                    DataflowStatus::Finished => "Finished",
+                    DataflowStatus::Failed(_) => "Failed",
+                };
+                serde_json::json!({
+                    "id": { "uuid": df.uuid.to_string(), "name": df.name },
+                    "status": status,
+                })
+            })
+            .collect();
+        return serde_json::json!({ "DataflowList": entries });
+    }
+    if request == "Destroy" {
+        return serde_json::json!("DestroyOk");
+    }
+    if request == "DaemonConnected" {
+        return serde_json::json!({ "DaemonConnected": state.daemon_connected });
+    }
+    if request.get("Start").is_some() {
+        return match state.start_result.take() {
+            Some(Err(error)) => serde_json::json!({ "Error": error }),
+            Some(Ok(uuid)) => serde_json::json!({ "DataflowStarted": { "uuid": uuid.to_string() } }),
+            None => serde_json::json!({ "DataflowStarted": { "uuid": Uuid::new_v4().to_string() } }),
+        };
+    }
+    if let Some(stop) = request.get("Stop") {
+        let uuid = stop["dataflow_uuid"].as_str().unwrap_or_default();
+        let running = state.dataflows.iter_mut().find(|df| {
+            df.uuid.to_string() == uuid && matches!(df.status, DataflowStatus::Running)
+        });
+        return match running {
+            Some(df) => {
+                df.status = DataflowStatus::Finished;
+                serde_json::json!({ "DataflowStopped": { "uuid": uuid, "result": {} } })
+            }
+            None => serde_json::json!({ "Error": format!("no running dataflow with ID `{}`", uuid) }),
+        };
+    }
+    serde_json::json!({ "Error": format!("unsupported request: {}", request) })
+}
+
+fn write_frame(stream: &mut TcpStream, data: &[u8]) -> std::io::Result<()> {
+    stream.write_all(&(data.len() as u64).to_le_bytes())?;
+    stream.write_all(data)
+}
+
+fn read_frame(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut len = [0u8; 8];
+    stream.read_exact(&mut len)?;
+    let mut data = vec![0u8; u64::from_le_bytes(len) as usize];
+    stream.read_exact(&mut data)?;
+    Ok(data)
 }
 
 // ============================================================================